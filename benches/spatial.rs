@@ -0,0 +1,15 @@
+//! Late-wave collision and targeting frame, brute force against `SpatialGrid`.
+//! `cargo bench --bench spatial [-- <enemies>]`, 2,000 enemies by default.
+
+use bevy_autoshooter_clone::systems::gameplay::benchmark::run_spatial_benchmark;
+
+const DEFAULT_ENEMIES: usize = 2_000;
+const FRAMES: u32 = 100;
+
+fn main() {
+    let enemies = std::env::args()
+        .skip(1)
+        .find_map(|arg| arg.parse().ok())
+        .unwrap_or(DEFAULT_ENEMIES);
+    println!("{}", run_spatial_benchmark(enemies, FRAMES));
+}
@@ -1,36 +1,44 @@
-mod systems;
+use bevy_autoshooter_clone::systems;
 
 use crate::systems::animations::plugin::SpriteAnimationPlugin;
-use crate::systems::game::{GameOverStats, GameState};
+use crate::systems::game::{GameRng, GameState, WaveState};
+use crate::systems::gameplay::headless::HeadlessSimulation;
+use crate::systems::gameplay::plugin::GameplayPlugin;
 use crate::systems::hud::resources::HUDTextureAtlas;
 use crate::systems::input::plugin::InputPlugin;
 use crate::systems::input::resources::{GamepadAsset, KeyboardAsset};
+use crate::systems::save::RunSavePlugin;
+use crate::systems::states::characterselect::plugin::CharacterSelectPlugin;
 use crate::systems::states::gamemenu::plugin::MainMenuPlugin;
-use crate::systems::states::upgrades::resources::{RedrawCardsPool, UpgradeCardsPool};
-use crate::systems::states::waves::enemy::messages::{EnemySpawnedMessage, EnemySpawningMessage};
-use crate::systems::states::waves::enemy::resources::EnemyAnimations;
-use crate::systems::states::waves::resources::TilesTextureAtlas;
-use crate::systems::states::waves::weapons::messages::{
-    BulletSpawnedMessage, WeaponSpawnedMessage,
-};
-use crate::systems::states::waves::weapons::resources::WeaponsLibrary;
-use crate::systems::states::{gameover, shopping, waves};
+use crate::systems::states::profile::plugin::ProfilePlugin;
+use crate::systems::states::settings::plugin::SettingsPlugin;
+use crate::systems::states::waves::resources::{ObstacleSprites, TilesTextureAtlas};
+use crate::systems::states::{gameover, pause, shopping, waves};
 use bevy::prelude::*;
 use bevy::window::WindowResolution;
 use bevy::winit::{UpdateMode, WinitSettings};
-use messages::EnemyDeathMessage;
 use states::upgrades;
 use std::time::Duration;
 use systems::constants::{WINDOW_HEIGHT, WINDOW_WIDTH};
 use systems::input::debug;
-use systems::states::waves::enemy::messages;
 use systems::states::waves::player::resources::PlayerAnimations;
-use systems::states::waves::resources::WaveManager;
-use systems::states::waves::{camera, collision, enemy, player, weapons};
+use systems::states::waves::{camera, enemy, weapons};
 use systems::*;
 use systems::{game, hud};
 
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let seed = arg_value(&args, "--seed").map(|s| s.parse().expect("--seed expects a u64"));
+    if let Some(seconds) = arg_value(&args, "--headless") {
+        run_headless(
+            seconds
+                .parse()
+                .expect("--headless expects a number of seconds"),
+            seed,
+        );
+        return;
+    }
+
     App::new()
         // ----------------------------- Plugins ---------------------------------- //
         .add_plugins(
@@ -61,81 +69,65 @@ fn main() {
         //         frame_time_graph_config: Default::default(),
         //     },
         // })
-        .add_plugins((SpriteAnimationPlugin, InputPlugin, MainMenuPlugin))
+        .add_plugins((
+            GameplayPlugin,
+            SpriteAnimationPlugin,
+            InputPlugin,
+            MainMenuPlugin,
+            CharacterSelectPlugin,
+            SettingsPlugin,
+            RunSavePlugin,
+            ProfilePlugin,
+        ))
         // ----------------------------- Resources ---------------------------------- //
-        .init_state::<GameState>()
         .insert_resource(WinitSettings {
             focused_mode: UpdateMode::reactive(Duration::from_secs_f32(1.0 / 60.0)),
             unfocused_mode: UpdateMode::reactive(Duration::from_secs_f32(1.0 / 60.0)),
         })
-        .insert_resource(game::MusicVolume(5))
-        .insert_resource(game::SoundEffectVolume(7))
-        .init_resource::<UpgradeCardsPool>()
-        .init_resource::<RedrawCardsPool>()
-        .init_resource::<WaveManager>()
+        .insert_resource(GameRng::new(seed))
         .init_resource::<TilesTextureAtlas>()
+        .init_resource::<ObstacleSprites>()
         .init_resource::<HUDTextureAtlas>()
         .init_resource::<GamepadAsset>()
         .init_resource::<KeyboardAsset>()
-        .init_resource::<WeaponsLibrary>()
-        .init_resource::<GameOverStats>()
         .init_resource::<PlayerAnimations>()
-        .init_resource::<EnemyAnimations>()
         // ------------------------------------------------------------------------- //
         .add_systems(
             PreUpdate,
             (
-                game::out_of_bounds_system,
-                game::despawn_marked_entities,
                 debug::handle_button_pressed,
                 hud::top::update_level_up_indicator,
                 hud::top::animate_hud_border,
             ),
         )
         .add_systems(Startup, game::spawn_camera)
+        .add_systems(
+            OnExit(GameState::Loading),
+            enemy::resources::build_enemy_animations,
+        )
         // ------------------------  In Wave state -------------------------------- //
         .add_systems(
             OnEnter(GameState::InWave),
             (
                 hud::top::spawn_hud,
                 waves::renderer::spawn_background,
-                game::spawn_player,
-                waves::systems::reset_wave_timers,
-                enemy::spawner::spawn_boss.after(game::spawn_player),
                 waves::systems::play_background_audio,
             ),
         )
-        .add_systems(
-            Update,
-            (
-                enemy::spawner::create_enemy_spawning,
-                enemy::spawner::spawn_enemies,
-                enemy::movement::move_to_player,
-                enemy::systems::check_if_dead,
-                enemy::shooter::update_enemy_shoot,
-                enemy::shooter::update_boss_shoot,
-                enemy::systems::handle_splitter_death,
-                waves::systems::update_wave_timer,
-                waves::systems::check_game_is_over,
-                player::movement::update_position,
-                player::experience::handle_enemy_death,
-                weapons::systems::update_weapon_positioning,
-                weapons::systems::add_weapon,
-                weapons::systems::auto_shoot,
-                weapons::systems::move_bullets,
-                weapons::systems::recalculate_weapon_area.after(weapons::systems::add_weapon),
-                collision::check_bullet_enemy_collision.after(weapons::systems::move_bullets),
-                collision::check_player_enemy_collision.after(weapons::systems::move_bullets),
-            )
-                .run_if(in_state(GameState::InWave)),
-        )
         .add_systems(
             PostUpdate,
             (
                 waves::renderer::animate_game_over,
                 waves::renderer::animate_player,
                 waves::renderer::animate_enemy,
+                waves::renderer::tint_enemies,
+                waves::renderer::render_obstacles,
                 weapons::renderer::render_bullet,
+                weapons::renderer::spawn_damage_numbers,
+                weapons::renderer::animate_damage_numbers,
+                weapons::renderer::spawn_chain_arcs,
+                weapons::renderer::animate_chain_arcs,
+                waves::renderer::render_experience_orbs,
                 weapons::renderer::render_weapon,
                 enemy::renderer::update_spawning,
                 enemy::renderer::handle_enemy_spawning,
@@ -144,10 +136,21 @@ fn main() {
             )
                 .run_if(in_state(GameState::InWave)),
         )
+        .add_systems(
+            PostUpdate,
+            (
+                enemy::renderer::spawn_elite_auras,
+                enemy::renderer::animate_elite_auras,
+                enemy::renderer::spawn_explosions,
+                enemy::renderer::animate_explosions,
+            )
+                .run_if(in_state(GameState::InWave)),
+        )
         .add_systems(
             PostUpdate,
             (
                 hud::top::update,
+                hud::top::update_dash_indicator,
                 hud::stats::toggle_stats_popup,
                 hud::stats::update_stats_popup,
                 camera::camera_follow_player,
@@ -156,6 +159,26 @@ fn main() {
             )
                 .run_if(in_state(GameState::InWave)),
         )
+        // ------------------------  Paused sub-state -------------------------------- //
+        .add_systems(
+            OnEnter(WaveState::Paused),
+            (
+                pause::renderer::spawn_pause_menu,
+                pause::systems::pause_background_music,
+            ),
+        )
+        .add_systems(
+            OnExit(WaveState::Paused),
+            pause::systems::resume_background_music,
+        )
+        .add_systems(
+            Update,
+            (
+                pause::systems::pause_on_focus_lost.run_if(in_state(WaveState::Running)),
+                pause::renderer::update_pause_button_interaction
+                    .run_if(in_state(WaveState::Paused)),
+            ),
+        )
         // ------------------------  UpgradeSelection state -------------------------------- //
         .add_systems(
             OnEnter(GameState::UpgradeSelection),
@@ -164,8 +187,6 @@ fn main() {
         .add_systems(
             Update,
             (
-                upgrades::systems::update_active_upgrade_card,
-                upgrades::systems::apply_active_upgrade_card,
                 upgrades::animations::animate_upgrade_cards,
                 upgrades::animations::animate_holding_bars,
                 upgrades::renderer::update_card_buttons,
@@ -182,8 +203,11 @@ fn main() {
         .add_systems(
             Update,
             (
-                shopping::systems::start_next_wave,
                 shopping::renderer::update_start_button_interaction,
+                shopping::renderer::update_shop_button_interaction,
+                shopping::renderer::redraw_shop_offers,
+                shopping::renderer::update_shop_texts,
+                weapons::renderer::render_weapon,
             )
                 .run_if(in_state(GameState::Shopping)),
         )
@@ -194,16 +218,21 @@ fn main() {
         )
         .add_systems(
             Update,
-            (
-                gameover::systems::handle_restart,
-                gameover::renderer::update_restart_button_interaction,
-            )
+            gameover::renderer::update_restart_button_interaction
                 .run_if(in_state(GameState::GameOver)),
         )
-        .add_message::<EnemyDeathMessage>()
-        .add_message::<EnemySpawningMessage>()
-        .add_message::<EnemySpawnedMessage>()
-        .add_message::<BulletSpawnedMessage>()
-        .add_message::<WeaponSpawnedMessage>()
         .run();
 }
+
+fn arg_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|index| args.get(index + 1))
+        .map(String::as_str)
+}
+
+/// `--headless <seconds>`: simulates one wave without a window and prints a summary.
+fn run_headless(seconds: f32, seed: Option<u64>) {
+    let report = HeadlessSimulation::new(seed).run_wave_for(seconds).report();
+    println!("{}", report);
+}
@@ -18,8 +18,10 @@ pub const WAVE_DURATION: f32 = 30.;
 pub const SPAWN_RATE: f32 = 0.80;
 pub const ENEMY_SPAWN_TIME_IN_S: f32 = 1.2;
 pub const ENEMY_BASE_XP: u32 = 2;
+pub const ENEMY_BASE_CURRENCY: u32 = 1;
 pub const NEXT_LEVEL_RATIO_PERCENT: u32 = 60;
 pub const NB_UPDATES_PER_LEVEL: usize = 4;
+pub const MAX_WEAPONS: usize = 6;
 
 pub const GAME_AREA: Rect = Rect {
     min: Vec2 {
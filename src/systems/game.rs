@@ -1,29 +1,144 @@
 use crate::systems::animations::animation::SpriteAnimation;
 use crate::systems::animations::animator::SpriteAnimator;
 use crate::systems::constants::{tiles_to_pixels, GAME_AREA};
+use crate::systems::states::profile::resources::Profile;
+use crate::systems::states::settings::resources::MAX_VOLUME;
+use crate::systems::states::shopping::resources::Shop;
 use crate::systems::states::waves::components::Action::IDLE;
 use crate::systems::states::waves::components::Direction::EAST;
+use crate::systems::states::waves::enemy::messages::EnemyDeathMessage;
+use crate::systems::states::waves::player::characters::{CharactersLibrary, SelectedCharacter};
 use crate::systems::states::waves::player::components::{Player, PlayerBundle};
+use crate::systems::states::waves::player::experience::PlayerExperience;
 use crate::systems::states::waves::player::resources::PlayerAnimations;
-use crate::systems::states::waves::weapons::components::WeaponBundle;
+use crate::systems::states::waves::resources::WaveManager;
+use crate::systems::states::waves::weapons::components::Pooled;
+use crate::systems::states::waves::weapons::kinds::WeaponKind;
 use crate::systems::states::waves::weapons::messages::WeaponSpawnedMessage;
 use crate::systems::states::waves::weapons::resources::WeaponsLibrary;
+use crate::systems::states::waves::weapons::systems::equip_weapon;
+use bevy::audio::Volume;
 use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::ops::{Deref, DerefMut};
 
 #[derive(States, Debug, Clone, PartialEq, Eq, Hash, Default)]
 pub enum GameState {
     #[default]
+    Loading,
     MainMenu,
+    CharacterSelect,
     InWave,
     UpgradeSelection,
     Shopping,
     GameOver,
 }
+
+/// Sub-state of `GameState::InWave`: the wave only advances while `Running`.
+/// Pausing keeps every wave entity alive, unlike leaving `InWave`.
+#[derive(SubStates, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[source(GameState = GameState::InWave)]
+pub enum WaveState {
+    #[default]
+    Running,
+    Paused,
+}
 #[derive(Resource, Default)]
 pub struct GameOverStats {
     pub wave_reached: u32,
     pub level_reached: u32,
     pub experience_total: u32,
+    pub enemies_killed: u32,
+}
+
+impl GameOverStats {
+    /// Fills in how far the run went, when the player dies or abandons it.
+    pub fn end_run(&mut self, wave: u32, xp: &PlayerExperience) {
+        self.wave_reached = wave;
+        self.level_reached = xp.level;
+        self.experience_total = xp.value;
+    }
+}
+
+/// Single source of randomness for a run. Gameplay draws come from the main stream (deref);
+/// cosmetic picks (tiles, music) use `presentation()` so that a headless run and a windowed
+/// run with the same seed and inputs stay in lockstep.
+#[derive(Resource)]
+pub struct GameRng {
+    /// Seed forced from the command line, reused by every run instead of a fresh one.
+    fixed_seed: Option<u64>,
+    seed: u64,
+    rng: StdRng,
+    presentation: StdRng,
+}
+
+impl GameRng {
+    pub fn new(fixed_seed: Option<u64>) -> Self {
+        let seed = fixed_seed.unwrap_or_else(rand::random);
+        Self {
+            fixed_seed,
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+            presentation: StdRng::seed_from_u64(!seed),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Re-seeds both streams for a new run.
+    pub fn start_run(&mut self) {
+        *self = Self::new(self.fixed_seed);
+    }
+
+    /// Continues a saved run: same seed, but streams derived from the wave so a resumed run
+    /// does not replay the rolls of its first waves.
+    pub fn resume_run(&mut self, seed: u64, wave: u32) {
+        let stream = seed ^ (wave as u64).rotate_left(32);
+        self.seed = seed;
+        self.rng = StdRng::seed_from_u64(stream);
+        self.presentation = StdRng::seed_from_u64(!stream);
+    }
+
+    pub fn presentation(&mut self) -> &mut StdRng {
+        &mut self.presentation
+    }
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl Deref for GameRng {
+    type Target = StdRng;
+
+    fn deref(&self) -> &Self::Target {
+        &self.rng
+    }
+}
+
+impl DerefMut for GameRng {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.rng
+    }
+}
+
+/// Scales both `MusicVolume` and `SoundEffectVolume`.
+#[derive(Resource, Debug, Component, PartialEq, Eq, Clone, Copy)]
+pub struct MasterVolume(pub u32);
+
+impl MasterVolume {
+    /// Volume to play a sound of the given channel (music or sound effects) at.
+    pub fn mix(&self, channel_volume: u32) -> Volume {
+        let max = MAX_VOLUME as f32;
+        Volume::Linear(self.0 as f32 / max * channel_volume as f32 / max)
+    }
 }
 
 #[derive(Resource, Debug, Component, PartialEq, Eq, Clone, Copy)]
@@ -32,12 +147,37 @@ pub struct MusicVolume(pub u32);
 #[derive(Resource, Debug, Component, PartialEq, Eq, Clone, Copy)]
 pub struct SoundEffectVolume(pub u32);
 
+/// Reads a RON config file, `path` is relative to the current working directory (where the
+/// game was launched from, not where the executable lives). The defaults are used when it is
+/// missing or invalid.
+pub fn load_config<T: DeserializeOwned + Default>(path: &str) -> T {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return T::default();
+    };
+    ron::from_str(&content).unwrap_or_else(|err| {
+        warn!("Ignoring invalid {}: {}", path, err);
+        T::default()
+    })
+}
+
+/// Writes a RON config file to `path`, relative to the current working directory like
+/// `load_config`.
+pub fn save_config<T: Serialize>(path: &str, config: &T) {
+    let result = ron::ser::to_string_pretty(config, ron::ser::PrettyConfig::default())
+        .map_err(|err| err.to_string())
+        .and_then(|content| std::fs::write(path, content).map_err(|err| err.to_string()));
+    if let Err(err) = result {
+        warn!("Could not save {}: {}", path, err);
+    }
+}
+
 #[derive(Component)]
 pub struct MarkedForDespawn;
 
+#[allow(clippy::type_complexity)]
 pub fn out_of_bounds_system(
     mut commands: Commands,
-    query: Query<(Entity, &GlobalTransform), Without<MarkedForDespawn>>,
+    query: Query<(Entity, &GlobalTransform), (Without<MarkedForDespawn>, Without<Pooled>)>,
 ) {
     const MARGIN: f32 = tiles_to_pixels(2.0);
 
@@ -49,7 +189,7 @@ pub fn out_of_bounds_system(
             || entity_pos.y < GAME_AREA.min.y - MARGIN
             || entity_pos.y > GAME_AREA.max.y + MARGIN
         {
-            commands.entity(entity).despawn();
+            commands.entity(entity).try_insert(MarkedForDespawn);
         }
     }
 }
@@ -63,6 +203,36 @@ pub fn despawn_marked_entities(
     }
 }
 
+/// Kills are counted as they happen, the rest of `GameOverStats` is read when the player dies.
+pub fn count_enemy_kills(
+    mut msg_reader: MessageReader<EnemyDeathMessage>,
+    mut game_over_stats: ResMut<GameOverStats>,
+) {
+    game_over_stats.enemies_killed += msg_reader.read().count() as u32;
+}
+
+pub fn start_new_run(mut rng: ResMut<GameRng>) {
+    rng.start_run();
+}
+
+/// Forgets everything about the current run: the player (and its weapons) outlives the waves,
+/// so it has to go explicitly when the run ends or is abandoned.
+pub fn reset_run(
+    mut commands: Commands,
+    mut wave_manager: ResMut<WaveManager>,
+    mut game_over_stats: ResMut<GameOverStats>,
+    mut shop: ResMut<Shop>,
+    player_query: Query<Entity, With<Player>>,
+) {
+    *wave_manager = WaveManager::default();
+    *game_over_stats = GameOverStats::default();
+    *shop = Shop::default();
+
+    for entity in &player_query {
+        commands.entity(entity).despawn();
+    }
+}
+
 pub fn spawn_camera(mut commands: Commands) {
     commands.spawn((Camera2d, Msaa::Sample4));
 }
@@ -87,45 +257,84 @@ impl TextBundle {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn spawn_player(
     mut commands: Commands,
     player_query: Option<Single<Entity, With<Player>>>,
     player_animations: Res<PlayerAnimations>,
     animations: Res<Assets<SpriteAnimation>>,
     weapons_resource: Res<WeaponsLibrary>,
+    characters: Res<CharactersLibrary>,
+    selected_character: Res<SelectedCharacter>,
+    profile: Res<Profile>,
     mut events: MessageWriter<WeaponSpawnedMessage>,
 ) {
-    if player_query.is_none() {
-        let player_anim = player_animations.get(IDLE, EAST).unwrap();
-        let sprite = animations.get(player_anim.id()).unwrap().to_sprite();
-
-        let player = commands
-            .spawn((
-                PlayerBundle::default(),
-                sprite,
-                SpriteAnimator::new(player_anim),
-                Transform::from_translation(Vec3::ZERO).with_scale(Vec3::splat(2.0)),
-                children![(
-                    Sprite::from_image(player_animations.shadow_texture.clone()),
-                    Transform::from_xyz(0.0, -2.0, -1.0),
-                )],
-            ))
-            .id();
-
-        // Spawn with 1  weapon
-        let weapon = weapons_resource.weapons.get(0).unwrap();
-        let weapon_bundle = WeaponBundle::new(
-            format!("{:?}-{}", weapon.kind, 0),
-            weapon.clone(),
-            weapon.base_cooldown,
-        );
-        let weapon_entity = commands.spawn(weapon_bundle.clone()).id();
-        commands.entity(player).add_child(weapon_entity);
-        events.write(WeaponSpawnedMessage {
-            name: weapon_bundle.name,
-            weapon: weapon_bundle.weapon,
-            entity: weapon_entity,
+    if player_query.is_some() {
+        return;
+    }
+    let character = characters.get_or_default(&selected_character.0);
+    let bundle = character.player_bundle();
+    let stats = bundle.stats.clone();
+    let player = spawn_player_entity(&mut commands, &player_animations, &animations, bundle);
+
+    // Characters without a loadout start with the weapon picked in the profile
+    let weapons: Vec<WeaponKind> = if character.starting_weapons.is_empty() {
+        weapons_resource
+            .find(&profile.starting_weapon)
+            .or_else(|| weapons_resource.starting_weapon())
+            .into_iter()
+            .collect()
+    } else {
+        character
+            .starting_weapons
+            .iter()
+            .filter_map(|id| {
+                let kind = weapons_resource.find(id);
+                if kind.is_none() {
+                    warn!(
+                        "Character '{}' starts with unknown weapon '{}'",
+                        character.id, id
+                    );
+                }
+                kind
+            })
+            .collect()
+    };
+    for (index, kind) in weapons.into_iter().enumerate() {
+        events.write(equip_weapon(
+            &mut commands,
+            &weapons_resource,
+            kind,
             player,
-        });
+            &stats,
+            index,
+        ));
     }
 }
+
+/// Spawns the player sprite and shadow, without any weapon.
+pub fn spawn_player_entity(
+    commands: &mut Commands,
+    player_animations: &PlayerAnimations,
+    animations: &Assets<SpriteAnimation>,
+    bundle: PlayerBundle,
+) -> Entity {
+    let player_anim = player_animations.get(IDLE, EAST).unwrap();
+    let sprite = Sprite {
+        color: player_animations.tint,
+        ..animations.get(player_anim.id()).unwrap().to_sprite()
+    };
+
+    commands
+        .spawn((
+            bundle,
+            sprite,
+            SpriteAnimator::new(player_anim),
+            Transform::from_translation(Vec3::ZERO).with_scale(Vec3::splat(2.0)),
+            children![(
+                Sprite::from_image(player_animations.shadow_texture.clone()),
+                Transform::from_xyz(0.0, -2.0, -1.0),
+            )],
+        ))
+        .id()
+}
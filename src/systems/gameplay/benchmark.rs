@@ -0,0 +1,127 @@
+use crate::systems::constants::{tiles_to_pixels, GAME_AREA};
+use crate::systems::states::waves::spatial::SpatialGrid;
+use crate::systems::states::waves::weapons::utils;
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{RngExt, SeedableRng};
+use std::fmt;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const BULLETS: usize = 300;
+const WEAPONS: usize = 6;
+const ENEMY_RADIUS: f32 = 15.0;
+const WEAPON_RANGE: f32 = tiles_to_pixels(10.0);
+
+#[derive(Clone)]
+pub struct SpatialBenchmarkReport {
+    pub enemies: usize,
+    pub bullets: usize,
+    pub weapons: usize,
+    pub frames: u32,
+    pub brute_force_per_frame: Duration,
+    pub grid_per_frame: Duration,
+    pub speedup: f32,
+}
+
+impl fmt::Display for SpatialBenchmarkReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} enemies, {} bullets, {} weapons, {} frames",
+            self.enemies, self.bullets, self.weapons, self.frames
+        )?;
+        writeln!(f, "brute force: {:?}/frame", self.brute_force_per_frame)?;
+        writeln!(f, "grid:        {:?}/frame", self.grid_per_frame)?;
+        write!(f, "speedup:     x{:.1}", self.speedup)
+    }
+}
+
+/// Times one late-wave frame of collision and targeting work, first with the old
+/// "every bullet against every enemy" loops, then with a `SpatialGrid` rebuilt each frame.
+/// Both passes must agree on the number of hits, otherwise the grid is wrong.
+pub fn run_spatial_benchmark(enemies: usize, frames: u32) -> SpatialBenchmarkReport {
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut random_point = || {
+        Vec2::new(
+            rng.random_range(GAME_AREA.min.x..GAME_AREA.max.x),
+            rng.random_range(GAME_AREA.min.y..GAME_AREA.max.y),
+        )
+    };
+    let enemy_positions: Vec<(Entity, Vec2)> = (0..enemies)
+        .map(|i| (Entity::from_raw_u32(i as u32 + 1).unwrap(), random_point()))
+        .collect();
+    let bullets: Vec<Vec2> = (0..BULLETS).map(|_| random_point()).collect();
+    let weapons: Vec<Vec2> = (0..WEAPONS).map(|_| random_point()).collect();
+
+    // Brute force, as done before the grid existed
+    let enemy_transforms: Vec<GlobalTransform> = enemy_positions
+        .iter()
+        .map(|(_, pos)| GlobalTransform::from_translation(pos.extend(0.0)))
+        .collect();
+    let mut brute_force_hits = 0;
+    let start = Instant::now();
+    for _ in 0..frames {
+        for bullet in &bullets {
+            brute_force_hits += enemy_positions
+                .iter()
+                .filter(|(_, pos)| pos.distance_squared(*bullet) < ENEMY_RADIUS * ENEMY_RADIUS)
+                .count();
+        }
+        for weapon in &weapons {
+            let weapon_transform = GlobalTransform::from_translation(weapon.extend(0.0));
+            black_box(utils::get_nearest_enemy(
+                &weapon_transform,
+                enemy_transforms.iter().collect(),
+                WEAPON_RANGE,
+            ));
+        }
+    }
+    let brute_force = start.elapsed();
+
+    let mut grid = SpatialGrid::default();
+    let mut grid_hits = 0;
+    let start = Instant::now();
+    for _ in 0..frames {
+        grid.clear();
+        for &(entity, pos) in &enemy_positions {
+            grid.insert(entity, pos);
+        }
+        for bullet in &bullets {
+            grid_hits += grid
+                .query_radius(*bullet, ENEMY_RADIUS)
+                .filter(|(_, pos)| pos.distance_squared(*bullet) < ENEMY_RADIUS * ENEMY_RADIUS)
+                .count();
+        }
+        for weapon in &weapons {
+            black_box(grid.nearest(*weapon, WEAPON_RANGE));
+        }
+    }
+    let grid_time = start.elapsed();
+    assert_eq!(
+        brute_force_hits, grid_hits,
+        "spatial grid disagrees with brute force"
+    );
+
+    SpatialBenchmarkReport {
+        enemies,
+        bullets: BULLETS,
+        weapons: WEAPONS,
+        frames,
+        brute_force_per_frame: brute_force / frames.max(1),
+        grid_per_frame: grid_time / frames.max(1),
+        speedup: brute_force.as_secs_f32() / grid_time.as_secs_f32().max(f32::EPSILON),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grid_agrees_with_brute_force() {
+        // Panics when the hit counts differ
+        let report = run_spatial_benchmark(500, 2);
+        assert_eq!(report.enemies, 500);
+    }
+}
@@ -0,0 +1,182 @@
+use crate::systems::animations::animation::SpriteAnimation;
+use crate::systems::animations::plugin::SpriteAnimationPlugin;
+use crate::systems::game::{GameOverStats, GameRng, GameState};
+use crate::systems::gameplay::plugin::GameplayPlugin;
+use crate::systems::input::resources::ActionState;
+use crate::systems::states::waves::components::Health;
+use crate::systems::states::waves::enemy::components::Enemy;
+use crate::systems::states::waves::enemy::resources::{
+    build_placeholder_enemy_animations, EnemyRegistry,
+};
+use crate::systems::states::waves::player::components::Player;
+use crate::systems::states::waves::player::currency::Currency;
+use crate::systems::states::waves::player::experience::PlayerExperience;
+use crate::systems::states::waves::player::resources::PlayerAnimations;
+use crate::systems::states::waves::resources::{TilesTextureAtlas, WaveManager};
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+use std::fmt;
+use std::time::Duration;
+
+/// Fixed simulation step used by headless runs (one 60 FPS frame).
+pub const HEADLESS_TIMESTEP: f32 = 1.0 / 60.0;
+
+/// Upper bound on the frames spent waiting for `GameState::Loading` to finish.
+const MAX_LOADING_FRAMES: u32 = 5_000;
+
+/// Runs `GameplayPlugin` on top of `MinimalPlugins`: no window, no GPU, no audio.
+/// Time advances by `HEADLESS_TIMESTEP` on every `App::update`, regardless of wall clock.
+pub struct HeadlessPlugin;
+
+impl Plugin for HeadlessPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            AssetPlugin::default(),
+            TransformPlugin,
+            SpriteAnimationPlugin,
+            GameplayPlugin,
+        ))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+            HEADLESS_TIMESTEP,
+        )))
+        // Enemy projectiles are meshes, their assets must exist even though nothing renders them
+        .init_asset::<Mesh>()
+        .init_asset::<ColorMaterial>()
+        .insert_resource(TilesTextureAtlas::placeholder())
+        .init_resource::<ActionState>()
+        .add_systems(
+            OnExit(GameState::Loading),
+            build_placeholder_enemy_animations,
+        );
+
+        let mut animations = app.world_mut().resource_mut::<Assets<SpriteAnimation>>();
+        let player_animations = PlayerAnimations::placeholder(&mut animations);
+        app.insert_resource(player_animations);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct HeadlessReport {
+    pub seed: u64,
+    pub simulated_secs: f32,
+    pub final_state: GameState,
+    pub wave: u32,
+    /// Seconds elapsed on the wave timer
+    pub wave_elapsed: f32,
+    pub enemies_alive: u32,
+    pub enemies_killed: u32,
+    pub level: u32,
+    pub experience: u32,
+    pub currency: u32,
+    pub player_health: f32,
+}
+
+impl fmt::Display for HeadlessReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "seed:           {}", self.seed)?;
+        writeln!(f, "simulated:      {:.1}s", self.simulated_secs)?;
+        writeln!(f, "final state:    {:?}", self.final_state)?;
+        writeln!(f, "wave:           {}", self.wave)?;
+        writeln!(f, "wave elapsed:   {:.1}s", self.wave_elapsed)?;
+        writeln!(f, "enemies alive:  {}", self.enemies_alive)?;
+        writeln!(f, "enemies killed: {}", self.enemies_killed)?;
+        writeln!(f, "level:          {} ({} xp)", self.level, self.experience)?;
+        writeln!(f, "currency:       {}", self.currency)?;
+        write!(f, "player health:  {:.1}", self.player_health)
+    }
+}
+
+/// A headless app already switched to `GameState::InWave`, ticked by hand.
+pub struct HeadlessSimulation {
+    pub app: App,
+    simulated_secs: f32,
+}
+
+impl Default for HeadlessSimulation {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl HeadlessSimulation {
+    /// `seed` pins `GameRng` so the same seed always replays the same wave.
+    pub fn new(seed: Option<u64>) -> Self {
+        let mut app = App::new();
+        app.add_plugins(HeadlessPlugin)
+            .insert_resource(GameRng::new(seed));
+        // Enemy definitions are read from disk on the IO task pool, wait for them.
+        for _ in 0..MAX_LOADING_FRAMES {
+            app.update();
+            if *app.world().resource::<State<GameState>>().get() != GameState::Loading {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert!(
+            app.world().contains_resource::<EnemyRegistry>(),
+            "enemy definitions failed to load from assets/enemies"
+        );
+        app.world_mut()
+            .resource_mut::<NextState<GameState>>()
+            .set(GameState::InWave);
+        // Apply the transition so OnEnter(InWave) spawns the player and weapon.
+        app.update();
+        Self {
+            app,
+            simulated_secs: 0.0,
+        }
+    }
+
+    pub fn state(&self) -> GameState {
+        self.app
+            .world()
+            .resource::<State<GameState>>()
+            .get()
+            .clone()
+    }
+
+    /// Advances the simulation by up to `seconds`, stopping early once the game
+    /// leaves `GameState::InWave` (wave cleared or player dead).
+    pub fn run_wave_for(&mut self, seconds: f32) -> &mut Self {
+        let steps = (seconds / HEADLESS_TIMESTEP).ceil() as u32;
+        for _ in 0..steps {
+            if self.state() != GameState::InWave {
+                break;
+            }
+            self.app.update();
+            self.simulated_secs += HEADLESS_TIMESTEP;
+        }
+        self
+    }
+
+    pub fn report(&mut self) -> HeadlessReport {
+        let world = self.app.world_mut();
+        let (level, experience, currency, player_health) = world
+            .query_filtered::<(&PlayerExperience, &Currency, &Health), With<Player>>()
+            .single(world)
+            .map(|(xp, currency, health)| (xp.level, xp.value, currency.0, health.value))
+            .unwrap_or_default();
+        let enemies_alive = world
+            .query_filtered::<(), With<Enemy>>()
+            .iter(world)
+            .count() as u32;
+        let wave_manager = world.resource::<WaveManager>();
+
+        HeadlessReport {
+            seed: world.resource::<GameRng>().seed(),
+            simulated_secs: self.simulated_secs,
+            final_state: world.resource::<State<GameState>>().get().clone(),
+            wave: wave_manager.wave,
+            wave_elapsed: wave_manager.wave_timer.elapsed_secs(),
+            enemies_alive,
+            enemies_killed: world.resource::<GameOverStats>().enemies_killed,
+            level,
+            experience,
+            currency,
+            player_health,
+        }
+    }
+}
@@ -0,0 +1,3 @@
+pub mod benchmark;
+pub mod headless;
+pub mod plugin;
@@ -0,0 +1,234 @@
+use crate::systems::game;
+use crate::systems::game::{GameOverStats, GameRng, GameState, WaveState};
+use crate::systems::save::ResumeRun;
+use crate::systems::states::loading::plugin::LoadingPlugin;
+use crate::systems::states::profile::resources::Profile;
+use crate::systems::states::shopping::resources::{PassiveItemsPool, Shop};
+use crate::systems::states::upgrades::resources::{RedrawCardsPool, UpgradeCardsPool};
+use crate::systems::states::waves::enemy::messages::{
+    EnemyDeathMessage, EnemySpawnedMessage, EnemySpawningMessage, ExplosionMessage,
+};
+use crate::systems::states::waves::navigation::FlowField;
+use crate::systems::states::waves::obstacles::ObstacleMap;
+use crate::systems::states::waves::player::characters::SelectedCharacter;
+use crate::systems::states::waves::resources::WaveManager;
+use crate::systems::states::waves::spatial::{rebuild_spatial_grid, SpatialGrid};
+use crate::systems::states::waves::weapons::messages::{
+    BulletSpawnedMessage, ChainLightningMessage, DamageDealtMessage, WeaponSpawnedMessage,
+};
+use crate::systems::states::waves::weapons::resources::{AimMode, BulletPool, ProjectileAssets};
+use crate::systems::states::waves::{
+    collision, enemy, navigation, obstacles, player, status, weapons,
+};
+use crate::systems::states::{gameover, pause, shopping, upgrades, waves};
+use bevy::prelude::*;
+
+/// Simulation side of the game: state machine, spawning, movement, shooting, collisions and
+/// wave progression. Nothing in here touches a window, the GPU, audio or the UI tree.
+///
+/// Expects `PlayerAnimations` and `ActionState` to be provided by the host app, either the
+/// asset-backed versions (windowed) or the stand-ins from `HeadlessPlugin`.
+pub struct GameplayPlugin;
+
+/// Systems drawing from the main `GameRng` stream. They run in this order, one after the other,
+/// so a seed always hands out its rolls the same way and a run replays identically.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RandomRolls {
+    EnemySpawning,
+    EnemySpawns,
+    WaveEvents,
+    Weapons,
+    BulletHits,
+}
+
+impl Plugin for GameplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<GameState>()
+            .add_sub_state::<WaveState>()
+            .add_plugins(LoadingPlugin)
+            .init_resource::<UpgradeCardsPool>()
+            .init_resource::<RedrawCardsPool>()
+            .init_resource::<WaveManager>()
+            .init_resource::<GameOverStats>()
+            .init_resource::<GameRng>()
+            .init_resource::<Profile>()
+            .init_resource::<SelectedCharacter>()
+            .init_resource::<SpatialGrid>()
+            .init_resource::<ObstacleMap>()
+            .init_resource::<FlowField>()
+            .init_resource::<ProjectileAssets>()
+            .init_resource::<BulletPool>()
+            .init_resource::<AimMode>()
+            .init_resource::<Shop>()
+            .init_resource::<PassiveItemsPool>()
+            .add_message::<EnemyDeathMessage>()
+            .add_message::<EnemySpawningMessage>()
+            .add_message::<EnemySpawnedMessage>()
+            .add_message::<ExplosionMessage>()
+            .add_message::<BulletSpawnedMessage>()
+            .add_message::<WeaponSpawnedMessage>()
+            .add_message::<DamageDealtMessage>()
+            .add_message::<ChainLightningMessage>()
+            .configure_sets(
+                Update,
+                (
+                    RandomRolls::EnemySpawning,
+                    RandomRolls::EnemySpawns,
+                    RandomRolls::WaveEvents,
+                    RandomRolls::Weapons,
+                    RandomRolls::BulletHits,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                PreUpdate,
+                (
+                    game::out_of_bounds_system,
+                    weapons::systems::recycle_bullets,
+                    game::despawn_marked_entities,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                PreUpdate,
+                rebuild_spatial_grid
+                    .after(game::despawn_marked_entities)
+                    .run_if(in_state(WaveState::Running)),
+            )
+            // A run starts when leaving the main menu or restarting from the game-over screen
+            .add_systems(OnExit(GameState::MainMenu), game::start_new_run)
+            .add_systems(
+                OnExit(GameState::GameOver),
+                (game::start_new_run, game::reset_run),
+            )
+            // Abandoning a run from the pause menu leads back to the main menu
+            .add_systems(OnEnter(GameState::MainMenu), game::reset_run)
+            // ------------------------  In Wave state -------------------------------- //
+            .add_systems(
+                OnEnter(GameState::InWave),
+                (
+                    game::spawn_player,
+                    waves::systems::reset_wave_timers,
+                    obstacles::populate_obstacles.after(game::spawn_player),
+                ),
+            )
+            .add_systems(
+                OnExit(GameState::InWave),
+                weapons::systems::clear_bullet_pool,
+            )
+            .add_systems(
+                Update,
+                (
+                    enemy::spawner::create_enemy_spawning.in_set(RandomRolls::EnemySpawning),
+                    enemy::spawner::spawn_enemies.in_set(RandomRolls::EnemySpawns),
+                    (
+                        enemy::movement::move_to_player,
+                        enemy::movement::separate_enemies,
+                    )
+                        .chain(),
+                    status::update_status_effects,
+                    enemy::systems::check_if_dead,
+                    enemy::shooter::update_enemy_shoot,
+                    enemy::shooter::update_boss_shoot,
+                    enemy::systems::handle_splitter_death,
+                    waves::systems::update_wave_timer,
+                    waves::systems::check_game_is_over,
+                    game::count_enemy_kills,
+                    (
+                        player::movement::update_position,
+                        // Orbs dropped on the last frame of the wave are spawned before the
+                        // leftovers are collected, or leaving the wave would despawn them
+                        player::experience::handle_enemy_death
+                            .after(enemy::systems::check_if_dead)
+                            .before(player::experience::collect_remaining_orbs),
+                        player::experience::attract_experience_orbs,
+                        player::experience::collect_remaining_orbs
+                            .after(waves::systems::update_wave_timer),
+                        player::currency::earn_currency,
+                        player::characters::heal_on_kill,
+                    ),
+                    weapons::systems::update_weapon_positioning,
+                    weapons::systems::add_weapon.in_set(RandomRolls::Weapons),
+                    weapons::systems::auto_shoot,
+                    weapons::systems::move_bullets,
+                    weapons::systems::recalculate_weapon_area.after(weapons::systems::add_weapon),
+                    collision::check_bullet_enemy_collision
+                        .in_set(RandomRolls::BulletHits)
+                        .after(weapons::systems::move_bullets),
+                    collision::check_player_enemy_collision.after(weapons::systems::move_bullets),
+                )
+                    .run_if(in_state(WaveState::Running)),
+            )
+            .add_systems(
+                Update,
+                (
+                    enemy::spawner::run_wave_events
+                        .in_set(RandomRolls::WaveEvents)
+                        .after(waves::systems::update_wave_timer),
+                    waves::systems::grant_wave_rewards.after(waves::systems::update_wave_timer),
+                    navigation::update_flow_field.before(enemy::movement::move_to_player),
+                    obstacles::collide_with_obstacles
+                        .after(enemy::movement::separate_enemies)
+                        .after(player::movement::update_position),
+                    obstacles::hide_in_bushes.after(obstacles::collide_with_obstacles),
+                    obstacles::block_bullets
+                        .after(weapons::systems::move_bullets)
+                        .before(collision::check_bullet_enemy_collision)
+                        .before(collision::check_player_enemy_collision),
+                )
+                    .run_if(in_state(WaveState::Running)),
+            )
+            // Elite modifiers
+            .add_systems(
+                Update,
+                (
+                    enemy::elites::regenerate,
+                    enemy::elites::drain_life.after(collision::check_player_enemy_collision),
+                    enemy::elites::explode_on_death.after(enemy::systems::check_if_dead),
+                )
+                    .run_if(in_state(WaveState::Running)),
+            )
+            // ------------------------  Paused sub-state -------------------------------- //
+            .add_systems(
+                Update,
+                pause::systems::toggle_pause.run_if(in_state(GameState::InWave)),
+            )
+            .add_systems(OnEnter(WaveState::Paused), pause::systems::pause_time)
+            .add_systems(OnExit(WaveState::Paused), pause::systems::resume_time)
+            .add_systems(
+                Update,
+                pause::systems::handle_pause_menu.run_if(in_state(WaveState::Paused)),
+            )
+            // ------------------------  UpgradeSelection state -------------------------------- //
+            .add_systems(
+                Update,
+                (
+                    upgrades::systems::update_active_upgrade_card,
+                    upgrades::systems::apply_active_upgrade_card,
+                )
+                    .run_if(in_state(GameState::UpgradeSelection)),
+            )
+            // ------------------------  Shopping state -------------------------------- //
+            // A resumed run enters the shop it was saved with
+            .add_systems(
+                OnEnter(GameState::Shopping),
+                shopping::systems::restock_shop.run_if(not(resource_exists::<ResumeRun>)),
+            )
+            .add_systems(
+                Update,
+                (
+                    shopping::systems::handle_shop_actions,
+                    shopping::systems::start_next_wave,
+                    // Weapons bought in the shop take their place around the player right away
+                    weapons::systems::recalculate_weapon_area
+                        .after(shopping::systems::handle_shop_actions),
+                )
+                    .run_if(in_state(GameState::Shopping)),
+            )
+            // ------------------------  GameOver state --------------------------------- //
+            .add_systems(
+                Update,
+                gameover::systems::handle_restart.run_if(in_state(GameState::GameOver)),
+            );
+    }
+}
@@ -27,6 +27,8 @@ pub const FILL_HEALTH_DANGER: Color = Color::srgb(0.85, 0.20, 0.20); // red
 pub const FILL_TIMER: Color = Color::srgb(0.53, 0.81, 0.98); // sky-blue
 pub const FILL_TIMER_URGENT: Color = Color::srgb(0.85, 0.65, 0.13); // goldenrod  (< 5 s)
 pub const FILL_XP: Color = Color::srgb(0.80, 0.60, 1.00); // violet
+pub const FILL_DASH: Color = Color::srgb(0.40, 1.00, 0.80); // aquamarine (ready)
+pub const FILL_DASH_CHARGING: Color = Color::srgb(0.35, 0.45, 0.50); // slate      (cooldown)
 
 // ── Stats-popup display kinds ─────────────────────────────────────────────────────────────
 #[derive(Component)]
@@ -62,6 +64,13 @@ impl DisplayStatKind {
                 StatKind::Range => (ICON_RANGE, "Range", Color::srgb(0.4, 0.8, 1.0)),
                 StatKind::MaxHealth => (ICON_HEALTH, "Health", Color::srgb(0.2, 1.0, 0.3)),
                 StatKind::Speed => (ICON_SPEED, "Speed", Color::srgb(0.4, 1.0, 0.8)),
+                StatKind::CritChance => (ICON_DAMAGE, "Crit Chance", Color::srgb(1.0, 0.85, 0.2)),
+                StatKind::CritMultiplier => {
+                    (ICON_LEVEL_UP, "Crit Damage", Color::srgb(1.0, 0.55, 0.1))
+                }
+                StatKind::Pierce => (ICON_RANGE, "Pierce", Color::srgb(0.85, 0.85, 0.95)),
+                StatKind::Bounce => (ICON_SPEED, "Bounce", Color::srgb(0.6, 1.0, 0.6)),
+                StatKind::Chain => (ICON_FIRE_RATE, "Chain", Color::srgb(0.55, 0.7, 1.0)),
             },
         }
     }
@@ -101,6 +110,10 @@ pub struct HUDLevelText;
 #[derive(Component)]
 pub struct HUDXPFill;
 
+/// The fill node inside the dash cooldown track (refills while on cooldown).
+#[derive(Component)]
+pub struct HUDDashFill;
+
 /// The thin bottom border of the top bar — driven by the palette animation.
 #[derive(Component)]
 pub struct HUDBottomBorder;
@@ -12,8 +12,8 @@ use bevy::image::TextureAtlas;
 use bevy::prelude;
 use bevy::prelude::{
     default, AlignItems, BackgroundColor, BorderColor, BorderRadius, Bundle, Commands,
-    DespawnOnExit, Entity, FlexDirection, ImageNode, JustifyContent, Node, PositionType, Query, Res
-    , Text, UiRect, Val, With,
+    DespawnOnExit, Entity, FlexDirection, ImageNode, JustifyContent, Node, PositionType, Query,
+    Res, Text, UiRect, Val, With,
 };
 
 pub fn toggle_stats_popup(
@@ -61,6 +61,14 @@ pub fn toggle_stats_popup(
             stat_row(DisplayStatKind::PlayerStat(StatKind::FireRate), &sprites),
             stat_row(DisplayStatKind::PlayerStat(StatKind::Range), &sprites),
             stat_row(DisplayStatKind::PlayerStat(StatKind::Speed), &sprites),
+            stat_row(DisplayStatKind::PlayerStat(StatKind::CritChance), &sprites),
+            stat_row(
+                DisplayStatKind::PlayerStat(StatKind::CritMultiplier),
+                &sprites
+            ),
+            stat_row(DisplayStatKind::PlayerStat(StatKind::Pierce), &sprites),
+            stat_row(DisplayStatKind::PlayerStat(StatKind::Bounce), &sprites),
+            stat_row(DisplayStatKind::PlayerStat(StatKind::Chain), &sprites),
             separator(),
             stat_row(DisplayStatKind::Health, &sprites),
             separator(),
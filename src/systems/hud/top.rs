@@ -1,15 +1,15 @@
-use crate::systems::constants::{NEXT_LEVEL_RATIO_PERCENT, WAVE_DURATION};
+use crate::systems::constants::NEXT_LEVEL_RATIO_PERCENT;
 use crate::systems::game::GameState;
 use crate::systems::hud::components::{
-    HUDBottomBorder, HUDHealthFill, HUDHealthText, HUDLevelText, HUDLevelUp, HUDLevelUps,
-    HUDTimeFill, HUDTimeText, HUDTopBar, HUDWaveText, HUDXPFill, FILL_HEALTH, FILL_HEALTH_DANGER,
-    FILL_TIMER, FILL_TIMER_URGENT, FILL_XP, ICON_HEALTH, ICON_LEVEL, ICON_TIMER, ICON_WAVE, TINT_LEVEL,
-    TINT_TIMER, TINT_WAVE,
+    HUDBottomBorder, HUDDashFill, HUDHealthFill, HUDHealthText, HUDLevelText, HUDLevelUp,
+    HUDLevelUps, HUDTimeFill, HUDTimeText, HUDTopBar, HUDWaveText, HUDXPFill, FILL_DASH,
+    FILL_DASH_CHARGING, FILL_HEALTH, FILL_HEALTH_DANGER, FILL_TIMER, FILL_TIMER_URGENT, FILL_XP,
+    ICON_HEALTH, ICON_LEVEL, ICON_SPEED, ICON_TIMER, ICON_WAVE, TINT_LEVEL, TINT_TIMER, TINT_WAVE,
 };
 use crate::systems::hud::resources::HUDTextureAtlas;
 use crate::systems::states::gamemenu::renderer::palette_color;
 use crate::systems::states::waves::components::Health;
-use crate::systems::states::waves::player::components::{Player, PlayerStats};
+use crate::systems::states::waves::player::components::{Dash, Player, PlayerStats};
 use crate::systems::states::waves::player::experience::PlayerExperience;
 use crate::systems::states::waves::resources::WaveManager;
 use bevy::color::palettes::css::*;
@@ -67,6 +67,8 @@ pub fn spawn_hud(mut commands: Commands, sprites: Res<HUDTextureAtlas>) {
                 spawn_timer_pill(row, &sprites);
                 spawn_divider(row);
                 spawn_level_pill(row, &sprites);
+                spawn_divider(row);
+                spawn_dash_pill(row, &sprites);
             });
 
             // ── Animated bottom border ────────────────────────────────────────
@@ -101,6 +103,7 @@ pub fn spawn_hud(mut commands: Commands, sprites: Res<HUDTextureAtlas>) {
 }
 
 // ── Per-frame update ──────────────────────────────────────────────────────────
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn update(
     // health
     mut hp_fill: Query<
@@ -186,7 +189,7 @@ pub fn update(
 
     // ── Timer ─────────────────────────────────────────────────────────────────
     let remaining = wave_manager.wave_timer.remaining_secs();
-    let time_ratio = (remaining / WAVE_DURATION).clamp(0.0, 1.0);
+    let time_ratio = wave_manager.wave_timer.fraction_remaining();
     if let Ok((mut node, mut color)) = t_fill.single_mut() {
         node.width = Val::Percent(time_ratio * 100.0);
         *color = BackgroundColor(if remaining < 5.0 {
@@ -211,6 +214,25 @@ pub fn update(
     }
 }
 
+// ── Dash cooldown ─────────────────────────────────────────────────────────────
+pub fn update_dash_indicator(
+    mut dash_fill: Query<(&mut Node, &mut BackgroundColor), With<HUDDashFill>>,
+    player_query: Query<&Dash, With<Player>>,
+) {
+    let Ok(dash) = player_query.single() else {
+        return;
+    };
+    let Ok((mut node, mut color)) = dash_fill.single_mut() else {
+        return;
+    };
+    node.width = Val::Percent(dash.cooldown.fraction() * 100.0);
+    *color = BackgroundColor(if dash.is_ready() {
+        FILL_DASH
+    } else {
+        FILL_DASH_CHARGING
+    });
+}
+
 // ── Animated bottom border ────────────────────────────────────────────────────
 pub fn animate_hud_border(
     time: Res<Time>,
@@ -501,3 +523,43 @@ fn spawn_level_pill(parent: &mut ChildSpawnerCommands, sprites: &HUDTextureAtlas
             });
         });
 }
+
+/// » Dash pill — fixed width, refills while the dash is on cooldown.
+fn spawn_dash_pill(parent: &mut ChildSpawnerCommands, sprites: &HUDTextureAtlas) {
+    parent
+        .spawn(Node {
+            width: Val::Px(90.0),
+            flex_shrink: 0.0,
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(6.0),
+            ..default()
+        })
+        .with_children(|pill| {
+            spawn_icon(pill, sprites, ICON_SPEED, FILL_DASH);
+
+            // Track
+            pill.spawn((
+                Node {
+                    flex_grow: 1.0,
+                    height: Val::Px(6.0),
+                    border_radius: BorderRadius::all(Val::Px(3.0)),
+                    overflow: Overflow::clip(),
+                    ..default()
+                },
+                BackgroundColor(BG_TRACK),
+            ))
+            .with_children(|track| {
+                track.spawn((
+                    HUDDashFill,
+                    Node {
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                        border_radius: BorderRadius::all(Val::Px(3.0)),
+                        ..default()
+                    },
+                    BackgroundColor(FILL_DASH),
+                ));
+            });
+        });
+}
@@ -1,6 +1,7 @@
 use crate::systems::input::resources::{
-    ActionState, ActiveInputDevice, GamepadAsset, KeyboardAsset,
+    ActionState, ActiveGamepad, ActiveInputDevice, GamepadAsset, KeyboardAsset,
 };
+use crate::systems::input::systems;
 use bevy::prelude::*;
 
 #[derive(Component)]
@@ -87,11 +88,13 @@ pub fn update_active_device_indicator(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn handle_button_pressed(
     mut commands: Commands,
     keyboard: Res<ButtonInput<KeyCode>>,
     keyboard_asset: Res<KeyboardAsset>,
-    gamepad: Option<Single<&Gamepad>>,
+    gamepads: Query<(Entity, &Gamepad)>,
+    active_gamepad: Res<ActiveGamepad>,
     gamepad_asset: Res<GamepadAsset>,
     row_query: Query<Entity, With<InputIconsRow>>,
     actions: Res<ActionState>,
@@ -139,7 +142,7 @@ pub fn handle_button_pressed(
         }
         ActiveInputDevice::Gamepad => {
             // Spawn icons for newly pressed buttons
-            if let Some(ref gp) = gamepad {
+            if let Some(gp) = systems::pick_gamepad(&gamepads, &active_gamepad) {
                 for button in gp.get_pressed() {
                     commands.entity(row).with_child((
                         ButtonIcon,
@@ -1,4 +1,10 @@
+use crate::systems::game::{load_config, save_config};
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Loaded at startup, see `load_config`.
+pub const INPUT_BINDINGS_PATH: &str = "input_bindings.ron";
 
 #[derive(Resource, Default, Debug, PartialEq, Eq, Clone, Copy)]
 pub enum ActiveInputDevice {
@@ -6,9 +12,17 @@ pub enum ActiveInputDevice {
     Keyboard,
     Gamepad,
 }
+
+/// Gamepad that sent the last button or stick input, `None` until one is used
+#[derive(Resource, Default, Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ActiveGamepad(pub Option<Entity>);
+
 #[derive(Resource, Default, Debug)]
 pub struct ActionState {
     pub movement: Vec2,
+    /// Manual aiming direction (normalized), zero when not aiming
+    pub aim: Vec2,
+    pub dash: bool,
 
     pub toggle_show_stats: bool,
     pub toggle_show_debug: bool,
@@ -16,6 +30,11 @@ pub struct ActionState {
     pub card_select: [bool; 4],
 
     pub start_next_wave: bool,
+    pub pause: bool,
+
+    pub shop_buy: [bool; 4],
+    pub shop_lock: [bool; 4],
+    pub shop_reroll: bool,
 
     pub add_weapon: bool,
 }
@@ -23,8 +42,220 @@ pub struct ActionState {
 impl ActionState {
     pub fn clear(&mut self) {
         self.movement = Vec2::ZERO;
+        self.aim = Vec2::ZERO;
+        self.dash = false;
         self.card_select = [false; 4];
         self.start_next_wave = false;
+        self.pause = false;
+        self.shop_buy = [false; 4];
+        self.shop_lock = [false; 4];
+        self.shop_reroll = false;
+    }
+}
+
+/// Logical action a key or gamepad button can be bound to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum InputAction {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Dash,
+    /// Selects the matching upgrade card or buys the matching shop offer
+    Slot1,
+    Slot2,
+    Slot3,
+    Slot4,
+    /// Held with a slot to lock the shop offer instead of buying it
+    ShopLock,
+    ShopReroll,
+    StartNextWave,
+    Pause,
+    ToggleShowStats,
+    ToggleShowDebug,
+    AddWeapon,
+}
+
+impl InputAction {
+    pub const ALL: [InputAction; 16] = [
+        InputAction::MoveUp,
+        InputAction::MoveDown,
+        InputAction::MoveLeft,
+        InputAction::MoveRight,
+        InputAction::Dash,
+        InputAction::Slot1,
+        InputAction::Slot2,
+        InputAction::Slot3,
+        InputAction::Slot4,
+        InputAction::ShopLock,
+        InputAction::ShopReroll,
+        InputAction::StartNextWave,
+        InputAction::Pause,
+        InputAction::ToggleShowStats,
+        InputAction::ToggleShowDebug,
+        InputAction::AddWeapon,
+    ];
+
+    pub const SLOTS: [InputAction; 4] = [
+        InputAction::Slot1,
+        InputAction::Slot2,
+        InputAction::Slot3,
+        InputAction::Slot4,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            InputAction::MoveUp => "Move up",
+            InputAction::MoveDown => "Move down",
+            InputAction::MoveLeft => "Move left",
+            InputAction::MoveRight => "Move right",
+            InputAction::Dash => "Dash",
+            InputAction::Slot1 => "Card / offer 1",
+            InputAction::Slot2 => "Card / offer 2",
+            InputAction::Slot3 => "Card / offer 3",
+            InputAction::Slot4 => "Card / offer 4",
+            InputAction::ShopLock => "Lock offer (hold)",
+            InputAction::ShopReroll => "Reroll shop",
+            InputAction::StartNextWave => "Start / next wave",
+            InputAction::Pause => "Pause / back",
+            InputAction::ToggleShowStats => "Show stats",
+            InputAction::ToggleShowDebug => "Show input debug",
+            InputAction::AddWeapon => "Add weapon (debug)",
+        }
+    }
+}
+
+/// Keys and gamepad buttons bound to each `InputAction`, several inputs can share an action.
+/// Saved to `INPUT_BINDINGS_PATH` by the rebinding screen.
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InputBindings {
+    pub keyboard: BTreeMap<InputAction, Vec<KeyCode>>,
+    pub gamepad: BTreeMap<InputAction, Vec<GamepadButton>>,
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        use InputAction::*;
+        Self {
+            keyboard: BTreeMap::from([
+                (MoveUp, vec![KeyCode::KeyW, KeyCode::ArrowUp]),
+                (MoveDown, vec![KeyCode::KeyS, KeyCode::ArrowDown]),
+                (MoveLeft, vec![KeyCode::KeyA, KeyCode::ArrowLeft]),
+                (MoveRight, vec![KeyCode::KeyD, KeyCode::ArrowRight]),
+                (Dash, vec![KeyCode::ShiftLeft, KeyCode::ShiftRight]),
+                (Slot1, vec![KeyCode::Digit1]),
+                (Slot2, vec![KeyCode::Digit2]),
+                (Slot3, vec![KeyCode::Digit3]),
+                (Slot4, vec![KeyCode::Digit4]),
+                (ShopLock, vec![KeyCode::ShiftLeft, KeyCode::ShiftRight]),
+                (ShopReroll, vec![KeyCode::KeyR]),
+                (StartNextWave, vec![KeyCode::Enter, KeyCode::Space]),
+                (Pause, vec![KeyCode::Escape]),
+                (ToggleShowStats, vec![KeyCode::Tab]),
+                (ToggleShowDebug, vec![KeyCode::F1]),
+                (AddWeapon, vec![KeyCode::Backspace]),
+            ]),
+            gamepad: BTreeMap::from([
+                (MoveUp, vec![GamepadButton::DPadUp]),
+                (MoveDown, vec![GamepadButton::DPadDown]),
+                (MoveLeft, vec![GamepadButton::DPadLeft]),
+                (MoveRight, vec![GamepadButton::DPadRight]),
+                (Dash, vec![GamepadButton::RightTrigger2]),
+                (Slot1, vec![GamepadButton::West]),
+                (Slot2, vec![GamepadButton::South]),
+                (Slot3, vec![GamepadButton::North]),
+                (Slot4, vec![GamepadButton::East]),
+                (ShopLock, vec![GamepadButton::RightTrigger]),
+                (ShopReroll, vec![GamepadButton::LeftTrigger]),
+                (StartNextWave, vec![GamepadButton::Start]),
+                (Pause, vec![GamepadButton::Start]),
+                (ToggleShowStats, vec![GamepadButton::Select]),
+                (ToggleShowDebug, vec![GamepadButton::LeftThumb]),
+                (AddWeapon, vec![]),
+            ]),
+        }
+    }
+}
+
+impl InputBindings {
+    /// Reads `INPUT_BINDINGS_PATH`, actions missing from the file keep their default inputs.
+    pub fn load() -> Self {
+        load_config::<Self>(INPUT_BINDINGS_PATH).with_defaults()
+    }
+
+    /// Fills in the default inputs of every action these bindings do not mention
+    fn with_defaults(mut self) -> Self {
+        let defaults = Self::default();
+        for (action, keys) in defaults.keyboard {
+            self.keyboard.entry(action).or_insert(keys);
+        }
+        for (action, buttons) in defaults.gamepad {
+            self.gamepad.entry(action).or_insert(buttons);
+        }
+        self
+    }
+
+    pub fn save(&self) {
+        save_config(INPUT_BINDINGS_PATH, self);
+    }
+
+    pub fn keys(&self, action: InputAction) -> &[KeyCode] {
+        self.keyboard.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn buttons(&self, action: InputAction) -> &[GamepadButton] {
+        self.gamepad.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Makes `key` the primary key of `action`, the other keys stay bound as alternatives.
+    /// See `bind_without_conflicts` for the actions `key` was already bound to.
+    pub fn bind_key(&mut self, action: InputAction, key: KeyCode) {
+        bind_without_conflicts(&mut self.keyboard, action, key);
+    }
+
+    pub fn bind_button(&mut self, action: InputAction, button: GamepadButton) {
+        bind_without_conflicts(&mut self.gamepad, action, button);
+    }
+}
+
+/// Binds `input` as the primary input of `action` and takes it away from the other actions,
+/// so one key never triggers two actions the player did not pick. An action left without any
+/// input gets the replaced primary of `action`, swapping the two bindings.
+/// An input `action` already had is only moved to the front: the defaults share a few inputs
+/// between actions that are never used at the same time (Shift dashes and locks shop offers).
+fn bind_without_conflicts<T: PartialEq + Copy>(
+    bindings: &mut BTreeMap<InputAction, Vec<T>>,
+    action: InputAction,
+    input: T,
+) {
+    let inputs = bindings.entry(action).or_default();
+    if inputs.contains(&input) {
+        bind_primary(inputs, input);
+        return;
+    }
+    let replaced = inputs.first().copied();
+    bind_primary(inputs, input);
+
+    for (_, inputs) in bindings.iter_mut().filter(|(other, _)| **other != action) {
+        if !inputs.contains(&input) {
+            continue;
+        }
+        inputs.retain(|bound| *bound != input);
+        if inputs.is_empty()
+            && let Some(replaced) = replaced
+        {
+            inputs.push(replaced);
+        }
+    }
+}
+
+fn bind_primary<T: PartialEq>(inputs: &mut Vec<T>, input: T) {
+    inputs.retain(|bound| *bound != input);
+    if inputs.is_empty() {
+        inputs.push(input);
+    } else {
+        inputs[0] = input;
     }
 }
 
@@ -39,26 +270,26 @@ const SPRITESHEET_BEGIN: u32 = 71;
 
 impl GamepadAsset {
     pub fn get_button_index(&self, button: &GamepadButton) -> usize {
-        return match button {
-            GamepadButton::West => SPRITESHEET_BEGIN + (SPRITESHEET_WIDTH * 0),
-            GamepadButton::South => SPRITESHEET_BEGIN + (SPRITESHEET_WIDTH * 1),
+        (match button {
+            GamepadButton::West => SPRITESHEET_BEGIN,
+            GamepadButton::South => SPRITESHEET_BEGIN + SPRITESHEET_WIDTH,
             GamepadButton::North => SPRITESHEET_BEGIN + (SPRITESHEET_WIDTH * 2),
             GamepadButton::East => SPRITESHEET_BEGIN + (SPRITESHEET_WIDTH * 3),
             GamepadButton::LeftTrigger => SPRITESHEET_BEGIN + (SPRITESHEET_WIDTH * 3) + 20,
-            GamepadButton::LeftTrigger2 => SPRITESHEET_BEGIN + (SPRITESHEET_WIDTH * 1) + 20,
+            GamepadButton::LeftTrigger2 => SPRITESHEET_BEGIN + SPRITESHEET_WIDTH + 20,
             GamepadButton::RightTrigger => SPRITESHEET_BEGIN + (SPRITESHEET_WIDTH * 4) + 20,
             GamepadButton::RightTrigger2 => SPRITESHEET_BEGIN + (SPRITESHEET_WIDTH * 2) + 20,
             GamepadButton::Select => SPRITESHEET_BEGIN + (SPRITESHEET_WIDTH * 4),
             GamepadButton::Start => SPRITESHEET_BEGIN + (SPRITESHEET_WIDTH * 5),
             GamepadButton::Mode => SPRITESHEET_BEGIN + (SPRITESHEET_WIDTH * 5) + 20,
-            GamepadButton::LeftThumb => SPRITESHEET_BEGIN + (SPRITESHEET_WIDTH * 0) + 12,
-            GamepadButton::RightThumb => SPRITESHEET_BEGIN + (SPRITESHEET_WIDTH * 0) + 16,
+            GamepadButton::LeftThumb => SPRITESHEET_BEGIN + 12,
+            GamepadButton::RightThumb => SPRITESHEET_BEGIN + 16,
             GamepadButton::DPadUp => SPRITESHEET_BEGIN + (SPRITESHEET_WIDTH * 3) + 7,
             GamepadButton::DPadDown => SPRITESHEET_BEGIN + (SPRITESHEET_WIDTH * 3) + 8,
             GamepadButton::DPadLeft => SPRITESHEET_BEGIN + (SPRITESHEET_WIDTH * 4) + 7,
             GamepadButton::DPadRight => SPRITESHEET_BEGIN + (SPRITESHEET_WIDTH * 4) + 8,
             _ => 0,
-        } as usize;
+        }) as usize
     }
 }
 impl FromWorld for GamepadAsset {
@@ -82,11 +313,38 @@ pub struct KeyboardAsset {}
 impl KeyboardAsset {
     pub fn keycode_label(&self, key: &KeyCode) -> &'static str {
         match key {
-            // Movement
-            KeyCode::KeyW | KeyCode::ArrowUp => "UP",
-            KeyCode::KeyS | KeyCode::ArrowDown => "DOWN",
-            KeyCode::KeyA | KeyCode::ArrowLeft => "LEFT",
-            KeyCode::KeyD | KeyCode::ArrowRight => "RIGHT",
+            // Arrows
+            KeyCode::ArrowUp => "UP",
+            KeyCode::ArrowDown => "DOWN",
+            KeyCode::ArrowLeft => "LEFT",
+            KeyCode::ArrowRight => "RIGHT",
+            // Letters
+            KeyCode::KeyA => "A",
+            KeyCode::KeyB => "B",
+            KeyCode::KeyC => "C",
+            KeyCode::KeyD => "D",
+            KeyCode::KeyE => "E",
+            KeyCode::KeyF => "F",
+            KeyCode::KeyG => "G",
+            KeyCode::KeyH => "H",
+            KeyCode::KeyI => "I",
+            KeyCode::KeyJ => "J",
+            KeyCode::KeyK => "K",
+            KeyCode::KeyL => "L",
+            KeyCode::KeyM => "M",
+            KeyCode::KeyN => "N",
+            KeyCode::KeyO => "O",
+            KeyCode::KeyP => "P",
+            KeyCode::KeyQ => "Q",
+            KeyCode::KeyR => "R",
+            KeyCode::KeyS => "S",
+            KeyCode::KeyT => "T",
+            KeyCode::KeyU => "U",
+            KeyCode::KeyV => "V",
+            KeyCode::KeyW => "W",
+            KeyCode::KeyX => "X",
+            KeyCode::KeyY => "Y",
+            KeyCode::KeyZ => "Z",
             // Common actions
             KeyCode::Space => "SPC",
             KeyCode::Enter => "ENTER",
@@ -124,3 +382,103 @@ impl KeyboardAsset {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partial_bindings_keep_the_defaults_of_missing_actions() {
+        let saved: InputBindings =
+            ron::from_str("(keyboard: {MoveUp: [KeyI]}, gamepad: {Dash: []})").unwrap();
+        let bindings = saved.with_defaults();
+        let defaults = InputBindings::default();
+
+        assert_eq!(bindings.keys(InputAction::MoveUp), &[KeyCode::KeyI]);
+        assert_eq!(
+            bindings.keys(InputAction::MoveDown),
+            defaults.keys(InputAction::MoveDown)
+        );
+        // An action saved without inputs stays unbound
+        assert!(bindings.buttons(InputAction::Dash).is_empty());
+        assert_eq!(
+            bindings.buttons(InputAction::Pause),
+            defaults.buttons(InputAction::Pause)
+        );
+        assert_eq!(bindings.keyboard.len(), InputAction::ALL.len());
+        assert_eq!(bindings.gamepad.len(), InputAction::ALL.len());
+    }
+
+    #[test]
+    fn empty_file_loads_the_defaults() {
+        let saved: InputBindings = ron::from_str("()").unwrap();
+        assert_eq!(saved.with_defaults(), InputBindings::default());
+    }
+
+    #[test]
+    fn bind_primary_replaces_the_first_input_and_keeps_the_others() {
+        let mut inputs = vec![KeyCode::KeyW, KeyCode::ArrowUp];
+        bind_primary(&mut inputs, KeyCode::KeyI);
+        assert_eq!(inputs, [KeyCode::KeyI, KeyCode::ArrowUp]);
+    }
+
+    #[test]
+    fn bind_primary_moves_an_alternative_to_the_front_without_duplicating_it() {
+        let mut inputs = vec![KeyCode::KeyW, KeyCode::ArrowUp];
+        bind_primary(&mut inputs, KeyCode::ArrowUp);
+        assert_eq!(inputs, [KeyCode::ArrowUp]);
+    }
+
+    #[test]
+    fn binding_a_key_takes_it_away_from_the_other_actions() {
+        let mut bindings = InputBindings::default();
+        bindings.bind_key(InputAction::Dash, KeyCode::KeyW);
+        assert_eq!(
+            bindings.keys(InputAction::Dash),
+            &[KeyCode::KeyW, KeyCode::ShiftRight]
+        );
+        assert_eq!(bindings.keys(InputAction::MoveUp), &[KeyCode::ArrowUp]);
+        // ShopLock never had W, it keeps its shared Shift keys
+        assert_eq!(
+            bindings.keys(InputAction::ShopLock),
+            &[KeyCode::ShiftLeft, KeyCode::ShiftRight]
+        );
+    }
+
+    #[test]
+    fn binding_the_only_input_of_another_action_swaps_them() {
+        let mut bindings = InputBindings::default();
+        bindings.bind_key(InputAction::Slot2, KeyCode::Digit1);
+        assert_eq!(bindings.keys(InputAction::Slot2), &[KeyCode::Digit1]);
+        assert_eq!(bindings.keys(InputAction::Slot1), &[KeyCode::Digit2]);
+
+        bindings.bind_button(InputAction::Slot1, GamepadButton::East);
+        assert_eq!(bindings.buttons(InputAction::Slot1), &[GamepadButton::East]);
+        assert_eq!(bindings.buttons(InputAction::Slot4), &[GamepadButton::West]);
+    }
+
+    #[test]
+    fn rebinding_a_shared_input_keeps_it_shared() {
+        let mut bindings = InputBindings::default();
+        bindings.bind_button(InputAction::Pause, GamepadButton::Start);
+        assert_eq!(
+            bindings.buttons(InputAction::StartNextWave),
+            &[GamepadButton::Start]
+        );
+        bindings.bind_key(InputAction::ShopLock, KeyCode::ShiftRight);
+        assert_eq!(
+            bindings.keys(InputAction::Dash),
+            &[KeyCode::ShiftLeft, KeyCode::ShiftRight]
+        );
+    }
+
+    #[test]
+    fn bind_primary_fills_an_unbound_action() {
+        let mut bindings = InputBindings::default();
+        bindings.bind_button(InputAction::AddWeapon, GamepadButton::Mode);
+        assert_eq!(
+            bindings.buttons(InputAction::AddWeapon),
+            &[GamepadButton::Mode]
+        );
+    }
+}
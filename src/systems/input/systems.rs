@@ -1,13 +1,21 @@
-use crate::systems::input::resources::{ActionState, ActiveInputDevice};
+use crate::systems::input::resources::{
+    ActionState, ActiveGamepad, ActiveInputDevice, InputAction, InputBindings,
+};
+use crate::systems::states::waves::player::components::Player;
 use bevy::input::gamepad::GamepadEvent;
 use bevy::input::keyboard::KeyboardInput;
 use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use std::collections::BTreeMap;
+use std::hash::Hash;
+
 const GAMEPAD_DEAD_ZONE: f32 = 0.15;
 
 pub fn detect_input_device(
     mut gamepad_events: MessageReader<GamepadEvent>,
     mut keyboard_events: MessageReader<KeyboardInput>,
     mut active_device: ResMut<ActiveInputDevice>,
+    mut active_gamepad: ResMut<ActiveGamepad>,
 ) {
     // Detect keyboard key stroke
     if keyboard_events.read().next().is_some() {
@@ -19,89 +27,134 @@ pub fn detect_input_device(
     for event in gamepad_events.read() {
         match event {
             GamepadEvent::Connection(e) => info!("Gamepad connection: {:?}", e),
-            GamepadEvent::Button(_) => {
+            GamepadEvent::Button(e) => {
                 *active_device = ActiveInputDevice::Gamepad;
+                active_gamepad.0 = Some(e.entity);
                 return;
             }
-            GamepadEvent::Axis(_) => {
+            GamepadEvent::Axis(e) => {
                 *active_device = ActiveInputDevice::Gamepad;
+                active_gamepad.0 = Some(e.entity);
                 return;
             }
         }
     }
 }
 
+/// The gamepad last used, or the first one connected when none was used yet (or it left)
+pub fn pick_gamepad<'a>(
+    gamepads: &'a Query<(Entity, &Gamepad)>,
+    active: &ActiveGamepad,
+) -> Option<&'a Gamepad> {
+    active
+        .0
+        .and_then(|entity| gamepads.get(entity).ok())
+        .or_else(|| gamepads.iter().next())
+        .map(|(_, gamepad)| gamepad)
+}
+
 pub fn collect_actions(
     keyboard: Res<ButtonInput<KeyCode>>,
-    gamepad: Option<Single<&Gamepad>>,
-    active_device: ResMut<ActiveInputDevice>,
+    gamepads: Query<(Entity, &Gamepad)>,
+    active_gamepad: Res<ActiveGamepad>,
+    bindings: Res<InputBindings>,
+    active_device: Res<ActiveInputDevice>,
     mut actions: ResMut<ActionState>,
 ) {
     actions.clear();
     match *active_device {
         ActiveInputDevice::Gamepad => {
-            if let Some(gamepad) = gamepad {
-                collect_gamepad_actions(&gamepad, &mut actions);
+            if let Some(gamepad) = pick_gamepad(&gamepads, &active_gamepad) {
+                collect_bound_actions(gamepad.digital(), &bindings.gamepad, &mut actions);
+                // The stick wins over the D-pad bindings
+                if gamepad.left_stick().length() > GAMEPAD_DEAD_ZONE {
+                    actions.movement = gamepad.left_stick().normalize_or_zero();
+                }
             }
         }
         ActiveInputDevice::Keyboard => {
-            collect_keyboard_actions(keyboard, &mut actions);
+            collect_bound_actions(&keyboard, &bindings.keyboard, &mut actions);
         }
     }
 }
 
-fn collect_keyboard_actions(
-    keyboard: Res<ButtonInput<KeyCode>>,
-    actions: &mut ResMut<ActionState>,
+/// Right stick direction, or the direction from the player towards the mouse cursor.
+/// Only used by weapons in `AimMode::Manual`.
+pub fn collect_aim(
+    active_device: Res<ActiveInputDevice>,
+    gamepads: Query<(Entity, &Gamepad)>,
+    active_gamepad: Res<ActiveGamepad>,
+    window: Option<Single<&Window, With<PrimaryWindow>>>,
+    camera: Option<Single<(&Camera, &GlobalTransform)>>,
+    player: Option<Single<&GlobalTransform, With<Player>>>,
+    mut actions: ResMut<ActionState>,
 ) {
-    for key in keyboard.get_just_pressed() {
-        match key {
-            KeyCode::Tab => actions.toggle_show_stats ^= true,
-            KeyCode::F1 => actions.toggle_show_debug ^= true,
-            KeyCode::Backspace => actions.add_weapon ^= true,
-            KeyCode::Unidentified(_) => {}
-            _ => {}
+    match *active_device {
+        ActiveInputDevice::Gamepad => {
+            if let Some(gamepad) = pick_gamepad(&gamepads, &active_gamepad)
+                && gamepad.right_stick().length() > GAMEPAD_DEAD_ZONE
+            {
+                actions.aim = gamepad.right_stick().normalize_or_zero();
+            }
         }
-    }
-    for key in keyboard.get_pressed() {
-        match key {
-            KeyCode::KeyW | KeyCode::ArrowUp => actions.movement.y += 1.0,
-            KeyCode::KeyS | KeyCode::ArrowDown => actions.movement.y -= 1.0,
-            KeyCode::KeyA | KeyCode::ArrowLeft => actions.movement.x -= 1.0,
-            KeyCode::KeyD | KeyCode::ArrowRight => actions.movement.x += 1.0,
-            KeyCode::Digit1 => actions.card_select[0] |= true,
-            KeyCode::Digit2 => actions.card_select[1] |= true,
-            KeyCode::Digit3 => actions.card_select[2] |= true,
-            KeyCode::Digit4 => actions.card_select[3] |= true,
-            KeyCode::Enter | KeyCode::Space => actions.start_next_wave |= true,
-            _ => {}
+        ActiveInputDevice::Keyboard => {
+            let (Some(window), Some(camera), Some(player)) = (window, camera, player) else {
+                return;
+            };
+            let (camera, camera_transform) = *camera;
+            if let Some(cursor) = window.cursor_position()
+                && let Ok(target) = camera.viewport_to_world_2d(camera_transform, cursor)
+            {
+                actions.aim = (target - player.translation().truncate()).normalize_or_zero();
+            }
         }
     }
-    actions.movement = actions.movement.normalize_or_zero();
 }
 
-fn collect_gamepad_actions(gamepad: &Gamepad, actions: &mut ResMut<ActionState>) {
-    for button in gamepad.get_just_pressed() {
-        match button {
-            GamepadButton::Select => actions.toggle_show_stats ^= true,
-            GamepadButton::LeftThumb => actions.toggle_show_debug ^= true,
-            _ => {}
-        }
-    }
-    for button in gamepad.get_pressed() {
-        match button {
-            GamepadButton::West => actions.card_select[0] |= true,
-            GamepadButton::South => actions.card_select[1] |= true,
-            GamepadButton::North => actions.card_select[2] |= true,
-            GamepadButton::East => actions.card_select[3] |= true,
-            GamepadButton::Start => actions.start_next_wave |= true,
-            _ => {}
+/// Same mapping for keys and gamepad buttons, only the bindings differ.
+fn collect_bound_actions<T>(
+    input: &ButtonInput<T>,
+    bindings: &BTreeMap<InputAction, Vec<T>>,
+    actions: &mut ActionState,
+) where
+    T: Copy + Eq + Hash + Send + Sync + 'static,
+{
+    let bound = |action| bindings.get(&action).into_iter().flatten().copied();
+    let pressed = |action| input.any_pressed(bound(action));
+    let just_pressed = |action| input.any_just_pressed(bound(action));
+
+    actions.toggle_show_stats ^= just_pressed(InputAction::ToggleShowStats);
+    actions.toggle_show_debug ^= just_pressed(InputAction::ToggleShowDebug);
+    actions.add_weapon ^= just_pressed(InputAction::AddWeapon);
+    actions.pause |= just_pressed(InputAction::Pause);
+    actions.shop_reroll |= just_pressed(InputAction::ShopReroll);
+    actions.dash |= just_pressed(InputAction::Dash);
+    actions.start_next_wave |= pressed(InputAction::StartNextWave);
+
+    // A slot selects the matching card, or buys the matching shop offer (locks it with ShopLock held)
+    let locking = pressed(InputAction::ShopLock);
+    for (index, slot) in InputAction::SLOTS.into_iter().enumerate() {
+        actions.card_select[index] |= pressed(slot);
+        if just_pressed(slot) {
+            if locking {
+                actions.shop_lock[index] = true;
+            } else {
+                actions.shop_buy[index] = true;
+            }
         }
     }
 
-    if gamepad.left_stick().length() > GAMEPAD_DEAD_ZONE {
-        actions.movement = gamepad.left_stick().normalize_or_zero();
-    } else if gamepad.dpad().length() > 0.0 {
-        actions.movement = gamepad.dpad().normalize_or_zero();
+    if pressed(InputAction::MoveUp) {
+        actions.movement.y += 1.0;
+    }
+    if pressed(InputAction::MoveDown) {
+        actions.movement.y -= 1.0;
+    }
+    if pressed(InputAction::MoveLeft) {
+        actions.movement.x -= 1.0;
     }
+    if pressed(InputAction::MoveRight) {
+        actions.movement.x += 1.0;
+    }
+    actions.movement = actions.movement.normalize_or_zero();
 }
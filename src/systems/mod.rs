@@ -1,6 +1,8 @@
 pub mod animations;
 pub mod constants;
 pub mod game;
+pub mod gameplay;
 pub mod hud;
 pub mod input;
+pub mod save;
 pub mod states;
@@ -0,0 +1,462 @@
+use crate::systems::animations::animation::SpriteAnimation;
+use crate::systems::game::{self, GameOverStats, GameRng, GameState};
+use crate::systems::states::shopping::resources::{
+    PassiveItemsPool, Shop, ShopItem, ShopOffer, SHOP_SLOTS,
+};
+use crate::systems::states::shopping::systems::{handle_shop_actions, restock_shop};
+use crate::systems::states::waves::components::Health;
+use crate::systems::states::waves::player::characters::{
+    CharactersLibrary, SelectedCharacter, DEFAULT_CHARACTER,
+};
+use crate::systems::states::waves::player::components::{Player, PlayerBundle, PlayerStats};
+use crate::systems::states::waves::player::currency::Currency;
+use crate::systems::states::waves::player::experience::PlayerExperience;
+use crate::systems::states::waves::player::resources::PlayerAnimations;
+use crate::systems::states::waves::resources::WaveManager;
+use crate::systems::states::waves::weapons::components::Weapon;
+use crate::systems::states::waves::weapons::messages::WeaponSpawnedMessage;
+use crate::systems::states::waves::weapons::resources::WeaponsLibrary;
+use crate::systems::states::waves::weapons::systems::equip_weapon;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Rewritten on every change of the `Shop`, from entering it to each buy, lock and reroll.
+/// See `save_config`.
+pub const RUN_SAVE_PATH: &str = "run_save.ron";
+
+/// Bumped whenever `RunSave` changes in a way older files cannot be read as.
+pub const RUN_SAVE_VERSION: u32 = 1;
+
+/// Everything needed to resume a run from the shop it was saved at.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunSave {
+    pub version: u32,
+    pub seed: u64,
+    pub wave: u32,
+    pub stats: PlayerStats,
+    pub experience: PlayerExperience,
+    pub health: f32,
+    pub currency: u32,
+    /// `CharacterDefinition` id the run was started with
+    #[serde(default = "default_character")]
+    pub character: String,
+    /// Kills so far, added to the profile when the resumed run ends
+    #[serde(default)]
+    pub enemies_killed: u32,
+    /// `WeaponDefinition` ids, stable when weapon files are added or removed
+    pub weapons: Vec<String>,
+    /// Duration of the wave timer, in seconds
+    #[serde(default)]
+    pub wave_duration: f32,
+    /// Every shop offer, by slot, so the shop comes back exactly as it was left
+    #[serde(default)]
+    pub shop_offers: Vec<SavedOffer>,
+    /// Rerolls already paid for during the saved shop visit
+    #[serde(default)]
+    pub rerolls: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedOffer {
+    pub item: SavedItem,
+    pub price: u32,
+    pub locked: bool,
+    pub sold: bool,
+}
+
+/// `ShopItem` by id: `WeaponDefinition` id or `PassiveItem` name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SavedItem {
+    Weapon(String),
+    Passive(String),
+}
+
+fn default_character() -> String {
+    DEFAULT_CHARACTER.to_string()
+}
+
+/// Only the version, read first so a save from another version is never half-parsed.
+#[derive(Deserialize)]
+struct RunSaveHeader {
+    version: u32,
+}
+
+impl RunSave {
+    /// Reads `RUN_SAVE_PATH`, `None` when there is no save or it cannot be used.
+    pub fn load() -> Option<Self> {
+        Self::parse(&std::fs::read_to_string(RUN_SAVE_PATH).ok()?)
+    }
+
+    /// `None` when `content` is not a save of `RUN_SAVE_VERSION`.
+    fn parse(content: &str) -> Option<Self> {
+        let header = ron::from_str::<RunSaveHeader>(content)
+            .inspect_err(|err| warn!("Ignoring invalid {}: {}", RUN_SAVE_PATH, err))
+            .ok()?;
+        match header.version {
+            RUN_SAVE_VERSION => ron::from_str::<Self>(content)
+                .inspect_err(|err| warn!("Ignoring invalid {}: {}", RUN_SAVE_PATH, err))
+                .ok(),
+            // Migrations from older versions go here, once there are any
+            version => {
+                warn!(
+                    "Ignoring {}: version {} is not supported (expected {})",
+                    RUN_SAVE_PATH, version, RUN_SAVE_VERSION
+                );
+                None
+            }
+        }
+    }
+
+    pub fn save(&self) {
+        game::save_config(RUN_SAVE_PATH, self);
+    }
+
+    /// The run is over (game over or abandoned), there is nothing left to continue.
+    pub fn delete() {
+        if let Err(err) = std::fs::remove_file(RUN_SAVE_PATH)
+            && err.kind() != std::io::ErrorKind::NotFound
+        {
+            warn!("Could not delete {}: {}", RUN_SAVE_PATH, err);
+        }
+    }
+}
+
+/// Save found when entering the main menu, `None` hides the "Continue" button.
+#[derive(Resource, Default)]
+pub struct SavedRun(pub Option<RunSave>);
+
+/// Save picked from the main menu, restored when the menu is left.
+#[derive(Resource)]
+pub struct ResumeRun(pub RunSave);
+
+pub struct RunSavePlugin;
+
+impl Plugin for RunSavePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SavedRun>()
+            .add_systems(OnEnter(GameState::MainMenu), load_saved_run)
+            .add_systems(
+                OnExit(GameState::MainMenu),
+                restore_run.after(game::start_new_run),
+            )
+            // On entering the shop and after every purchase, lock or reroll
+            .add_systems(
+                Update,
+                save_run
+                    .after(handle_shop_actions)
+                    .run_if(in_state(GameState::Shopping).and(resource_changed::<Shop>)),
+            )
+            .add_systems(
+                OnEnter(GameState::Shopping),
+                finish_resume.after(restock_shop),
+            )
+            .add_systems(OnEnter(GameState::GameOver), delete_run_save);
+    }
+}
+
+pub fn load_saved_run(mut saved_run: ResMut<SavedRun>) {
+    saved_run.0 = RunSave::load();
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn save_run(
+    wave_manager: Res<WaveManager>,
+    rng: Res<GameRng>,
+    game_over_stats: Res<GameOverStats>,
+    selected_character: Res<SelectedCharacter>,
+    library: Res<WeaponsLibrary>,
+    shop: Res<Shop>,
+    player: Single<
+        (
+            &PlayerStats,
+            &PlayerExperience,
+            &Health,
+            &Currency,
+            Option<&Children>,
+        ),
+        With<Player>,
+    >,
+    weapons_query: Query<&Weapon>,
+) {
+    let (stats, experience, health, currency, children) = player.into_inner();
+    RunSave {
+        version: RUN_SAVE_VERSION,
+        seed: rng.seed(),
+        wave: wave_manager.wave,
+        stats: stats.clone(),
+        experience: experience.clone(),
+        health: health.value,
+        currency: currency.0,
+        character: selected_character.0.clone(),
+        enemies_killed: game_over_stats.enemies_killed,
+        // A player without any weapon has no children, the run is still saved
+        weapons: weapons_query
+            .iter_many(children.into_iter().flatten())
+            .map(|weapon| library.get(weapon.kind).id.clone())
+            .collect(),
+        wave_duration: wave_manager.wave_timer.duration().as_secs_f32(),
+        shop_offers: shop
+            .offers
+            .iter()
+            .map(|offer| SavedOffer {
+                item: match offer.item {
+                    ShopItem::Weapon(kind) => SavedItem::Weapon(library.get(kind).id.clone()),
+                    ShopItem::Passive(item) => SavedItem::Passive(item.name.to_string()),
+                },
+                price: offer.price,
+                locked: offer.locked,
+                sold: offer.sold,
+            })
+            .collect(),
+        rerolls: shop.rerolls,
+    }
+    .save();
+}
+
+/// Rebuilds the player, the wave progress and the shop of a `ResumeRun`, the menu then goes to
+/// the shop. `ResumeRun` is kept until the shop is entered so it is not restocked.
+#[allow(clippy::too_many_arguments)]
+pub fn restore_run(
+    mut commands: Commands,
+    resume: Option<Res<ResumeRun>>,
+    mut wave_manager: ResMut<WaveManager>,
+    mut rng: ResMut<GameRng>,
+    mut game_over_stats: ResMut<GameOverStats>,
+    mut shop: ResMut<Shop>,
+    library: Res<WeaponsLibrary>,
+    passives: Res<PassiveItemsPool>,
+    characters: Res<CharactersLibrary>,
+    player_animations: Res<PlayerAnimations>,
+    animations: Res<Assets<SpriteAnimation>>,
+    mut events: MessageWriter<WeaponSpawnedMessage>,
+) {
+    let Some(resume) = resume else {
+        return;
+    };
+    let save = &resume.0;
+
+    wave_manager.wave = save.wave;
+    rng.resume_run(save.seed, save.wave);
+    game_over_stats.enemies_killed = save.enemies_killed;
+    if save.wave_duration > 0.0 {
+        let duration = Duration::from_secs_f32(save.wave_duration);
+        wave_manager.wave_timer.set_duration(duration);
+    }
+    restore_shop(&mut shop, &save.shop_offers, &library, &passives);
+    if shop.offers.is_empty() {
+        // Saved before the shop was, a fresh stock like any other visit
+        shop.restock(save.wave, &library, &passives, &mut rng);
+    }
+    shop.rerolls = save.rerolls;
+
+    let player = game::spawn_player_entity(
+        &mut commands,
+        &player_animations,
+        &animations,
+        PlayerBundle {
+            health: Health { value: save.health },
+            xp: save.experience.clone(),
+            currency: Currency(save.currency),
+            stats: save.stats.clone(),
+            ..characters.get_or_default(&save.character).player_bundle()
+        },
+    );
+    for (index, id) in save.weapons.iter().enumerate() {
+        let Some(kind) = library.find(id) else {
+            warn!("Skipping unknown weapon {:?} from {}", id, RUN_SAVE_PATH);
+            continue;
+        };
+        events.write(equip_weapon(
+            &mut commands,
+            &library,
+            kind,
+            player,
+            &save.stats,
+            index,
+        ));
+    }
+}
+
+/// Puts the saved offers back in their slots. An item that no longer exists, or a slot missing
+/// from the save, comes back sold. Nothing is restored from a save without offers.
+fn restore_shop(
+    shop: &mut Shop,
+    saved_offers: &[SavedOffer],
+    library: &WeaponsLibrary,
+    passives: &PassiveItemsPool,
+) {
+    shop.offers.clear();
+    if saved_offers.is_empty() {
+        return;
+    }
+    let sold = ShopOffer {
+        item: ShopItem::Passive(passives.items[0]),
+        price: 0,
+        locked: false,
+        sold: true,
+    };
+    for saved in saved_offers.iter().take(SHOP_SLOTS) {
+        let item = match &saved.item {
+            SavedItem::Weapon(id) => library.find(id).map(ShopItem::Weapon),
+            SavedItem::Passive(name) => passives
+                .items
+                .iter()
+                .find(|item| item.name == name)
+                .map(|item| ShopItem::Passive(*item)),
+        };
+        let Some(item) = item else {
+            warn!(
+                "Skipping unknown shop offer {:?} from {}",
+                saved.item, RUN_SAVE_PATH
+            );
+            shop.offers.push(sold);
+            continue;
+        };
+        shop.offers.push(ShopOffer {
+            item,
+            price: saved.price,
+            locked: saved.locked,
+            sold: saved.sold,
+        });
+    }
+    shop.offers.resize(SHOP_SLOTS, sold);
+}
+
+/// The restored shop is the one the player left, only a new visit restocks it.
+pub fn finish_resume(mut commands: Commands) {
+    commands.remove_resource::<ResumeRun>();
+}
+
+pub fn delete_run_save() {
+    RunSave::delete();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_save() -> RunSave {
+        RunSave {
+            version: RUN_SAVE_VERSION,
+            seed: 42,
+            wave: 7,
+            stats: PlayerStats::default(),
+            experience: PlayerExperience::default(),
+            health: 35.0,
+            currency: 120,
+            character: "rogue".to_string(),
+            enemies_killed: 310,
+            weapons: vec!["pistol".to_string(), "shotgun".to_string()],
+            wave_duration: 45.0,
+            shop_offers: vec![
+                offer(SavedItem::Passive("Whetstone".to_string()), 17, false, true),
+                offer(SavedItem::Weapon("shotgun".to_string()), 30, true, false),
+                offer(
+                    SavedItem::Passive("Tough Hide".to_string()),
+                    17,
+                    false,
+                    false,
+                ),
+                offer(SavedItem::Passive("Scope".to_string()), 14, true, false),
+            ],
+            rerolls: 2,
+        }
+    }
+
+    fn offer(item: SavedItem, price: u32, locked: bool, sold: bool) -> SavedOffer {
+        SavedOffer {
+            item,
+            price,
+            locked,
+            sold,
+        }
+    }
+
+    fn to_ron(save: &RunSave) -> String {
+        ron::ser::to_string_pretty(save, ron::ser::PrettyConfig::default()).unwrap()
+    }
+
+    #[test]
+    fn save_round_trips() {
+        let save = run_save();
+        let loaded = RunSave::parse(&to_ron(&save)).unwrap();
+
+        assert_eq!(loaded.seed, save.seed);
+        assert_eq!(loaded.wave, save.wave);
+        assert_eq!(loaded.health, save.health);
+        assert_eq!(loaded.currency, save.currency);
+        assert_eq!(loaded.character, save.character);
+        assert_eq!(loaded.enemies_killed, save.enemies_killed);
+        assert_eq!(loaded.weapons, save.weapons);
+        assert_eq!(loaded.wave_duration, save.wave_duration);
+        assert_eq!(loaded.shop_offers, save.shop_offers);
+        assert_eq!(loaded.rerolls, save.rerolls);
+        assert_eq!(to_ron(&loaded), to_ron(&save));
+    }
+
+    #[test]
+    fn other_versions_are_rejected() {
+        for version in [0, RUN_SAVE_VERSION + 1] {
+            let save = RunSave {
+                version,
+                ..run_save()
+            };
+            assert!(
+                RunSave::parse(&to_ron(&save)).is_none(),
+                "version {version}"
+            );
+        }
+    }
+
+    #[test]
+    fn corrupt_saves_are_ignored() {
+        let content = to_ron(&run_save());
+        assert!(RunSave::parse("").is_none());
+        assert!(RunSave::parse("not a save").is_none());
+        assert!(RunSave::parse(&content[..content.len() / 2]).is_none());
+        assert!(RunSave::parse(&content.replace("seed: 42", "seed: \"42\"")).is_none());
+    }
+
+    #[test]
+    fn saves_without_the_shop_fields_still_load() {
+        let content = to_ron(&run_save());
+        let start = content.find("    wave_duration").unwrap();
+        let end = content.rfind(')').unwrap();
+        let older = format!("{}{}", &content[..start], &content[end..]);
+
+        let loaded = RunSave::parse(&older).unwrap();
+        assert_eq!(loaded.wave_duration, 0.0);
+        assert!(loaded.shop_offers.is_empty());
+        assert_eq!(loaded.rerolls, 0);
+    }
+
+    #[test]
+    fn the_shop_comes_back_as_it_was_left() {
+        let library = WeaponsLibrary::from_assets(&["shotgun"]);
+        let passives = PassiveItemsPool::default();
+        let mut saved_offers = run_save().shop_offers;
+        saved_offers[2].item = SavedItem::Weapon("removed".to_string());
+
+        let mut shop = Shop::default();
+        restore_shop(&mut shop, &saved_offers, &library, &passives);
+
+        assert_eq!(shop.offers.len(), SHOP_SLOTS);
+        assert!(matches!(shop.offers[0].item, ShopItem::Passive(item) if item.name == "Whetstone"));
+        assert!(shop.offers[0].sold && !shop.offers[0].locked);
+        let shotgun = library.find("shotgun").unwrap();
+        assert_eq!(shop.offers[1].item, ShopItem::Weapon(shotgun));
+        assert_eq!(shop.offers[1].price, 30);
+        assert!(shop.offers[1].locked && !shop.offers[1].sold);
+        assert!(shop.offers[2].sold, "unknown items can't be bought");
+        assert!(matches!(shop.offers[3].item, ShopItem::Passive(item) if item.name == "Scope"));
+        assert!(shop.offers[3].locked);
+
+        restore_shop(&mut shop, &saved_offers[..1], &library, &passives);
+        assert_eq!(shop.offers.len(), SHOP_SLOTS);
+        assert!(shop.offers.iter().all(|offer| offer.sold));
+
+        restore_shop(&mut shop, &[], &library, &passives);
+        assert!(shop.offers.is_empty(), "left for the restock");
+    }
+}
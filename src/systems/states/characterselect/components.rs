@@ -0,0 +1,17 @@
+use bevy::prelude::Component;
+
+#[derive(Component)]
+pub struct CharacterSelectUI;
+
+/// Card of the character at this index of the `CharactersLibrary`
+#[derive(Component)]
+pub struct CharacterCard {
+    pub index: usize,
+    pub unlocked: bool,
+}
+
+#[derive(Component)]
+pub struct CharacterStartButton;
+
+#[derive(Component)]
+pub struct CharacterBackButton;
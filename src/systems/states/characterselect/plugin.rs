@@ -0,0 +1,39 @@
+use crate::systems::game::GameState;
+use crate::systems::states::characterselect::renderer::{
+    spawn_character_select, update_character_button_interaction, update_character_cards,
+};
+use crate::systems::states::characterselect::systems::{
+    ensure_character_unlocked, handle_character_select, load_character_animations,
+};
+use crate::systems::states::waves::player::characters::{CharactersLibrary, SelectedCharacter};
+use bevy::prelude::*;
+
+/// Screen between the main menu and the first wave where the character of the run is picked.
+/// Also keeps `PlayerAnimations` in sync with the selected character.
+pub struct CharacterSelectPlugin;
+
+impl Plugin for CharacterSelectPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::CharacterSelect),
+            (ensure_character_unlocked, spawn_character_select),
+        )
+        .add_systems(
+            Update,
+            (
+                handle_character_select,
+                update_character_cards,
+                update_character_button_interaction,
+            )
+                .run_if(in_state(GameState::CharacterSelect)),
+        )
+        // PostUpdate, so a character picked from the main menu (Continue) has its animations
+        // before the state transition spawns the player
+        .add_systems(
+            PostUpdate,
+            load_character_animations.run_if(
+                resource_changed::<SelectedCharacter>.or(resource_added::<CharactersLibrary>),
+            ),
+        );
+    }
+}
@@ -0,0 +1,208 @@
+use crate::systems::game::{GameState, TextBundle};
+use crate::systems::hud::components::DisplayStatKind;
+use crate::systems::states::characterselect::components::*;
+use crate::systems::states::profile::resources::{Profile, UnlockTree};
+use crate::systems::states::waves::player::characters::{
+    CharacterDefinition, CharactersLibrary, SelectedCharacter,
+};
+use crate::systems::states::waves::player::components::StatKind;
+use bevy::ecs::relationship::RelatedSpawnerCommands;
+use bevy::prelude::*;
+
+const BG: Color = Color::srgb(0.04, 0.02, 0.08);
+const CARD_BG: Color = Color::srgb(0.07, 0.07, 0.12);
+const CARD_BG_LOCKED: Color = Color::srgb(0.04, 0.04, 0.06);
+const BUTTON_BG: Color = Color::srgb(0.15, 0.15, 0.25);
+const BUTTON_BG_HOVER: Color = Color::srgb(0.25, 0.25, 0.4);
+const GREY: Color = Color::srgb(0.55, 0.55, 0.65);
+const DARK_GREY: Color = Color::srgb(0.3, 0.3, 0.38);
+const GOLD: Color = Color::srgb(1.0, 0.78, 0.2);
+const GREEN: Color = Color::srgb(0.4, 0.85, 0.4);
+const RED: Color = Color::srgb(0.9, 0.35, 0.35);
+
+pub fn spawn_character_select(
+    mut commands: Commands,
+    characters: Res<CharactersLibrary>,
+    profile: Res<Profile>,
+    tree: Res<UnlockTree>,
+) {
+    let unlocked = profile.characters(&tree);
+    commands
+        .spawn((
+            CharacterSelectUI,
+            DespawnOnExit(GameState::CharacterSelect),
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(28.0),
+                ..default()
+            },
+            BackgroundColor(BG),
+        ))
+        .with_children(|root| {
+            root.spawn(TextBundle::new("CHOOSE YOUR CHARACTER", 44.0, Color::WHITE));
+            root.spawn(Node {
+                column_gap: Val::Px(20.0),
+                ..default()
+            })
+            .with_children(|row| {
+                for (index, character) in characters.iter().enumerate() {
+                    let is_unlocked = unlocked.contains(&character.id.as_str());
+                    spawn_character_card(row, index, character, is_unlocked);
+                }
+            });
+            root.spawn(Node {
+                column_gap: Val::Px(20.0),
+                ..default()
+            })
+            .with_children(|buttons| {
+                buttons.spawn((
+                    CharacterBackButton,
+                    button(),
+                    children![TextBundle::new("Back", 26.0, Color::WHITE)],
+                ));
+                buttons.spawn((
+                    CharacterStartButton,
+                    button(),
+                    children![TextBundle::new("Fall In", 26.0, Color::WHITE)],
+                ));
+            });
+            root.spawn(TextBundle::new(
+                "1-4 / click to choose, Enter / Start to fall in, Escape to go back",
+                14.0,
+                GREY,
+            ));
+        });
+}
+
+/// Gold border on the selected character, white on hover.
+pub fn update_character_cards(
+    characters: Res<CharactersLibrary>,
+    selected: Res<SelectedCharacter>,
+    mut cards: Query<(&CharacterCard, &Interaction, &mut BorderColor)>,
+) {
+    for (card, interaction, mut border) in &mut cards {
+        let is_selected = characters
+            .get(card.index)
+            .is_some_and(|character| character.id == selected.0);
+        let color = match (is_selected, interaction, card.unlocked) {
+            (true, _, _) => GOLD,
+            (false, _, false) => DARK_GREY,
+            (false, Interaction::None, true) => GREY,
+            (false, _, true) => Color::WHITE,
+        };
+        if *border != BorderColor::all(color) {
+            *border = BorderColor::all(color);
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn update_character_button_interaction(
+    mut query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (
+            Changed<Interaction>,
+            Or<(With<CharacterStartButton>, With<CharacterBackButton>)>,
+        ),
+    >,
+) {
+    for (interaction, mut bg) in &mut query {
+        *bg = BackgroundColor(match interaction {
+            Interaction::Hovered | Interaction::Pressed => BUTTON_BG_HOVER,
+            Interaction::None => BUTTON_BG,
+        });
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+fn spawn_character_card(
+    row: &mut RelatedSpawnerCommands<ChildOf>,
+    index: usize,
+    character: &CharacterDefinition,
+    unlocked: bool,
+) {
+    let (background, name_color) = if unlocked {
+        (CARD_BG, character.sprites.tint())
+    } else {
+        (CARD_BG_LOCKED, DARK_GREY)
+    };
+    row.spawn((
+        CharacterCard { index, unlocked },
+        Button,
+        Node {
+            width: Val::Px(260.0),
+            min_height: Val::Px(320.0),
+            flex_direction: FlexDirection::Column,
+            padding: UiRect::all(Val::Px(18.0)),
+            border: UiRect::all(Val::Px(3.0)),
+            border_radius: BorderRadius::all(Val::Px(12.0)),
+            row_gap: Val::Px(10.0),
+            ..default()
+        },
+        BackgroundColor(background),
+        BorderColor::all(GREY),
+    ))
+    .with_children(|card| {
+        card.spawn(TextBundle::new(
+            format!("{}. {}", index + 1, character.name),
+            26.0,
+            name_color,
+        ));
+        if !unlocked {
+            card.spawn(TextBundle::new(
+                "Locked, unlock it in the profile",
+                16.0,
+                GREY,
+            ));
+            return;
+        }
+        card.spawn(TextBundle::new(character.description.clone(), 15.0, GREY));
+        card.spawn(TextBundle::new(character.passive.description(), 18.0, GOLD));
+        for (kind, value) in &character.stat_bonuses {
+            let color = if *value >= 0.0 { GREEN } else { RED };
+            card.spawn(TextBundle::new(stat_bonus_text(*kind, *value), 16.0, color));
+        }
+        let weapons = if character.starting_weapons.is_empty() {
+            "Profile starting weapon".to_string()
+        } else {
+            character.starting_weapons.join(", ").replace('_', " ")
+        };
+        card.spawn(TextBundle::new(
+            format!("Weapons: {}", weapons),
+            16.0,
+            Color::WHITE,
+        ));
+    });
+}
+
+fn stat_bonus_text(kind: StatKind, value: f32) -> String {
+    let (_, name, _) = DisplayStatKind::from(kind).get_display_info();
+    match kind {
+        StatKind::MaxHealth => format!("{:+.0} max HP", value),
+        kind if kind.is_count() => format!("{:+.0} {}", value, name.to_lowercase()),
+        _ => format!("{:+.0}% {}", value * 100.0, name.to_lowercase()),
+    }
+}
+
+fn button() -> impl Bundle {
+    (
+        Button,
+        Node {
+            width: Val::Px(240.0),
+            height: Val::Px(56.0),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            border: UiRect::all(Val::Px(2.0)),
+            border_radius: BorderRadius::all(Val::Px(8.0)),
+            ..default()
+        },
+        BackgroundColor(BUTTON_BG),
+        BorderColor::all(GREY),
+    )
+}
@@ -0,0 +1,80 @@
+use crate::systems::animations::animation::SpriteAnimation;
+use crate::systems::game::GameState;
+use crate::systems::input::resources::ActionState;
+use crate::systems::states::characterselect::components::{
+    CharacterBackButton, CharacterCard, CharacterStartButton,
+};
+use crate::systems::states::profile::resources::{Profile, UnlockTree};
+use crate::systems::states::waves::player::characters::{CharactersLibrary, SelectedCharacter};
+use crate::systems::states::waves::player::resources::PlayerAnimations;
+use bevy::prelude::*;
+
+/// A character picked in an earlier session may not be unlocked in the current profile.
+pub fn ensure_character_unlocked(
+    profile: Res<Profile>,
+    tree: Res<UnlockTree>,
+    mut selected: ResMut<SelectedCharacter>,
+) {
+    let characters = profile.characters(&tree);
+    if !characters.contains(&selected.0.as_str()) {
+        selected.set_if_neq(SelectedCharacter::default());
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn handle_character_select(
+    actions: Res<ActionState>,
+    characters: Res<CharactersLibrary>,
+    card_query: Query<(&Interaction, &CharacterCard), Changed<Interaction>>,
+    start_query: Query<&Interaction, (Changed<Interaction>, With<CharacterStartButton>)>,
+    back_query: Query<&Interaction, (Changed<Interaction>, With<CharacterBackButton>)>,
+    unlocked_query: Query<&CharacterCard>,
+    mut selected: ResMut<SelectedCharacter>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    // Cards are picked by clicking them or with the slot keys, like upgrade cards
+    let clicked = card_query
+        .iter()
+        .find(|(interaction, _)| **interaction == Interaction::Pressed)
+        .map(|(_, card)| card.index);
+    let slot = actions.card_select.iter().position(|pressed| *pressed);
+    if let Some(index) = clicked.or(slot)
+        && unlocked_query
+            .iter()
+            .any(|card| card.index == index && card.unlocked)
+        && let Some(character) = characters.get(index)
+    {
+        selected.set_if_neq(SelectedCharacter(character.id.clone()));
+    }
+
+    let start_clicked = start_query.iter().any(|i| *i == Interaction::Pressed);
+    if start_clicked || actions.start_next_wave {
+        next_state.set(GameState::InWave);
+        return;
+    }
+    let back_clicked = back_query.iter().any(|i| *i == Interaction::Pressed);
+    if back_clicked || actions.pause {
+        next_state.set(GameState::MainMenu);
+    }
+}
+
+/// Swaps `PlayerAnimations` for the spritesheets of the selected character.
+pub fn load_character_animations(
+    mut commands: Commands,
+    selected: Res<SelectedCharacter>,
+    characters: Option<Res<CharactersLibrary>>,
+    asset_server: Res<AssetServer>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut animations: ResMut<Assets<SpriteAnimation>>,
+) {
+    let Some(characters) = characters else {
+        return;
+    };
+    let character = characters.get_or_default(&selected.0);
+    commands.insert_resource(PlayerAnimations::load(
+        &asset_server,
+        &mut layouts,
+        &mut animations,
+        &character.sprites,
+    ));
+}
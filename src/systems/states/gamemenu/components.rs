@@ -16,6 +16,10 @@ pub struct AnimatedBorder {
     pub phase: f32,
 }
 
+/// Only spawned when a saved run can be resumed
+#[derive(Component)]
+pub struct ContinueButton;
+
 #[derive(Component)]
 pub struct StartButton;
 
@@ -1,9 +1,12 @@
 use crate::systems::game::GameState;
+use crate::systems::save::load_saved_run;
 use crate::systems::states::gamemenu::renderer::{despawn_main_menu, spawn_main_menu};
 use crate::systems::states::gamemenu::systems::{
     animate_button_borders, animate_divider, animate_title_colors, handle_menu_input,
     play_background_audio, stop_background_audio,
 };
+use crate::systems::states::profile::resources::ProfileMenu;
+use crate::systems::states::settings::resources::SettingsMenu;
 use bevy::prelude::*;
 
 pub struct MainMenuPlugin;
@@ -12,7 +15,7 @@ impl Plugin for MainMenuPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
             OnEnter(GameState::MainMenu),
-            (spawn_main_menu, play_background_audio),
+            (spawn_main_menu.after(load_saved_run), play_background_audio),
         )
         .add_systems(
             OnExit(GameState::MainMenu),
@@ -21,7 +24,9 @@ impl Plugin for MainMenuPlugin {
         .add_systems(
             Update,
             (
-                handle_menu_input,
+                // Enter / Space must not start a run from behind the settings or profile overlay
+                handle_menu_input
+                    .run_if(in_state(SettingsMenu::Closed).and(in_state(ProfileMenu::Closed))),
                 animate_title_colors,
                 animate_divider,
                 animate_button_borders,
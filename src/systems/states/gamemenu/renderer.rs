@@ -1,6 +1,9 @@
+use crate::systems::save::SavedRun;
 use crate::systems::states::gamemenu::components::{
-    AnimatedBorder, DividerSegment, MainMenuUI, QuitButton, StartButton, TitleWord,
+    AnimatedBorder, ContinueButton, DividerSegment, MainMenuUI, QuitButton, StartButton, TitleWord,
 };
+use crate::systems::states::profile::components::ProfileButton;
+use crate::systems::states::settings::components::SettingsButton;
 use bevy::color::palettes::css::*;
 use bevy::ecs::relationship::RelatedSpawnerCommands;
 use bevy::prelude::*;
@@ -34,7 +37,7 @@ pub fn palette_color(phase: f32) -> Color {
     )
 }
 
-pub fn spawn_main_menu(mut commands: Commands) {
+pub fn spawn_main_menu(mut commands: Commands, saved_run: Res<SavedRun>) {
     commands
         .spawn((
             MainMenuUI,
@@ -117,7 +120,7 @@ pub fn spawn_main_menu(mut commands: Commands) {
         ))
         .with_children(|root| {
             spawn_divider(root);
-            spawn_buttons(root);
+            spawn_buttons(root, saved_run.0.is_some());
             spawn_hint(root);
         });
 }
@@ -153,16 +156,43 @@ fn spawn_divider(root: &mut RelatedSpawnerCommands<ChildOf>) {
     });
 }
 
-fn spawn_buttons(root: &mut RelatedSpawnerCommands<ChildOf>) {
-    root.spawn((
-        Node {
-            flex_direction: FlexDirection::Column,
-            align_items: AlignItems::Center,
-            row_gap: Val::Px(18.0),
-            ..default()
-        },
-        children![
-            (
+fn spawn_buttons(root: &mut RelatedSpawnerCommands<ChildOf>, has_save: bool) {
+    root.spawn((Node {
+        flex_direction: FlexDirection::Column,
+        align_items: AlignItems::Center,
+        row_gap: Val::Px(18.0),
+        ..default()
+    },))
+        .with_children(|column| {
+            if has_save {
+                column.spawn((
+                    ContinueButton,
+                    AnimatedBorder {
+                        phase: -std::f32::consts::FRAC_PI_2,
+                    },
+                    Button,
+                    Node {
+                        width: Val::Px(340.0),
+                        height: Val::Px(68.0),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        border: UiRect::all(Val::Px(2.0)),
+                        border_radius: BorderRadius::all(Val::Px(6.0)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0.14, 0.06, 0.28, 0.92)),
+                    BorderColor::all(Color::Srgba(GOLDENROD)),
+                    children![(
+                        Text::new("> CONTINUE <"),
+                        TextFont {
+                            font_size: 28.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                    )],
+                ));
+            }
+            column.spawn((
                 StartButton,
                 AnimatedBorder { phase: 0.0 },
                 Button,
@@ -190,8 +220,60 @@ fn spawn_buttons(root: &mut RelatedSpawnerCommands<ChildOf>) {
                     },
                     TextColor(Color::WHITE),
                 )],
-            ),
-            (
+            ));
+            column.spawn((
+                ProfileButton,
+                AnimatedBorder {
+                    phase: std::f32::consts::FRAC_PI_4,
+                },
+                Button,
+                Node {
+                    width: Val::Px(340.0),
+                    height: Val::Px(68.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    border: UiRect::all(Val::Px(2.0)),
+                    border_radius: BorderRadius::all(Val::Px(6.0)),
+                    ..default()
+                },
+                BackgroundColor(Color::srgba(0.14, 0.06, 0.28, 0.92)),
+                BorderColor::all(Color::Srgba(GOLD)),
+                children![(
+                    Text::new("* PROFILE *"),
+                    TextFont {
+                        font_size: 28.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                )],
+            ));
+            column.spawn((
+                SettingsButton,
+                AnimatedBorder {
+                    phase: std::f32::consts::FRAC_PI_2,
+                },
+                Button,
+                Node {
+                    width: Val::Px(340.0),
+                    height: Val::Px(68.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    border: UiRect::all(Val::Px(2.0)),
+                    border_radius: BorderRadius::all(Val::Px(6.0)),
+                    ..default()
+                },
+                BackgroundColor(Color::srgba(0.14, 0.06, 0.28, 0.92)),
+                BorderColor::all(Color::Srgba(DEEP_SKY_BLUE)),
+                children![(
+                    Text::new("~ SETTINGS ~"),
+                    TextFont {
+                        font_size: 28.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                )],
+            ));
+            column.spawn((
                 QuitButton,
                 AnimatedBorder {
                     phase: std::f32::consts::PI, // starts at opposite side of palette
@@ -221,9 +303,8 @@ fn spawn_buttons(root: &mut RelatedSpawnerCommands<ChildOf>) {
                     },
                     TextColor(Color::WHITE),
                 )],
-            )
-        ],
-    ));
+            ));
+        });
 }
 
 fn spawn_hint(root: &mut RelatedSpawnerCommands<ChildOf>) {
@@ -1,10 +1,12 @@
-use crate::systems::game::{GameState, MarkedForDespawn};
+use crate::systems::game::{GameState, MarkedForDespawn, MasterVolume, MusicVolume};
 use crate::systems::input::resources::ActionState;
+use crate::systems::save::{ResumeRun, SavedRun};
 use crate::systems::states::gamemenu::components::{
-    AnimatedBorder, DividerSegment, QuitButton, StartButton, TitleWord,
+    AnimatedBorder, ContinueButton, DividerSegment, QuitButton, StartButton, TitleWord,
 };
 use crate::systems::states::gamemenu::renderer::{palette_color, DIVIDER_SEGMENTS};
 use crate::systems::states::waves::components::BackgroundMusic;
+use crate::systems::states::waves::player::characters::SelectedCharacter;
 use bevy::app::AppExit;
 use bevy::prelude::*;
 
@@ -13,12 +15,17 @@ const BORDER_CYCLE_SPEED: f32 = 1.8;
 const DIVIDER_WAVE_SPEED: f32 = 2.2;
 const DIVIDER_WAVE_WIDTH: f32 = 2.5;
 
-pub fn play_background_audio(asset_server: Res<AssetServer>, mut commands: Commands) {
+pub fn play_background_audio(
+    asset_server: Res<AssetServer>,
+    master_volume: Res<MasterVolume>,
+    music_volume: Res<MusicVolume>,
+    mut commands: Commands,
+) {
     let audio1 = asset_server.load("musics/intro.ogg".to_string());
     commands.spawn((
         BackgroundMusic,
         AudioPlayer::new(audio1),
-        PlaybackSettings::LOOP,
+        PlaybackSettings::LOOP.with_volume(master_volume.mix(music_volume.0)),
     ));
 }
 
@@ -31,18 +38,31 @@ pub fn stop_background_audio(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn handle_menu_input(
+    mut commands: Commands,
     actions: Res<ActionState>,
+    saved_run: Res<SavedRun>,
+    continue_query: Query<&Interaction, (Changed<Interaction>, With<ContinueButton>)>,
     start_query: Query<&Interaction, (Changed<Interaction>, With<StartButton>)>,
     quit_query: Query<&Interaction, (Changed<Interaction>, With<QuitButton>)>,
     mut next_state: ResMut<NextState<GameState>>,
     mut exit: MessageWriter<AppExit>,
 ) {
+    let continue_clicked = continue_query.iter().any(|i| *i == Interaction::Pressed);
     let start_clicked = start_query.iter().any(|i| *i == Interaction::Pressed);
     let quit_clicked = quit_query.iter().any(|i| *i == Interaction::Pressed);
 
+    // A resumed run picks up at the shop it was saved at
+    if continue_clicked && let Some(save) = &saved_run.0 {
+        // Picked before leaving the menu so the character's animations are ready for the restore
+        commands.insert_resource(SelectedCharacter(save.character.clone()));
+        commands.insert_resource(ResumeRun(save.clone()));
+        next_state.set(GameState::Shopping);
+        return;
+    }
     if start_clicked || actions.start_next_wave {
-        next_state.set(GameState::InWave);
+        next_state.set(GameState::CharacterSelect);
         return;
     }
     if quit_clicked {
@@ -1,14 +1,18 @@
-use crate::systems::game::{GameOverStats, GameState};
+use crate::systems::game::{GameOverStats, GameRng, GameState};
 use crate::systems::states::gameover::components::{GameOverUI, RestartButton};
+use crate::systems::states::profile::resources::run_reward;
 use bevy::prelude::*;
 
 const BG: Color = Color::srgba(0.0, 0.0, 0.0, 0.88);
 const STATS_BG: Color = Color::srgb(0.07, 0.07, 0.12);
 
-pub fn spawn_game_over_ui(mut commands: Commands, stats: Res<GameOverStats>) {
+pub fn spawn_game_over_ui(mut commands: Commands, stats: Res<GameOverStats>, rng: Res<GameRng>) {
     let wave_text = stats.wave_reached.to_string();
     let level_text = stats.level_reached.to_string();
     let xp_text = format!("{} XP", stats.experience_total);
+    let kills_text = stats.enemies_killed.to_string();
+    let reward_text = format!("+{}", run_reward(&stats));
+    let seed_text = rng.seed().to_string();
 
     commands.spawn((
         GameOverUI,
@@ -69,6 +73,9 @@ pub fn spawn_game_over_ui(mut commands: Commands, stats: Res<GameOverStats>) {
                     stat_row("Wave Reached", wave_text, Color::srgb(1.0, 0.78, 0.2)),
                     stat_row("Level Reached", level_text, Color::srgb(0.4, 0.8, 1.0)),
                     stat_row("Total XP", xp_text, Color::srgb(0.7, 0.5, 1.0)),
+                    stat_row("Enemies Killed", kills_text, Color::srgb(0.9, 0.4, 0.4)),
+                    stat_row("Meta Currency", reward_text, Color::srgb(1.0, 0.78, 0.2)),
+                    stat_row("Seed", seed_text, Color::srgb(0.6, 0.6, 0.75)),
                 ],
             ),
             // ── Restart button ───────────────────────────────────────────────
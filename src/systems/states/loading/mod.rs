@@ -0,0 +1,4 @@
+pub mod loader;
+pub mod plugin;
+pub mod resources;
+pub mod systems;
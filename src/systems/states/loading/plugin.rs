@@ -0,0 +1,29 @@
+use crate::systems::game::GameState;
+use crate::systems::states::loading::loader::RonAssetLoader;
+use crate::systems::states::loading::systems::{finish_loading, start_loading};
+use crate::systems::states::waves::enemy::kinds::EnemyDefinition;
+use crate::systems::states::waves::player::characters::CharacterDefinition;
+use crate::systems::states::waves::script::WaveScript;
+use crate::systems::states::waves::weapons::kinds::WeaponDefinition;
+use bevy::prelude::*;
+
+/// Loads the data-driven definitions (`assets/enemies/*.enemy.ron`, `assets/weapons/*.weapon.ron`,
+/// `assets/characters/*.character.ron`, `assets/waves/*.wave.ron`) before the main menu shows up.
+pub struct LoadingPlugin;
+
+impl Plugin for LoadingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<EnemyDefinition>()
+            .init_asset::<WeaponDefinition>()
+            .init_asset::<CharacterDefinition>()
+            .init_asset::<WaveScript>()
+            .register_asset_loader(RonAssetLoader::<EnemyDefinition>::new(&["enemy.ron"]))
+            .register_asset_loader(RonAssetLoader::<WeaponDefinition>::new(&["weapon.ron"]))
+            .register_asset_loader(RonAssetLoader::<CharacterDefinition>::new(&[
+                "character.ron",
+            ]))
+            .register_asset_loader(RonAssetLoader::<WaveScript>::new(&["wave.ron"]))
+            .add_systems(OnEnter(GameState::Loading), start_loading)
+            .add_systems(Update, finish_loading.run_if(in_state(GameState::Loading)));
+    }
+}
@@ -0,0 +1,11 @@
+use bevy::asset::{Handle, LoadedFolder};
+use bevy::prelude::Resource;
+
+/// Folders of game data requested when entering `GameState::Loading`.
+#[derive(Resource)]
+pub struct GameDataFolders {
+    pub enemies: Handle<LoadedFolder>,
+    pub weapons: Handle<LoadedFolder>,
+    pub characters: Handle<LoadedFolder>,
+    pub waves: Handle<LoadedFolder>,
+}
@@ -0,0 +1,121 @@
+use crate::systems::game::GameState;
+use crate::systems::states::loading::resources::GameDataFolders;
+use crate::systems::states::waves::enemy::kinds::EnemyDefinition;
+use crate::systems::states::waves::enemy::resources::EnemyRegistry;
+use crate::systems::states::waves::player::characters::{CharacterDefinition, CharactersLibrary};
+use crate::systems::states::waves::script::{WaveScript, WaveScripts};
+use crate::systems::states::waves::weapons::kinds::WeaponDefinition;
+use crate::systems::states::waves::weapons::resources::WeaponsLibrary;
+use bevy::asset::{LoadedFolder, RecursiveDependencyLoadState};
+use bevy::prelude::*;
+
+pub const ENEMIES_FOLDER: &str = "enemies";
+pub const WEAPONS_FOLDER: &str = "weapons";
+pub const CHARACTERS_FOLDER: &str = "characters";
+pub const WAVES_FOLDER: &str = "waves";
+
+pub fn start_loading(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(GameDataFolders {
+        enemies: asset_server.load_folder(ENEMIES_FOLDER),
+        weapons: asset_server.load_folder(WEAPONS_FOLDER),
+        characters: asset_server.load_folder(CHARACTERS_FOLDER),
+        waves: asset_server.load_folder(WAVES_FOLDER),
+    });
+}
+
+/// Waits for every data folder, then builds the registries and moves on to the main menu
+#[allow(clippy::too_many_arguments)]
+pub fn finish_loading(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    folders: Res<GameDataFolders>,
+    loaded_folders: Res<Assets<LoadedFolder>>,
+    enemies: Res<Assets<EnemyDefinition>>,
+    weapons: Res<Assets<WeaponDefinition>>,
+    characters: Res<Assets<CharacterDefinition>>,
+    waves: Res<Assets<WaveScript>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let folders_done = [
+        &folders.enemies,
+        &folders.weapons,
+        &folders.characters,
+        &folders.waves,
+    ]
+    .into_iter()
+    .all(|folder| {
+        matches!(
+            asset_server.recursive_dependency_load_state(folder),
+            RecursiveDependencyLoadState::Loaded | RecursiveDependencyLoadState::Failed(_)
+        )
+    });
+    if !folders_done {
+        return;
+    }
+
+    let enemies = collect_folder(
+        &asset_server,
+        ENEMIES_FOLDER,
+        &folders.enemies,
+        &loaded_folders,
+        &enemies,
+    );
+    let weapons = collect_folder(
+        &asset_server,
+        WEAPONS_FOLDER,
+        &folders.weapons,
+        &loaded_folders,
+        &weapons,
+    );
+    let characters = collect_folder(
+        &asset_server,
+        CHARACTERS_FOLDER,
+        &folders.characters,
+        &loaded_folders,
+        &characters,
+    );
+    let waves = collect_folder(
+        &asset_server,
+        WAVES_FOLDER,
+        &folders.waves,
+        &loaded_folders,
+        &waves,
+    );
+    let registry = EnemyRegistry::new(enemies);
+    commands.insert_resource(WaveScripts::new(waves, &registry));
+    commands.insert_resource(registry);
+    commands.insert_resource(WeaponsLibrary::new(weapons));
+    commands.insert_resource(CharactersLibrary::new(characters));
+    commands.remove_resource::<GameDataFolders>();
+    next_state.set(GameState::MainMenu);
+}
+
+// helper functions
+
+/// Clones every `A` of a loaded folder. A broken file shouldn't block the game,
+/// so failures are only logged and the remaining definitions are still used.
+fn collect_folder<A: Asset + Clone>(
+    asset_server: &AssetServer,
+    name: &str,
+    folder: &Handle<LoadedFolder>,
+    loaded_folders: &Assets<LoadedFolder>,
+    assets: &Assets<A>,
+) -> Vec<A> {
+    if let RecursiveDependencyLoadState::Failed(err) =
+        asset_server.recursive_dependency_load_state(folder)
+    {
+        error!("Some files in assets/{name} failed to load: {err}");
+    }
+    let definitions: Vec<A> = loaded_folders
+        .get(folder)
+        .map(|folder| folder.handles.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|handle| handle.clone().try_typed::<A>().ok())
+        .filter_map(|handle| assets.get(&handle).cloned())
+        .collect();
+    if definitions.is_empty() {
+        error!("No definitions found in assets/{name}");
+    }
+    definitions
+}
@@ -1,5 +1,10 @@
+pub mod characterselect;
 pub mod gamemenu;
 pub mod gameover;
+pub mod loading;
+pub mod pause;
+pub mod profile;
+pub mod settings;
 pub mod shopping;
 pub mod upgrades;
 pub mod waves;
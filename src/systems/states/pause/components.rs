@@ -0,0 +1,10 @@
+use bevy::prelude::Component;
+
+#[derive(Component)]
+pub struct PauseMenuUI;
+
+#[derive(Component)]
+pub struct ResumeButton;
+
+#[derive(Component)]
+pub struct AbandonRunButton;
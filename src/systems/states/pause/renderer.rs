@@ -0,0 +1,88 @@
+use crate::systems::game::{TextBundle, WaveState};
+use crate::systems::states::pause::components::{AbandonRunButton, PauseMenuUI, ResumeButton};
+use crate::systems::states::settings::components::SettingsButton;
+use bevy::prelude::*;
+
+const BG: Color = Color::srgba(0.0, 0.0, 0.0, 0.75);
+const BUTTON_BG: Color = Color::srgb(0.15, 0.15, 0.25);
+const BUTTON_BG_HOVER: Color = Color::srgb(0.25, 0.25, 0.4);
+const GREY: Color = Color::srgb(0.55, 0.55, 0.65);
+
+pub fn spawn_pause_menu(mut commands: Commands) {
+    commands.spawn((
+        PauseMenuUI,
+        DespawnOnExit(WaveState::Paused),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(20.0),
+            ..default()
+        },
+        BackgroundColor(BG),
+        // Above the HUD and the dying overlay
+        ZIndex(300),
+        children![
+            (
+                TextBundle::new("PAUSED", 64.0, Color::WHITE),
+                Node {
+                    margin: UiRect::bottom(Val::Px(20.0)),
+                    ..default()
+                },
+            ),
+            (
+                ResumeButton,
+                menu_button("Resume", Color::srgb(0.3, 0.8, 0.3))
+            ),
+            (SettingsButton, menu_button("Settings", GREY)),
+            (
+                AbandonRunButton,
+                menu_button("Abandon Run", Color::srgb(0.9, 0.3, 0.3))
+            ),
+            TextBundle::new("Escape / Start to resume", 14.0, GREY),
+        ],
+    ));
+}
+
+#[allow(clippy::type_complexity)]
+pub fn update_pause_button_interaction(
+    mut query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (
+            Changed<Interaction>,
+            Or<(
+                With<ResumeButton>,
+                With<SettingsButton>,
+                With<AbandonRunButton>,
+            )>,
+        ),
+    >,
+) {
+    for (interaction, mut bg) in &mut query {
+        *bg = BackgroundColor(match interaction {
+            Interaction::Hovered | Interaction::Pressed => BUTTON_BG_HOVER,
+            Interaction::None => BUTTON_BG,
+        });
+    }
+}
+
+fn menu_button(label: &str, border: Color) -> impl Bundle {
+    (
+        Button,
+        Node {
+            width: Val::Px(320.0),
+            height: Val::Px(60.0),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            border: UiRect::all(Val::Px(3.0)),
+            border_radius: BorderRadius::all(Val::Px(8.0)),
+            ..default()
+        },
+        BackgroundColor(BUTTON_BG),
+        BorderColor::all(border),
+        children![TextBundle::new(label, 26.0, Color::WHITE)],
+    )
+}
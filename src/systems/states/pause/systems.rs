@@ -0,0 +1,85 @@
+use crate::systems::game::{GameOverStats, GameState, WaveState};
+use crate::systems::input::resources::ActionState;
+use crate::systems::save::RunSave;
+use crate::systems::states::pause::components::{AbandonRunButton, ResumeButton};
+use crate::systems::states::profile::resources::Profile;
+use crate::systems::states::profile::systems::save_finished_run;
+use crate::systems::states::settings::components::SettingsUI;
+use crate::systems::states::waves::components::BackgroundMusic;
+use crate::systems::states::waves::player::components::Player;
+use crate::systems::states::waves::player::experience::PlayerExperience;
+use crate::systems::states::waves::resources::WaveManager;
+use bevy::prelude::*;
+use bevy::window::WindowFocused;
+
+/// Escape / Start toggles the pause, unless the settings overlay is the one being closed
+pub fn toggle_pause(
+    actions: Res<ActionState>,
+    state: Res<State<WaveState>>,
+    settings_query: Query<(), With<SettingsUI>>,
+    mut next_state: ResMut<NextState<WaveState>>,
+) {
+    if !actions.pause || !settings_query.is_empty() {
+        return;
+    }
+    next_state.set(match state.get() {
+        WaveState::Running => WaveState::Paused,
+        WaveState::Paused => WaveState::Running,
+    });
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn handle_pause_menu(
+    resume_query: Query<&Interaction, (Changed<Interaction>, With<ResumeButton>)>,
+    abandon_query: Query<&Interaction, (Changed<Interaction>, With<AbandonRunButton>)>,
+    wave_manager: Res<WaveManager>,
+    mut game_over_stats: ResMut<GameOverStats>,
+    mut profile: ResMut<Profile>,
+    player_query: Query<&PlayerExperience, With<Player>>,
+    mut next_wave_state: ResMut<NextState<WaveState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if resume_query.iter().any(|i| *i == Interaction::Pressed) {
+        next_wave_state.set(WaveState::Running);
+    }
+    // The run is reset by `game::reset_run` when entering the main menu, and can't be continued
+    if abandon_query.iter().any(|i| *i == Interaction::Pressed) {
+        // An abandoned run still counts for the profile, like a game over
+        if let Ok(xp) = player_query.single() {
+            game_over_stats.end_run(wave_manager.wave, xp);
+        }
+        save_finished_run(&mut profile, &game_over_stats);
+        RunSave::delete();
+        next_state.set(GameState::MainMenu);
+    }
+}
+
+/// Freezes `Time<Virtual>`, so every timer and animation driven by `Time` stops with the wave
+pub fn pause_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+pub fn resume_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+pub fn pause_background_music(audio_query: Query<&AudioSink, With<BackgroundMusic>>) {
+    for sink in &audio_query {
+        sink.pause();
+    }
+}
+
+pub fn resume_background_music(audio_query: Query<&AudioSink, With<BackgroundMusic>>) {
+    for sink in &audio_query {
+        sink.play();
+    }
+}
+
+pub fn pause_on_focus_lost(
+    mut focus_events: MessageReader<WindowFocused>,
+    mut next_state: ResMut<NextState<WaveState>>,
+) {
+    if focus_events.read().any(|event| !event.focused) {
+        next_state.set(WaveState::Paused);
+    }
+}
@@ -0,0 +1,30 @@
+use bevy::prelude::Component;
+
+#[derive(Component)]
+pub struct ProfileUI;
+
+/// Opens the profile screen from the main menu
+#[derive(Component)]
+pub struct ProfileButton;
+
+#[derive(Component)]
+pub struct ProfileBackButton;
+
+/// Meta-currency left to spend, goes down as unlocks are bought
+#[derive(Component)]
+pub struct MetaCurrencyText;
+
+/// Cycles through the unlocked starting weapons
+#[derive(Component)]
+pub struct StartingWeaponButton;
+
+#[derive(Component)]
+pub struct StartingWeaponText;
+
+/// Parent of the unlock rows, redrawn whenever the profile changes
+#[derive(Component)]
+pub struct UnlockList;
+
+/// Buys the unlock at this index of the `UnlockTree`
+#[derive(Component)]
+pub struct UnlockButton(pub usize);
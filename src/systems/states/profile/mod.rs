@@ -0,0 +1,5 @@
+pub mod components;
+pub mod plugin;
+pub mod renderer;
+pub mod resources;
+pub mod systems;
@@ -0,0 +1,47 @@
+use crate::systems::game::GameState;
+use crate::systems::states::profile::renderer::{
+    spawn_profile_menu, update_profile_button_interaction, update_profile_texts, update_unlock_list,
+};
+use crate::systems::states::profile::resources::{Profile, ProfileMenu, UnlockTree};
+use crate::systems::states::profile::systems::{
+    apply_unlocked_cards, handle_profile_input, open_profile, record_finished_run, save_profile,
+};
+use bevy::prelude::*;
+
+/// Meta-progression kept between runs. Loads the profile at startup, records every run that
+/// ends (game over or abandoned) into it and owns the profile overlay opened from the main
+/// menu, where the meta-currency is spent on the `UnlockTree`.
+pub struct ProfilePlugin;
+
+impl Plugin for ProfilePlugin {
+    fn build(&self, app: &mut App) {
+        let tree = UnlockTree::default();
+        app.init_state::<ProfileMenu>()
+            .insert_resource(Profile::load(&tree))
+            .insert_resource(tree)
+            .add_systems(OnEnter(GameState::GameOver), record_finished_run)
+            .add_systems(
+                Update,
+                apply_unlocked_cards.run_if(resource_changed::<Profile>),
+            )
+            // ------------------------  Profile screen -------------------------------- //
+            .add_systems(OnEnter(ProfileMenu::Open), spawn_profile_menu)
+            .add_systems(OnExit(ProfileMenu::Open), save_profile)
+            .add_systems(
+                Update,
+                open_profile
+                    .run_if(in_state(GameState::MainMenu).and(in_state(ProfileMenu::Closed))),
+            )
+            .add_systems(
+                Update,
+                (
+                    handle_profile_input,
+                    update_profile_texts,
+                    update_unlock_list,
+                    update_profile_button_interaction,
+                )
+                    .chain()
+                    .run_if(in_state(ProfileMenu::Open)),
+            );
+    }
+}
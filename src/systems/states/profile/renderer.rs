@@ -0,0 +1,267 @@
+use crate::systems::game::TextBundle;
+use crate::systems::states::profile::components::*;
+use crate::systems::states::profile::resources::{Profile, ProfileMenu, Unlock, UnlockTree};
+use bevy::ecs::relationship::RelatedSpawnerCommands;
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+
+const BG: Color = Color::srgba(0.0, 0.0, 0.0, 0.92);
+const PANEL_BG: Color = Color::srgb(0.07, 0.07, 0.12);
+const BUTTON_BG: Color = Color::srgb(0.15, 0.15, 0.25);
+const BUTTON_BG_HOVER: Color = Color::srgb(0.25, 0.25, 0.4);
+const GREY: Color = Color::srgb(0.55, 0.55, 0.65);
+const GOLD: Color = Color::srgb(1.0, 0.78, 0.2);
+const OWNED: Color = Color::srgb(0.4, 0.85, 0.4);
+
+pub fn spawn_profile_menu(mut commands: Commands, profile: Res<Profile>) {
+    commands.spawn((
+        ProfileUI,
+        DespawnOnExit(ProfileMenu::Open),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(20.0),
+            ..default()
+        },
+        BackgroundColor(BG),
+        GlobalZIndex(500),
+        // Keeps the buttons of the main menu underneath from reacting to clicks
+        FocusPolicy::Block,
+        children![
+            TextBundle::new("PROFILE", 52.0, Color::WHITE),
+            (
+                Node {
+                    column_gap: Val::Px(24.0),
+                    ..default()
+                },
+                children![
+                    // ── Lifetime statistics ──────────────────────────────────
+                    (
+                        panel(),
+                        children![
+                            TextBundle::new("LIFETIME", 18.0, GREY),
+                            stat_row("Runs", profile.runs.to_string()),
+                            stat_row("Best Wave", profile.best_wave.to_string()),
+                            stat_row("Enemies Killed", profile.lifetime_kills.to_string()),
+                            stat_row("Total XP", profile.total_experience.to_string()),
+                            (
+                                Node {
+                                    width: Val::Px(280.0),
+                                    justify_content: JustifyContent::SpaceBetween,
+                                    ..default()
+                                },
+                                children![
+                                    TextBundle::new("Meta Currency", 20.0, GREY),
+                                    (MetaCurrencyText, TextBundle::new("", 22.0, GOLD)),
+                                ],
+                            ),
+                            TextBundle::new("Starting weapon", 18.0, GREY),
+                            (
+                                StartingWeaponButton,
+                                button(280.0, 44.0),
+                                children![(
+                                    StartingWeaponText,
+                                    TextBundle::new("", 20.0, Color::WHITE)
+                                )],
+                            ),
+                        ],
+                    ),
+                    // ── Unlock tree ──────────────────────────────────────────
+                    (
+                        panel(),
+                        children![
+                            TextBundle::new("UNLOCKS", 18.0, GREY),
+                            (
+                                UnlockList,
+                                Node {
+                                    flex_direction: FlexDirection::Column,
+                                    row_gap: Val::Px(8.0),
+                                    ..default()
+                                },
+                            ),
+                        ],
+                    ),
+                ],
+            ),
+            (
+                ProfileBackButton,
+                button(240.0, 56.0),
+                children![TextBundle::new("Back", 26.0, Color::WHITE)],
+            ),
+            TextBundle::new("Escape / Start to go back", 14.0, GREY),
+        ],
+    ));
+}
+
+pub fn update_profile_texts(
+    profile: Res<Profile>,
+    mut currency_text: Single<&mut Text, With<MetaCurrencyText>>,
+    mut weapon_text: Single<&mut Text, (With<StartingWeaponText>, Without<MetaCurrencyText>)>,
+) {
+    let currency = profile.meta_currency.to_string();
+    if currency_text.0 != currency {
+        currency_text.0 = currency;
+    }
+    let weapon = profile.starting_weapon.replace('_', " ");
+    if weapon_text.0 != weapon {
+        weapon_text.0 = weapon;
+    }
+}
+
+/// Rebuilds the unlock rows when the list is spawned and after every purchase.
+pub fn update_unlock_list(
+    mut commands: Commands,
+    profile: Res<Profile>,
+    tree: Res<UnlockTree>,
+    list: Single<(Entity, Ref<UnlockList>)>,
+) {
+    let (entity, list) = list.into_inner();
+    if !profile.is_changed() && !list.is_added() {
+        return;
+    }
+    commands
+        .entity(entity)
+        .despawn_children()
+        .with_children(|rows| {
+            for (index, unlock) in tree.unlocks.iter().enumerate() {
+                spawn_unlock_row(rows, index, unlock, &tree, &profile);
+            }
+        });
+}
+
+#[allow(clippy::type_complexity)]
+pub fn update_profile_button_interaction(
+    mut query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (
+            Changed<Interaction>,
+            Or<(
+                With<UnlockButton>,
+                With<StartingWeaponButton>,
+                With<ProfileBackButton>,
+            )>,
+        ),
+    >,
+) {
+    for (interaction, mut bg) in &mut query {
+        *bg = BackgroundColor(match interaction {
+            Interaction::Hovered | Interaction::Pressed => BUTTON_BG_HOVER,
+            Interaction::None => BUTTON_BG,
+        });
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+fn panel() -> impl Bundle {
+    (
+        Node {
+            flex_direction: FlexDirection::Column,
+            padding: UiRect::all(Val::Px(24.0)),
+            border: UiRect::all(Val::Px(2.0)),
+            border_radius: BorderRadius::all(Val::Px(12.0)),
+            row_gap: Val::Px(12.0),
+            ..default()
+        },
+        BackgroundColor(PANEL_BG),
+        BorderColor::all(Color::srgb(0.3, 0.3, 0.45)),
+    )
+}
+
+fn stat_row(label: &str, value: String) -> impl Bundle {
+    (
+        Node {
+            width: Val::Px(280.0),
+            justify_content: JustifyContent::SpaceBetween,
+            ..default()
+        },
+        children![
+            TextBundle::new(label, 20.0, GREY),
+            TextBundle::new(value, 22.0, Color::WHITE),
+        ],
+    )
+}
+
+fn button(width: f32, height: f32) -> impl Bundle {
+    (
+        Button,
+        Node {
+            width: Val::Px(width),
+            height: Val::Px(height),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            border: UiRect::all(Val::Px(2.0)),
+            border_radius: BorderRadius::all(Val::Px(8.0)),
+            ..default()
+        },
+        BackgroundColor(BUTTON_BG),
+        BorderColor::all(GREY),
+    )
+}
+
+/// Name and reward on the left, then either "Owned", the missing requirement or the price.
+fn spawn_unlock_row(
+    rows: &mut RelatedSpawnerCommands<ChildOf>,
+    index: usize,
+    unlock: &Unlock,
+    tree: &UnlockTree,
+    profile: &Profile,
+) {
+    let mut row = rows.spawn(Node {
+        width: Val::Px(520.0),
+        justify_content: JustifyContent::SpaceBetween,
+        align_items: AlignItems::Center,
+        column_gap: Val::Px(12.0),
+        ..default()
+    });
+    row.with_child((
+        Node {
+            flex_direction: FlexDirection::Column,
+            ..default()
+        },
+        children![
+            TextBundle::new(unlock.name, 20.0, Color::WHITE),
+            TextBundle::new(unlock.reward.description(), 14.0, GREY),
+        ],
+    ));
+
+    if profile.is_unlocked(unlock.id) {
+        row.with_child(status(TextBundle::new("Owned", 20.0, OWNED)));
+    } else if let Some(required) = unlock.requires.filter(|id| !profile.is_unlocked(id)) {
+        let name = tree
+            .find(required)
+            .map_or(required, |required| required.name);
+        row.with_child(status(TextBundle::new(
+            format!("Needs {}", name),
+            16.0,
+            GREY,
+        )));
+    } else {
+        let color = if profile.can_unlock(unlock) {
+            GOLD
+        } else {
+            GREY
+        };
+        row.with_child((
+            UnlockButton(index),
+            button(140.0, 40.0),
+            children![TextBundle::new(format!("Buy {}", unlock.cost), 20.0, color)],
+        ));
+    }
+}
+
+fn status(text: TextBundle) -> impl Bundle {
+    (
+        Node {
+            width: Val::Px(140.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        children![text],
+    )
+}
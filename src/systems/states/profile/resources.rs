@@ -0,0 +1,382 @@
+use crate::systems::game::{load_config, save_config, GameOverStats};
+use crate::systems::states::upgrades::components::{UpgradeCard, UpgradeRarity};
+use crate::systems::states::waves::player::characters::DEFAULT_CHARACTER;
+use crate::systems::states::waves::player::components::StatKind;
+use crate::systems::states::waves::weapons::resources::STARTING_WEAPON;
+use bevy::prelude::{Resource, States};
+use serde::{Deserialize, Serialize};
+
+/// Updated at the end of every run, see `load_config`.
+pub const PROFILE_PATH: &str = "profile.ron";
+
+/// Meta-currency earned for every wave reached.
+pub const META_CURRENCY_PER_WAVE: u32 = 2;
+
+/// Kills needed to earn one meta-currency.
+pub const KILLS_PER_META_CURRENCY: u32 = 10;
+
+/// The profile screen is an overlay on top of the main menu, like the settings.
+#[derive(States, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ProfileMenu {
+    #[default]
+    Closed,
+    Open,
+}
+
+/// Meta-currency a finished run is worth.
+pub fn run_reward(stats: &GameOverStats) -> u32 {
+    stats.wave_reached * META_CURRENCY_PER_WAVE + stats.enemies_killed / KILLS_PER_META_CURRENCY
+}
+
+/// Progress kept from one run to the next, as stored in `PROFILE_PATH`.
+/// Missing fields fall back to their default so older files keep loading.
+#[derive(Resource, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    pub runs: u32,
+    pub lifetime_kills: u32,
+    pub best_wave: u32,
+    pub total_experience: u32,
+    pub meta_currency: u32,
+    /// `Unlock` ids bought so far
+    pub unlocked: Vec<String>,
+    /// `WeaponDefinition` id given to the player at the start of a run
+    pub starting_weapon: String,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self {
+            runs: 0,
+            lifetime_kills: 0,
+            best_wave: 0,
+            total_experience: 0,
+            meta_currency: 0,
+            unlocked: Vec::new(),
+            starting_weapon: STARTING_WEAPON.to_string(),
+        }
+    }
+}
+
+impl Profile {
+    /// Reads `PROFILE_PATH`, a fresh profile is used when it is missing or invalid.
+    /// A starting weapon that is not unlocked falls back to the default one.
+    pub fn load(tree: &UnlockTree) -> Self {
+        let mut profile = load_config::<Self>(PROFILE_PATH);
+        if !profile
+            .starting_weapons(tree)
+            .contains(&profile.starting_weapon.as_str())
+        {
+            profile.starting_weapon = STARTING_WEAPON.to_string();
+        }
+        profile
+    }
+
+    pub fn save(&self) {
+        save_config(PROFILE_PATH, self);
+    }
+
+    pub fn is_unlocked(&self, id: &str) -> bool {
+        self.unlocked.iter().any(|unlocked| unlocked == id)
+    }
+
+    /// Adds a finished run to the lifetime statistics, returns the meta-currency it earned.
+    pub fn record_run(&mut self, stats: &GameOverStats) -> u32 {
+        let reward = run_reward(stats);
+        self.runs += 1;
+        self.lifetime_kills += stats.enemies_killed;
+        self.best_wave = self.best_wave.max(stats.wave_reached);
+        self.total_experience += stats.experience_total;
+        self.meta_currency += reward;
+        reward
+    }
+
+    /// An unlock can be bought once its requirement is unlocked and the profile can afford it.
+    pub fn can_unlock(&self, unlock: &Unlock) -> bool {
+        !self.is_unlocked(unlock.id)
+            && unlock.requires.is_none_or(|id| self.is_unlocked(id))
+            && self.meta_currency >= unlock.cost
+    }
+
+    /// Spends the meta-currency on `unlock`, returns false when it cannot be bought.
+    pub fn unlock(&mut self, unlock: &Unlock) -> bool {
+        if !self.can_unlock(unlock) {
+            return false;
+        }
+        self.meta_currency -= unlock.cost;
+        self.unlocked.push(unlock.id.to_string());
+        true
+    }
+
+    /// Weapon ids a run can start with: the default one, then every unlocked one.
+    pub fn starting_weapons(&self, tree: &UnlockTree) -> Vec<&'static str> {
+        let mut weapons = vec![STARTING_WEAPON];
+        for unlock in tree.unlocked(self) {
+            if let UnlockReward::StartingWeapon(id) = unlock.reward {
+                weapons.push(id);
+            }
+        }
+        weapons
+    }
+
+    /// Character ids that can be picked: the default one, then every unlocked one.
+    pub fn characters(&self, tree: &UnlockTree) -> Vec<&'static str> {
+        let mut characters = vec![DEFAULT_CHARACTER];
+        for unlock in tree.unlocked(self) {
+            if let UnlockReward::Character(id) = unlock.reward {
+                characters.push(id);
+            }
+        }
+        characters
+    }
+
+    /// Cards added to the default `UpgradeCardsPool`.
+    pub fn unlocked_cards(&self, tree: &UnlockTree) -> Vec<UpgradeCard> {
+        tree.unlocked(self)
+            .filter_map(|unlock| match unlock.reward {
+                UnlockReward::UpgradeCard(card) => Some(card),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum UnlockReward {
+    /// `WeaponDefinition` id the player can pick as their starting weapon
+    StartingWeapon(&'static str),
+    /// Card added to the upgrade pool of every run
+    UpgradeCard(UpgradeCard),
+    /// `CharacterDefinition` id that becomes selectable
+    Character(&'static str),
+}
+
+impl UnlockReward {
+    pub fn description(&self) -> String {
+        match self {
+            UnlockReward::StartingWeapon(id) => {
+                format!("Start runs with the {}", id.replace('_', " "))
+            }
+            UnlockReward::UpgradeCard(card) => {
+                let (_, description, _) = card.get_display_info();
+                format!("{} card: {}", card.rarity, description)
+            }
+            UnlockReward::Character(id) => format!("Play as the {}", id),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Unlock {
+    /// Stored in `Profile::unlocked`, must never change once released
+    pub id: &'static str,
+    pub name: &'static str,
+    pub cost: u32,
+    /// Unlock that has to be bought first
+    pub requires: Option<&'static str>,
+    pub reward: UnlockReward,
+}
+
+/// Everything the meta-currency can buy, in display order.
+#[derive(Resource)]
+pub struct UnlockTree {
+    pub unlocks: Vec<Unlock>,
+}
+
+impl Default for UnlockTree {
+    fn default() -> Self {
+        Self {
+            unlocks: vec![
+                Unlock {
+                    id: "pistol_start",
+                    name: "Sidearm",
+                    cost: 15,
+                    requires: None,
+                    reward: UnlockReward::StartingWeapon("pistol"),
+                },
+                Unlock {
+                    id: "shotgun_start",
+                    name: "Boomstick",
+                    cost: 40,
+                    requires: Some("pistol_start"),
+                    reward: UnlockReward::StartingWeapon("shotgun"),
+                },
+                Unlock {
+                    id: "scattershot",
+                    name: "Scattershot",
+                    cost: 50,
+                    requires: None,
+                    reward: UnlockReward::Character("scattershot"),
+                },
+                Unlock {
+                    id: "leech",
+                    name: "Leech",
+                    cost: 50,
+                    requires: Some("scattershot"),
+                    reward: UnlockReward::Character("leech"),
+                },
+                Unlock {
+                    id: "keen_edge",
+                    name: "Keen Edge",
+                    cost: 20,
+                    requires: None,
+                    reward: UnlockReward::UpgradeCard(UpgradeCard::new(
+                        StatKind::Damage,
+                        0.30,
+                        UpgradeRarity::Rare,
+                    )),
+                },
+                Unlock {
+                    id: "quickdraw",
+                    name: "Quickdraw",
+                    cost: 30,
+                    requires: Some("keen_edge"),
+                    reward: UnlockReward::UpgradeCard(UpgradeCard::new(
+                        StatKind::FireRate,
+                        0.35,
+                        UpgradeRarity::Rare,
+                    )),
+                },
+                Unlock {
+                    id: "marathon",
+                    name: "Marathon",
+                    cost: 20,
+                    requires: None,
+                    reward: UnlockReward::UpgradeCard(UpgradeCard::new(
+                        StatKind::Speed,
+                        0.30,
+                        UpgradeRarity::Uncommon,
+                    )),
+                },
+                Unlock {
+                    id: "second_wind",
+                    name: "Second Wind",
+                    cost: 60,
+                    requires: Some("marathon"),
+                    reward: UnlockReward::UpgradeCard(UpgradeCard::new(
+                        StatKind::MaxHealth,
+                        40.0,
+                        UpgradeRarity::Legendary,
+                    )),
+                },
+            ],
+        }
+    }
+}
+
+impl UnlockTree {
+    pub fn find(&self, id: &str) -> Option<&Unlock> {
+        self.unlocks.iter().find(|unlock| unlock.id == id)
+    }
+
+    pub fn unlocked<'a>(&'a self, profile: &'a Profile) -> impl Iterator<Item = &'a Unlock> {
+        self.unlocks
+            .iter()
+            .filter(|unlock| profile.is_unlocked(unlock.id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::states::waves::player::experience::PlayerExperience;
+
+    fn run(wave_reached: u32, enemies_killed: u32, experience_total: u32) -> GameOverStats {
+        GameOverStats {
+            wave_reached,
+            level_reached: 1,
+            experience_total,
+            enemies_killed,
+        }
+    }
+
+    #[test]
+    fn finished_runs_add_up() {
+        let mut profile = Profile::default();
+
+        let reward = profile.record_run(&run(6, 45, 300));
+        assert_eq!(reward, 6 * META_CURRENCY_PER_WAVE + 4);
+        let reward = profile.record_run(&run(3, 9, 120));
+        assert_eq!(reward, 3 * META_CURRENCY_PER_WAVE);
+
+        assert_eq!(profile.runs, 2);
+        assert_eq!(profile.lifetime_kills, 54);
+        assert_eq!(profile.best_wave, 6);
+        assert_eq!(profile.total_experience, 420);
+        assert_eq!(profile.meta_currency, 9 * META_CURRENCY_PER_WAVE + 4);
+    }
+
+    #[test]
+    fn abandoned_runs_count_how_far_they_went() {
+        let mut stats = run(0, 25, 0);
+        let xp = PlayerExperience {
+            value: 80,
+            level: 3,
+            new_levels: 0,
+        };
+        stats.end_run(4, &xp);
+        let mut profile = Profile::default();
+        profile.record_run(&stats);
+
+        assert_eq!(profile.best_wave, 4);
+        assert_eq!(profile.total_experience, 80);
+        assert_eq!(profile.lifetime_kills, 25);
+        assert_eq!(profile.meta_currency, 4 * META_CURRENCY_PER_WAVE + 2);
+    }
+
+    #[test]
+    fn unlocks_need_their_requirement_and_enough_currency() {
+        let tree = UnlockTree::default();
+        let pistol = tree.find("pistol_start").unwrap();
+        let shotgun = tree.find("shotgun_start").unwrap();
+        let mut profile = Profile {
+            meta_currency: pistol.cost + shotgun.cost,
+            ..Profile::default()
+        };
+
+        assert!(!profile.can_unlock(shotgun), "requires the pistol first");
+        assert!(!profile.unlock(shotgun));
+        assert!(profile.unlock(pistol));
+        assert!(!profile.unlock(pistol), "already unlocked");
+        assert_eq!(profile.meta_currency, shotgun.cost);
+        assert!(profile.unlock(shotgun));
+        assert_eq!(profile.meta_currency, 0);
+        assert_eq!(profile.unlocked, ["pistol_start", "shotgun_start"]);
+    }
+
+    #[test]
+    fn unlocks_are_not_bought_on_credit() {
+        let tree = UnlockTree::default();
+        let keen_edge = tree.find("keen_edge").unwrap();
+        let mut profile = Profile {
+            meta_currency: keen_edge.cost - 1,
+            ..Profile::default()
+        };
+        assert!(!profile.unlock(keen_edge));
+        assert_eq!(profile.meta_currency, keen_edge.cost - 1);
+        assert!(profile.unlocked.is_empty());
+    }
+
+    #[test]
+    fn rewards_follow_the_unlocked_ids() {
+        let tree = UnlockTree::default();
+        let profile = Profile {
+            unlocked: vec!["pistol_start".to_string(), "scattershot".to_string()],
+            ..Profile::default()
+        };
+
+        assert_eq!(profile.starting_weapons(&tree), [STARTING_WEAPON, "pistol"]);
+        assert_eq!(
+            profile.characters(&tree),
+            [DEFAULT_CHARACTER, "scattershot"]
+        );
+        assert!(profile.unlocked_cards(&tree).is_empty());
+
+        let profile = Profile {
+            unlocked: vec!["keen_edge".to_string(), "removed_unlock".to_string()],
+            ..Profile::default()
+        };
+        assert_eq!(profile.unlocked_cards(&tree).len(), 1);
+        assert_eq!(profile.starting_weapons(&tree), [STARTING_WEAPON]);
+    }
+}
@@ -0,0 +1,76 @@
+use crate::systems::game::GameOverStats;
+use crate::systems::input::resources::ActionState;
+use crate::systems::states::profile::components::{
+    ProfileBackButton, ProfileButton, StartingWeaponButton, UnlockButton,
+};
+use crate::systems::states::profile::resources::{Profile, ProfileMenu, UnlockTree};
+use crate::systems::states::upgrades::resources::UpgradeCardsPool;
+use bevy::prelude::*;
+
+pub fn open_profile(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<ProfileButton>)>,
+    mut next_state: ResMut<NextState<ProfileMenu>>,
+) {
+    if interaction_query.iter().any(|i| *i == Interaction::Pressed) {
+        next_state.set(ProfileMenu::Open);
+    }
+}
+
+pub fn handle_profile_input(
+    actions: Res<ActionState>,
+    back_query: Query<&Interaction, (Changed<Interaction>, With<ProfileBackButton>)>,
+    weapon_query: Query<&Interaction, (Changed<Interaction>, With<StartingWeaponButton>)>,
+    unlock_query: Query<(&Interaction, &UnlockButton), Changed<Interaction>>,
+    tree: Res<UnlockTree>,
+    mut profile: ResMut<Profile>,
+    mut next_state: ResMut<NextState<ProfileMenu>>,
+) {
+    for (interaction, button) in &unlock_query {
+        if *interaction == Interaction::Pressed
+            && let Some(unlock) = tree.unlocks.get(button.0)
+        {
+            profile.unlock(unlock);
+        }
+    }
+
+    if weapon_query.iter().any(|i| *i == Interaction::Pressed) {
+        let weapons = profile.starting_weapons(&tree);
+        let current = weapons
+            .iter()
+            .position(|id| *id == profile.starting_weapon)
+            .unwrap_or(0);
+        profile.starting_weapon = weapons[(current + 1) % weapons.len()].to_string();
+    }
+
+    let back_clicked = back_query.iter().any(|i| *i == Interaction::Pressed);
+    if back_clicked || actions.pause {
+        next_state.set(ProfileMenu::Closed);
+    }
+}
+
+pub fn save_profile(profile: Res<Profile>) {
+    profile.save();
+}
+
+/// Adds a run that ended, by a game over or abandoned from the pause menu, to the profile and
+/// saves it right away so quitting keeps the progress.
+pub fn save_finished_run(profile: &mut Profile, stats: &GameOverStats) {
+    profile.record_run(stats);
+    profile.save();
+}
+
+/// Records the run when the game-over screen opens.
+pub fn record_finished_run(stats: Res<GameOverStats>, mut profile: ResMut<Profile>) {
+    save_finished_run(&mut profile, &stats);
+}
+
+/// Unlocked cards join the default pool, every run draws from the same cards.
+pub fn apply_unlocked_cards(
+    profile: Res<Profile>,
+    tree: Res<UnlockTree>,
+    mut pool: ResMut<UpgradeCardsPool>,
+) {
+    let mut upgrades = UpgradeCardsPool::default().upgrades;
+    upgrades.extend(profile.unlocked_cards(&tree));
+    pool.upgrades = upgrades;
+}
@@ -0,0 +1,65 @@
+use crate::systems::input::resources::{ActiveInputDevice, InputAction};
+use bevy::prelude::Component;
+
+#[derive(Component)]
+pub struct SettingsUI;
+
+/// Opens the settings overlay, whichever menu it is placed in
+#[derive(Component)]
+pub struct SettingsButton;
+
+#[derive(Component)]
+pub struct SettingsBackButton;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VolumeSetting {
+    Master,
+    Music,
+    SoundEffects,
+}
+
+#[derive(Component)]
+pub struct VolumeText(pub VolumeSetting);
+
+/// "-" / "+" button next to a volume, `step` is added to the volume when pressed
+#[derive(Component)]
+pub struct VolumeButton {
+    pub setting: VolumeSetting,
+    pub step: i32,
+}
+
+/// Track of a volume slider, clicking or dragging along it sets the volume
+#[derive(Component)]
+pub struct VolumeSlider(pub VolumeSetting);
+
+/// Filled part of a `VolumeSlider`, its width follows the volume
+#[derive(Component)]
+pub struct VolumeFill(pub VolumeSetting);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CycleOption {
+    WindowMode,
+    VSync,
+    AimMode,
+}
+
+/// Cycles through the values of an option when pressed
+#[derive(Component)]
+pub struct CycleOptionButton(pub CycleOption);
+
+#[derive(Component)]
+pub struct CycleOptionText(pub CycleOption);
+
+/// Opens the rebinding screen from the settings overlay
+#[derive(Component)]
+pub struct ControlsButton;
+
+#[derive(Component)]
+pub struct ResetBindingsButton;
+
+/// Shows the inputs bound to `action` on `device`, pressing it waits for a new one
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BindingButton {
+    pub action: InputAction,
+    pub device: ActiveInputDevice,
+}
@@ -0,0 +1,60 @@
+use crate::systems::game::{MasterVolume, MusicVolume, SoundEffectVolume};
+use crate::systems::states::settings::renderer::{
+    spawn_controls_menu, spawn_settings_menu, update_binding_buttons, update_option_texts,
+    update_settings_button_interaction, update_volume_texts,
+};
+use crate::systems::states::settings::resources::{PendingRebind, SettingsFile, SettingsMenu};
+use crate::systems::states::settings::systems::{
+    apply_display_settings, apply_music_volume, cancel_pending_rebind, drag_volume_sliders,
+    handle_controls_input, handle_settings_input, open_settings, save_input_bindings,
+    save_settings,
+};
+use bevy::prelude::*;
+
+/// Settings overlay, opened by any `SettingsButton`.
+/// Owns the volume and display resources, loaded from the settings file and saved on close.
+/// The controls screen edits `InputBindings`, saved when leaving it.
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        let settings = SettingsFile::load();
+        app.init_state::<SettingsMenu>()
+            .insert_resource(MasterVolume(settings.master_volume))
+            .insert_resource(MusicVolume(settings.music_volume))
+            .insert_resource(SoundEffectVolume(settings.sound_effect_volume))
+            .insert_resource(settings.display())
+            .insert_resource(settings.aim_mode)
+            .init_resource::<PendingRebind>()
+            .add_systems(OnEnter(SettingsMenu::Open), spawn_settings_menu)
+            .add_systems(OnExit(SettingsMenu::Open), save_settings)
+            .add_systems(Update, open_settings.run_if(in_state(SettingsMenu::Closed)))
+            .add_systems(
+                Update,
+                (
+                    handle_settings_input,
+                    drag_volume_sliders,
+                    update_volume_texts,
+                    update_option_texts,
+                )
+                    .run_if(in_state(SettingsMenu::Open)),
+            )
+            // ------------------------  Controls screen -------------------------------- //
+            .add_systems(OnEnter(SettingsMenu::Controls), spawn_controls_menu)
+            .add_systems(
+                OnExit(SettingsMenu::Controls),
+                (cancel_pending_rebind, save_input_bindings),
+            )
+            .add_systems(
+                Update,
+                (handle_controls_input, update_binding_buttons)
+                    .chain()
+                    .run_if(in_state(SettingsMenu::Controls)),
+            )
+            .add_systems(
+                Update,
+                update_settings_button_interaction.run_if(not(in_state(SettingsMenu::Closed))),
+            )
+            .add_systems(Update, (apply_music_volume, apply_display_settings));
+    }
+}
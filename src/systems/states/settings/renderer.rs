@@ -0,0 +1,464 @@
+use crate::systems::game::{MasterVolume, MusicVolume, SoundEffectVolume, TextBundle};
+use crate::systems::input::resources::{
+    ActiveInputDevice, GamepadAsset, InputAction, InputBindings, KeyboardAsset,
+};
+use crate::systems::states::settings::components::*;
+use crate::systems::states::settings::resources::{
+    DisplaySettings, PendingRebind, SettingsMenu, MAX_VOLUME,
+};
+use crate::systems::states::waves::weapons::resources::AimMode;
+use bevy::prelude::*;
+use bevy::ui::{FocusPolicy, RelativeCursorPosition};
+
+const BG: Color = Color::srgba(0.0, 0.0, 0.0, 0.92);
+const PANEL_BG: Color = Color::srgb(0.07, 0.07, 0.12);
+const BUTTON_BG: Color = Color::srgb(0.15, 0.15, 0.25);
+const BUTTON_BG_HOVER: Color = Color::srgb(0.25, 0.25, 0.4);
+const GREY: Color = Color::srgb(0.55, 0.55, 0.65);
+const SLIDER_BG: Color = Color::srgb(0.12, 0.12, 0.2);
+const SLIDER_FILL: Color = Color::srgb(0.4, 0.6, 1.0);
+
+pub fn spawn_settings_menu(mut commands: Commands) {
+    commands.spawn((
+        SettingsUI,
+        DespawnOnExit(SettingsMenu::Open),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(24.0),
+            ..default()
+        },
+        BackgroundColor(BG),
+        // Above every other menu, settings can be opened from any of them
+        GlobalZIndex(500),
+        // Keeps the buttons of the menu underneath from reacting to clicks
+        FocusPolicy::Block,
+        children![
+            TextBundle::new("SETTINGS", 52.0, Color::WHITE),
+            (
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(32.0)),
+                    border: UiRect::all(Val::Px(2.0)),
+                    border_radius: BorderRadius::all(Val::Px(12.0)),
+                    row_gap: Val::Px(18.0),
+                    ..default()
+                },
+                BackgroundColor(PANEL_BG),
+                BorderColor::all(Color::srgb(0.3, 0.3, 0.45)),
+                children![
+                    volume_row("Master", VolumeSetting::Master),
+                    volume_row("Music", VolumeSetting::Music),
+                    volume_row("Effects", VolumeSetting::SoundEffects),
+                    option_row("Window", CycleOption::WindowMode),
+                    option_row("VSync", CycleOption::VSync),
+                    option_row("Aiming", CycleOption::AimMode),
+                ],
+            ),
+            (
+                Node {
+                    column_gap: Val::Px(20.0),
+                    ..default()
+                },
+                children![
+                    (ControlsButton, menu_button("Controls")),
+                    (SettingsBackButton, menu_button("Back")),
+                ],
+            ),
+            TextBundle::new("Escape / Start to go back", 14.0, GREY),
+        ],
+    ));
+}
+
+pub fn spawn_controls_menu(mut commands: Commands) {
+    commands
+        .spawn((
+            // Also a `SettingsUI`, so Escape does not toggle the pause behind it
+            SettingsUI,
+            DespawnOnExit(SettingsMenu::Controls),
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(16.0),
+                ..default()
+            },
+            BackgroundColor(BG),
+            GlobalZIndex(500),
+            FocusPolicy::Block,
+        ))
+        .with_children(|root| {
+            root.spawn(TextBundle::new("CONTROLS", 44.0, Color::WHITE));
+            root.spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(20.0)),
+                    border: UiRect::all(Val::Px(2.0)),
+                    border_radius: BorderRadius::all(Val::Px(12.0)),
+                    row_gap: Val::Px(4.0),
+                    ..default()
+                },
+                BackgroundColor(PANEL_BG),
+                BorderColor::all(Color::srgb(0.3, 0.3, 0.45)),
+            ))
+            .with_children(|panel| {
+                panel.spawn(binding_header());
+                for action in InputAction::ALL {
+                    panel.spawn(binding_row(action));
+                }
+            });
+            root.spawn((
+                Node {
+                    column_gap: Val::Px(20.0),
+                    ..default()
+                },
+                children![
+                    (ResetBindingsButton, menu_button("Reset")),
+                    (SettingsBackButton, menu_button("Back")),
+                ],
+            ));
+            root.spawn(TextBundle::new(
+                "Click a binding then press the new key or button, Escape or Pause cancels",
+                14.0,
+                GREY,
+            ));
+        });
+}
+
+/// Redraws every binding when the bindings change or one starts waiting for an input.
+pub fn update_binding_buttons(
+    mut commands: Commands,
+    bindings: Res<InputBindings>,
+    pending: Res<PendingRebind>,
+    keyboard_asset: Res<KeyboardAsset>,
+    gamepad_asset: Res<GamepadAsset>,
+    buttons: Query<(Entity, Ref<BindingButton>)>,
+) {
+    let redraw_all = bindings.is_changed() || pending.is_changed();
+    for (entity, binding) in &buttons {
+        if !redraw_all && !binding.is_added() {
+            continue;
+        }
+        let mut button = commands.entity(entity);
+        button.despawn_children();
+        if pending.0 == Some(*binding) {
+            button.with_child(TextBundle::new("Press...", 18.0, SLIDER_FILL));
+            continue;
+        }
+        match binding.device {
+            ActiveInputDevice::Keyboard => {
+                let keys = bindings.keys(binding.action);
+                let label = if keys.is_empty() {
+                    "-".to_string()
+                } else {
+                    keys.iter()
+                        .map(|key| keyboard_asset.keycode_label(key))
+                        .collect::<Vec<_>>()
+                        .join(" / ")
+                };
+                button.with_child(TextBundle::new(label, 18.0, Color::WHITE));
+            }
+            ActiveInputDevice::Gamepad => {
+                let buttons = bindings.buttons(binding.action);
+                if buttons.is_empty() {
+                    button.with_child(TextBundle::new("-", 18.0, Color::WHITE));
+                }
+                for gamepad_button in buttons {
+                    button.with_child((
+                        ImageNode::from_atlas_image(
+                            gamepad_asset.texture.clone(),
+                            TextureAtlas::from(gamepad_asset.layout.clone())
+                                .with_index(gamepad_asset.get_button_index(gamepad_button)),
+                        ),
+                        Node {
+                            width: Val::Px(28.0),
+                            height: Val::Px(28.0),
+                            ..default()
+                        },
+                    ));
+                }
+            }
+        }
+    }
+}
+
+pub fn update_volume_texts(
+    master_volume: Res<MasterVolume>,
+    music_volume: Res<MusicVolume>,
+    sound_effect_volume: Res<SoundEffectVolume>,
+    mut texts: Query<(&mut Text, &VolumeText)>,
+    mut fills: Query<(&mut Node, &VolumeFill)>,
+) {
+    let volume = |setting| match setting {
+        VolumeSetting::Master => master_volume.0,
+        VolumeSetting::Music => music_volume.0,
+        VolumeSetting::SoundEffects => sound_effect_volume.0,
+    };
+    for (mut node, fill) in &mut fills {
+        let width = Val::Percent(volume(fill.0) as f32 / MAX_VOLUME as f32 * 100.0);
+        if node.width != width {
+            node.width = width;
+        }
+    }
+    for (mut text, volume_text) in &mut texts {
+        let value = volume(volume_text.0).to_string();
+        if text.0 != value {
+            text.0 = value;
+        }
+    }
+}
+
+pub fn update_option_texts(
+    display: Res<DisplaySettings>,
+    aim_mode: Res<AimMode>,
+    mut texts: Query<(&mut Text, &CycleOptionText)>,
+) {
+    for (mut text, option_text) in &mut texts {
+        let value = match option_text.0 {
+            CycleOption::WindowMode => display.window_mode.label(),
+            CycleOption::VSync => {
+                if display.vsync {
+                    "On"
+                } else {
+                    "Off"
+                }
+            }
+            CycleOption::AimMode => aim_mode.label(),
+        };
+        if text.0 != value {
+            text.0 = value.to_string();
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn update_settings_button_interaction(
+    mut query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (
+            Changed<Interaction>,
+            Or<(
+                With<VolumeButton>,
+                With<CycleOptionButton>,
+                With<ControlsButton>,
+                With<BindingButton>,
+                With<ResetBindingsButton>,
+                With<SettingsBackButton>,
+            )>,
+        ),
+    >,
+) {
+    for (interaction, mut bg) in &mut query {
+        *bg = BackgroundColor(match interaction {
+            Interaction::Hovered | Interaction::Pressed => BUTTON_BG_HOVER,
+            Interaction::None => BUTTON_BG,
+        });
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+fn volume_row(label: &str, setting: VolumeSetting) -> impl Bundle {
+    (
+        Node {
+            width: Val::Px(520.0),
+            flex_direction: FlexDirection::Row,
+            justify_content: JustifyContent::SpaceBetween,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        children![
+            TextBundle::new(label, 22.0, GREY),
+            (
+                Node {
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(12.0),
+                    ..default()
+                },
+                children![
+                    volume_button(setting, -1, "-"),
+                    (
+                        VolumeSlider(setting),
+                        Button,
+                        RelativeCursorPosition::default(),
+                        Node {
+                            width: Val::Px(200.0),
+                            height: Val::Px(16.0),
+                            border_radius: BorderRadius::all(Val::Px(8.0)),
+                            ..default()
+                        },
+                        BackgroundColor(SLIDER_BG),
+                        children![(
+                            VolumeFill(setting),
+                            Node {
+                                height: Val::Percent(100.0),
+                                border_radius: BorderRadius::all(Val::Px(8.0)),
+                                ..default()
+                            },
+                            BackgroundColor(SLIDER_FILL),
+                        )],
+                    ),
+                    volume_button(setting, 1, "+"),
+                    (
+                        VolumeText(setting),
+                        TextBundle::new("", 22.0, Color::WHITE),
+                        Node {
+                            width: Val::Px(40.0),
+                            justify_content: JustifyContent::Center,
+                            ..default()
+                        },
+                        TextLayout::new_with_justify(Justify::Center),
+                    ),
+                ],
+            ),
+        ],
+    )
+}
+
+fn volume_button(setting: VolumeSetting, step: i32, label: &str) -> impl Bundle {
+    (
+        VolumeButton { setting, step },
+        Button,
+        Node {
+            width: Val::Px(40.0),
+            height: Val::Px(40.0),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            border: UiRect::all(Val::Px(2.0)),
+            border_radius: BorderRadius::all(Val::Px(6.0)),
+            ..default()
+        },
+        BackgroundColor(BUTTON_BG),
+        BorderColor::all(GREY),
+        children![TextBundle::new(label, 24.0, Color::WHITE)],
+    )
+}
+
+fn menu_button(label: &str) -> impl Bundle {
+    (
+        Button,
+        Node {
+            width: Val::Px(240.0),
+            height: Val::Px(56.0),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            border: UiRect::all(Val::Px(2.0)),
+            border_radius: BorderRadius::all(Val::Px(8.0)),
+            ..default()
+        },
+        BackgroundColor(BUTTON_BG),
+        BorderColor::all(GREY),
+        children![TextBundle::new(label, 26.0, Color::WHITE)],
+    )
+}
+
+fn binding_header() -> impl Bundle {
+    (
+        Node {
+            width: Val::Px(640.0),
+            column_gap: Val::Px(12.0),
+            ..default()
+        },
+        children![
+            (
+                TextBundle::new("Action", 16.0, GREY),
+                Node {
+                    width: Val::Px(200.0),
+                    ..default()
+                },
+            ),
+            (
+                TextBundle::new("Keyboard", 16.0, GREY),
+                Node {
+                    width: Val::Px(240.0),
+                    ..default()
+                },
+            ),
+            TextBundle::new("Gamepad", 16.0, GREY),
+        ],
+    )
+}
+
+fn binding_row(action: InputAction) -> impl Bundle {
+    (
+        Node {
+            width: Val::Px(640.0),
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(12.0),
+            ..default()
+        },
+        children![
+            (
+                TextBundle::new(action.label(), 18.0, GREY),
+                Node {
+                    width: Val::Px(200.0),
+                    ..default()
+                },
+            ),
+            binding_button(action, ActiveInputDevice::Keyboard, 240.0),
+            binding_button(action, ActiveInputDevice::Gamepad, 176.0),
+        ],
+    )
+}
+
+/// Its content is filled in by `update_binding_buttons`
+fn binding_button(action: InputAction, device: ActiveInputDevice, width: f32) -> impl Bundle {
+    (
+        BindingButton { action, device },
+        Button,
+        Node {
+            width: Val::Px(width),
+            height: Val::Px(34.0),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(4.0),
+            border: UiRect::all(Val::Px(2.0)),
+            border_radius: BorderRadius::all(Val::Px(6.0)),
+            ..default()
+        },
+        BackgroundColor(BUTTON_BG),
+        BorderColor::all(GREY),
+    )
+}
+
+fn option_row(label: &str, option: CycleOption) -> impl Bundle {
+    (
+        Node {
+            width: Val::Px(520.0),
+            flex_direction: FlexDirection::Row,
+            justify_content: JustifyContent::SpaceBetween,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        children![
+            TextBundle::new(label, 22.0, GREY),
+            (
+                CycleOptionButton(option),
+                Button,
+                Node {
+                    width: Val::Px(200.0),
+                    height: Val::Px(40.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    border: UiRect::all(Val::Px(2.0)),
+                    border_radius: BorderRadius::all(Val::Px(6.0)),
+                    ..default()
+                },
+                BackgroundColor(BUTTON_BG),
+                BorderColor::all(GREY),
+                children![(
+                    CycleOptionText(option),
+                    TextBundle::new("", 22.0, Color::WHITE)
+                )],
+            ),
+        ],
+    )
+}
@@ -0,0 +1,152 @@
+use crate::systems::game::{
+    load_config, save_config, MasterVolume, MusicVolume, SoundEffectVolume,
+};
+use crate::systems::states::settings::components::BindingButton;
+use crate::systems::states::waves::weapons::resources::AimMode;
+use bevy::prelude::{Resource, States};
+use bevy::window::{MonitorSelection, PresentMode, VideoModeSelection, WindowMode};
+use serde::{Deserialize, Serialize};
+
+/// Highest value of `MasterVolume`, `MusicVolume` and `SoundEffectVolume`.
+pub const MAX_VOLUME: u32 = 10;
+
+/// Loaded at startup, see `load_config`.
+pub const SETTINGS_PATH: &str = "settings.ron";
+
+/// The settings overlay is independent from `GameState` so it can open on top of any menu.
+#[derive(States, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SettingsMenu {
+    #[default]
+    Closed,
+    Open,
+    /// Rebinding screen, reached from the settings overlay
+    Controls,
+}
+
+/// Binding of the controls screen waiting for a key or gamepad button press.
+#[derive(Resource, Debug, Default)]
+pub struct PendingRebind(pub Option<BindingButton>);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum WindowModeSetting {
+    #[default]
+    Windowed,
+    Borderless,
+    Fullscreen,
+}
+
+impl WindowModeSetting {
+    pub fn next(self) -> Self {
+        match self {
+            WindowModeSetting::Windowed => WindowModeSetting::Borderless,
+            WindowModeSetting::Borderless => WindowModeSetting::Fullscreen,
+            WindowModeSetting::Fullscreen => WindowModeSetting::Windowed,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            WindowModeSetting::Windowed => "Windowed",
+            WindowModeSetting::Borderless => "Borderless",
+            WindowModeSetting::Fullscreen => "Fullscreen",
+        }
+    }
+
+    pub fn window_mode(self) -> WindowMode {
+        match self {
+            WindowModeSetting::Windowed => WindowMode::Windowed,
+            WindowModeSetting::Borderless => {
+                WindowMode::BorderlessFullscreen(MonitorSelection::Current)
+            }
+            WindowModeSetting::Fullscreen => {
+                WindowMode::Fullscreen(MonitorSelection::Current, VideoModeSelection::Current)
+            }
+        }
+    }
+}
+
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisplaySettings {
+    pub window_mode: WindowModeSetting,
+    pub vsync: bool,
+}
+
+impl DisplaySettings {
+    pub fn present_mode(&self) -> PresentMode {
+        if self.vsync {
+            PresentMode::AutoVsync
+        } else {
+            PresentMode::AutoNoVsync
+        }
+    }
+}
+
+/// Everything the settings menu edits, as stored in `SETTINGS_PATH`.
+/// Missing fields fall back to their default so older files keep loading.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SettingsFile {
+    pub master_volume: u32,
+    pub music_volume: u32,
+    pub sound_effect_volume: u32,
+    pub window_mode: WindowModeSetting,
+    pub vsync: bool,
+    pub aim_mode: AimMode,
+}
+
+impl Default for SettingsFile {
+    fn default() -> Self {
+        Self {
+            master_volume: MAX_VOLUME,
+            music_volume: 5,
+            sound_effect_volume: 7,
+            window_mode: WindowModeSetting::Windowed,
+            vsync: true,
+            aim_mode: AimMode::Auto,
+        }
+    }
+}
+
+impl SettingsFile {
+    /// Reads `SETTINGS_PATH`, the defaults are used when it is missing or invalid.
+    pub fn load() -> Self {
+        load_config::<Self>(SETTINGS_PATH).clamped()
+    }
+
+    pub fn save(&self) {
+        save_config(SETTINGS_PATH, self);
+    }
+
+    pub fn from_resources(
+        master_volume: &MasterVolume,
+        music_volume: &MusicVolume,
+        sound_effect_volume: &SoundEffectVolume,
+        display: &DisplaySettings,
+        aim_mode: &AimMode,
+    ) -> Self {
+        Self {
+            master_volume: master_volume.0,
+            music_volume: music_volume.0,
+            sound_effect_volume: sound_effect_volume.0,
+            window_mode: display.window_mode,
+            vsync: display.vsync,
+            aim_mode: *aim_mode,
+        }
+    }
+
+    pub fn display(&self) -> DisplaySettings {
+        DisplaySettings {
+            window_mode: self.window_mode,
+            vsync: self.vsync,
+        }
+    }
+
+    fn clamped(self) -> Self {
+        Self {
+            master_volume: self.master_volume.min(MAX_VOLUME),
+            music_volume: self.music_volume.min(MAX_VOLUME),
+            sound_effect_volume: self.sound_effect_volume.min(MAX_VOLUME),
+            ..self
+        }
+    }
+}
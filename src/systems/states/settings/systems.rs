@@ -0,0 +1,219 @@
+use crate::systems::game::{MasterVolume, MusicVolume, SoundEffectVolume};
+use crate::systems::input::resources::{
+    ActionState, ActiveInputDevice, InputAction, InputBindings,
+};
+use crate::systems::states::settings::components::{
+    BindingButton, ControlsButton, CycleOption, CycleOptionButton, ResetBindingsButton,
+    SettingsBackButton, SettingsButton, VolumeButton, VolumeSetting, VolumeSlider,
+};
+use crate::systems::states::settings::resources::{
+    DisplaySettings, PendingRebind, SettingsFile, SettingsMenu, MAX_VOLUME,
+};
+use crate::systems::states::waves::components::BackgroundMusic;
+use crate::systems::states::waves::weapons::resources::AimMode;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::ui::RelativeCursorPosition;
+use bevy::window::PrimaryWindow;
+
+#[derive(SystemParam)]
+pub struct Volumes<'w> {
+    master: ResMut<'w, MasterVolume>,
+    music: ResMut<'w, MusicVolume>,
+    sound_effects: ResMut<'w, SoundEffectVolume>,
+}
+
+impl Volumes<'_> {
+    fn get_mut(&mut self, setting: VolumeSetting) -> &mut u32 {
+        match setting {
+            VolumeSetting::Master => &mut self.master.0,
+            VolumeSetting::Music => &mut self.music.0,
+            VolumeSetting::SoundEffects => &mut self.sound_effects.0,
+        }
+    }
+}
+
+pub fn open_settings(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<SettingsButton>)>,
+    mut next_state: ResMut<NextState<SettingsMenu>>,
+) {
+    if interaction_query.iter().any(|i| *i == Interaction::Pressed) {
+        next_state.set(SettingsMenu::Open);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn handle_settings_input(
+    actions: Res<ActionState>,
+    back_query: Query<&Interaction, (Changed<Interaction>, With<SettingsBackButton>)>,
+    volume_query: Query<(&Interaction, &VolumeButton), Changed<Interaction>>,
+    option_query: Query<(&Interaction, &CycleOptionButton), Changed<Interaction>>,
+    controls_query: Query<&Interaction, (Changed<Interaction>, With<ControlsButton>)>,
+    mut volumes: Volumes,
+    mut display: ResMut<DisplaySettings>,
+    mut aim_mode: ResMut<AimMode>,
+    mut next_state: ResMut<NextState<SettingsMenu>>,
+) {
+    for (interaction, button) in &volume_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let volume = volumes.get_mut(button.setting);
+        *volume = volume.saturating_add_signed(button.step).min(MAX_VOLUME);
+    }
+
+    for (interaction, button) in &option_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button.0 {
+            CycleOption::WindowMode => display.window_mode = display.window_mode.next(),
+            CycleOption::VSync => display.vsync = !display.vsync,
+            CycleOption::AimMode => *aim_mode = aim_mode.next(),
+        }
+    }
+
+    if controls_query.iter().any(|i| *i == Interaction::Pressed) {
+        next_state.set(SettingsMenu::Controls);
+        return;
+    }
+
+    // Escape / Start closes the overlay, the same input that opened the menu behind it
+    let back_clicked = back_query.iter().any(|i| *i == Interaction::Pressed);
+    if back_clicked || actions.pause {
+        next_state.set(SettingsMenu::Closed);
+    }
+}
+
+/// While a binding is pending, the next key (or gamepad button) pressed replaces its primary
+/// input. Escape and the gamepad buttons bound to `Pause` cancel, so they cannot be bound to
+/// anything but what they already are.
+#[allow(clippy::too_many_arguments)]
+pub fn handle_controls_input(
+    actions: Res<ActionState>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    back_query: Query<&Interaction, (Changed<Interaction>, With<SettingsBackButton>)>,
+    reset_query: Query<&Interaction, (Changed<Interaction>, With<ResetBindingsButton>)>,
+    binding_query: Query<(&Interaction, &BindingButton), Changed<Interaction>>,
+    mut pending: ResMut<PendingRebind>,
+    mut bindings: ResMut<InputBindings>,
+    mut next_state: ResMut<NextState<SettingsMenu>>,
+) {
+    if let Some(binding) = pending.0 {
+        let pause_buttons = bindings.buttons(InputAction::Pause);
+        let cancel = keyboard.just_pressed(KeyCode::Escape)
+            || gamepads
+                .iter()
+                .any(|gamepad| gamepad.any_just_pressed(pause_buttons.iter().copied()));
+        if cancel {
+            pending.0 = None;
+            return;
+        }
+        match binding.device {
+            ActiveInputDevice::Keyboard => {
+                if let Some(key) = keyboard.get_just_pressed().next() {
+                    bindings.bind_key(binding.action, *key);
+                    pending.0 = None;
+                }
+            }
+            ActiveInputDevice::Gamepad => {
+                let button = gamepads
+                    .iter()
+                    .find_map(|gamepad| gamepad.get_just_pressed().next().copied());
+                if let Some(button) = button {
+                    bindings.bind_button(binding.action, button);
+                    pending.0 = None;
+                }
+            }
+        }
+        return;
+    }
+
+    for (interaction, binding) in &binding_query {
+        if *interaction == Interaction::Pressed {
+            pending.0 = Some(*binding);
+            return;
+        }
+    }
+
+    if reset_query.iter().any(|i| *i == Interaction::Pressed) {
+        *bindings = InputBindings::default();
+    }
+
+    let back_clicked = back_query.iter().any(|i| *i == Interaction::Pressed);
+    if back_clicked || actions.pause {
+        next_state.set(SettingsMenu::Open);
+    }
+}
+
+pub fn cancel_pending_rebind(mut pending: ResMut<PendingRebind>) {
+    pending.0 = None;
+}
+
+pub fn save_input_bindings(bindings: Res<InputBindings>) {
+    bindings.save();
+}
+
+/// Sliders stay `Pressed` while the mouse button is held, so dragging keeps updating the volume.
+pub fn drag_volume_sliders(
+    slider_query: Query<(&Interaction, &RelativeCursorPosition, &VolumeSlider)>,
+    mut volumes: Volumes,
+) {
+    for (interaction, cursor, slider) in &slider_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        // `normalized` goes from -0.5 on the left edge to 0.5 on the right edge
+        let Some(position) = cursor.normalized else {
+            continue;
+        };
+        let value = ((position.x + 0.5).clamp(0.0, 1.0) * MAX_VOLUME as f32).round() as u32;
+        let volume = volumes.get_mut(slider.0);
+        if *volume != value {
+            *volume = value;
+        }
+    }
+}
+
+/// Sounds pick up the volumes when spawned, only the looping music has to follow changes.
+pub fn apply_music_volume(
+    master_volume: Res<MasterVolume>,
+    music_volume: Res<MusicVolume>,
+    mut audio_query: Query<&mut AudioSink, With<BackgroundMusic>>,
+) {
+    if !master_volume.is_changed() && !music_volume.is_changed() {
+        return;
+    }
+    for mut sink in &mut audio_query {
+        sink.set_volume(master_volume.mix(music_volume.0));
+    }
+}
+
+pub fn apply_display_settings(
+    display: Res<DisplaySettings>,
+    mut window: Single<&mut Window, With<PrimaryWindow>>,
+) {
+    if !display.is_changed() {
+        return;
+    }
+    window.mode = display.window_mode.window_mode();
+    window.present_mode = display.present_mode();
+}
+
+pub fn save_settings(
+    master_volume: Res<MasterVolume>,
+    music_volume: Res<MusicVolume>,
+    sound_effect_volume: Res<SoundEffectVolume>,
+    display: Res<DisplaySettings>,
+    aim_mode: Res<AimMode>,
+) {
+    SettingsFile::from_resources(
+        &master_volume,
+        &music_volume,
+        &sound_effect_volume,
+        &display,
+        &aim_mode,
+    )
+    .save();
+}
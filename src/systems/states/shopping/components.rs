@@ -5,3 +5,25 @@ pub struct ShoppingUI;
 
 #[derive(Component)]
 pub struct NextWaveButton;
+
+/// Row holding one card per shop offer, rebuilt whenever the `Shop` changes
+#[derive(Component)]
+pub struct ShopOffersRow;
+
+#[derive(Component)]
+pub struct ShopBuyButton(pub usize);
+
+#[derive(Component)]
+pub struct ShopLockButton(pub usize);
+
+#[derive(Component)]
+pub struct RerollButton;
+
+#[derive(Component)]
+pub struct RerollCostText;
+
+#[derive(Component)]
+pub struct CurrencyText;
+
+#[derive(Component)]
+pub struct WeaponsCountText;
@@ -1,3 +1,4 @@
 pub mod components;
 pub mod renderer;
+pub mod resources;
 pub mod systems;
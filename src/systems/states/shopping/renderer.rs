@@ -1,7 +1,23 @@
-use crate::systems::game::GameState;
-use crate::systems::states::shopping::components::{NextWaveButton, ShoppingUI};
+use crate::systems::constants::MAX_WEAPONS;
+use crate::systems::game::{GameState, TextBundle};
+use crate::systems::states::shopping::components::*;
+use crate::systems::states::shopping::resources::{Shop, ShopItem, ShopOffer};
+use crate::systems::states::waves::player::components::Player;
+use crate::systems::states::waves::player::currency::Currency;
+use crate::systems::states::waves::resources::WaveManager;
+use crate::systems::states::waves::weapons::components::Weapon;
+use crate::systems::states::waves::weapons::resources::WeaponsLibrary;
+use bevy::color::palettes::css::GOLD;
 use bevy::prelude::*;
 
+const GREY: Color = Color::srgb(0.55, 0.55, 0.65);
+const RED: Color = Color::srgb(0.9, 0.3, 0.3);
+const BG_CARD: Color = Color::srgb(0.07, 0.07, 0.12);
+const BG_BUTTON: Color = Color::srgb(0.15, 0.15, 0.25);
+const BG_BUTTON_HOVER: Color = Color::srgb(0.25, 0.25, 0.4);
+const CARD_W: f32 = 220.0;
+const CARD_H: f32 = 250.0;
+
 pub fn spawn_shopping(mut commands: Commands) {
     commands
         .spawn((
@@ -19,16 +35,55 @@ pub fn spawn_shopping(mut commands: Commands) {
         ))
         .with_children(|parent| {
             parent.spawn((
-                TextFont {
-                    font_size: 36.0,
+                TextBundle::new("SHOP", 36.0, Color::srgb(0.8, 0.8, 0.8)),
+                Node {
+                    margin: UiRect::bottom(Val::Px(16.0)),
+                    ..default()
+                },
+            ));
+            parent.spawn((
+                Node {
+                    column_gap: Val::Px(40.0),
+                    margin: UiRect::bottom(Val::Px(16.0)),
+                    ..default()
+                },
+                children![
+                    (CurrencyText, TextBundle::new("", 24.0, GOLD.into())),
+                    (WeaponsCountText, TextBundle::new("", 24.0, Color::WHITE)),
+                ],
+            ));
+            parent.spawn((
+                ShopOffersRow,
+                Node {
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Val::Px(18.0),
+                    margin: UiRect::bottom(Val::Px(16.0)),
                     ..default()
                 },
-                TextColor(Color::srgb(0.8, 0.8, 0.8)),
+            ));
+            parent.spawn((
+                TextBundle::new("1-4 buy · Shift + 1-4 lock · R reroll", 16.0, GREY),
                 Node {
-                    margin: UiRect::bottom(Val::Px(40.0)),
+                    margin: UiRect::bottom(Val::Px(24.0)),
                     ..default()
                 },
             ));
+            parent.spawn((
+                RerollButton,
+                Button,
+                Node {
+                    width: Val::Px(240.0),
+                    height: Val::Px(50.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    margin: UiRect::bottom(Val::Px(16.0)),
+                    border: UiRect::all(Val::Px(2.0)),
+                    ..default()
+                },
+                BackgroundColor(BG_BUTTON),
+                BorderColor::all(GREY),
+                children![(RerollCostText, TextBundle::new("", 22.0, Color::WHITE))],
+            ));
             parent.spawn((
                 NextWaveButton,
                 Button,
@@ -72,3 +127,194 @@ pub fn update_start_button_interaction(
         }
     }
 }
+
+/// Rebuilds the offer cards whenever the shop or the player's wallet changes
+pub fn redraw_shop_offers(
+    mut commands: Commands,
+    row: Single<Entity, With<ShopOffersRow>>,
+    shop: Res<Shop>,
+    library: Res<WeaponsLibrary>,
+    currency: Single<Ref<Currency>, With<Player>>,
+) {
+    if !shop.is_changed() && !currency.is_changed() {
+        return;
+    }
+    commands.entity(*row).despawn_children();
+    commands.entity(*row).with_children(|parent| {
+        for (index, offer) in shop.offers.iter().enumerate() {
+            parent.spawn(offer_bundle(index, offer, &library, currency.0));
+        }
+    });
+}
+
+#[allow(clippy::type_complexity)]
+pub fn update_shop_texts(
+    shop: Res<Shop>,
+    wave_manager: Res<WaveManager>,
+    player: Single<(&Currency, &Children), With<Player>>,
+    weapon_query: Query<&Weapon>,
+    mut texts: Query<(
+        &mut Text,
+        AnyOf<(&CurrencyText, &RerollCostText, &WeaponsCountText)>,
+    )>,
+) {
+    let (currency, children) = player.into_inner();
+    let weapons_count = weapon_query.iter_many(children).count();
+    for (mut text, (currency_text, reroll_text, weapons_text)) in &mut texts {
+        let value = if currency_text.is_some() {
+            format!("Gold: {}", currency.0)
+        } else if reroll_text.is_some() {
+            format!("Reroll ({})", shop.reroll_cost(wave_manager.wave))
+        } else if weapons_text.is_some() {
+            format!("Weapons: {}/{}", weapons_count, MAX_WEAPONS)
+        } else {
+            continue;
+        };
+        if text.0 != value {
+            text.0 = value;
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn update_shop_button_interaction(
+    mut query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (
+            Changed<Interaction>,
+            Or<(
+                With<ShopBuyButton>,
+                With<ShopLockButton>,
+                With<RerollButton>,
+            )>,
+        ),
+    >,
+) {
+    for (interaction, mut bg) in &mut query {
+        *bg = BackgroundColor(match interaction {
+            Interaction::Hovered | Interaction::Pressed => BG_BUTTON_HOVER,
+            Interaction::None => BG_BUTTON,
+        });
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+fn offer_bundle(
+    index: usize,
+    offer: &ShopOffer,
+    library: &WeaponsLibrary,
+    currency: u32,
+) -> impl Bundle {
+    let (tag, name, description, color) = match offer.item {
+        ShopItem::Weapon(kind) => {
+            let definition = library.get(kind);
+            (
+                "WEAPON",
+                display_name(&definition.id),
+                format!(
+                    "{:.0} damage x{}\nevery {:.2}s",
+                    definition.damage, definition.projectile_count, definition.cooldown
+                ),
+                definition.color(),
+            )
+        }
+        ShopItem::Passive(item) => (
+            "ITEM",
+            item.name.to_string(),
+            item.description(),
+            Color::srgb(0.3, 0.9, 0.45),
+        ),
+    };
+    let (price, price_color) = if offer.sold {
+        ("SOLD".to_string(), GREY)
+    } else if offer.price > currency {
+        (format!("{} gold", offer.price), RED)
+    } else {
+        (format!("{} gold", offer.price), GOLD.into())
+    };
+    let border = if offer.locked { GOLD.into() } else { color };
+    let lock_label = if offer.locked { "Unlock" } else { "Lock" };
+    let key_label = ["1", "2", "3", "4"].get(index).copied().unwrap_or("?");
+
+    (
+        Node {
+            width: Val::Px(CARD_W),
+            height: Val::Px(CARD_H),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(10.0),
+            padding: UiRect::all(Val::Px(14.0)),
+            border: UiRect::all(Val::Px(2.0)),
+            ..default()
+        },
+        BackgroundColor(BG_CARD),
+        BorderColor::all(border.with_alpha(if offer.sold { 0.3 } else { 1.0 })),
+        children![
+            (
+                Node {
+                    width: Val::Percent(100.0),
+                    justify_content: JustifyContent::SpaceBetween,
+                    ..default()
+                },
+                children![
+                    TextBundle::new(tag, 14.0, GREY),
+                    TextBundle::new(key_label, 14.0, Color::WHITE),
+                ],
+            ),
+            TextBundle::new(name, 24.0, Color::WHITE),
+            (
+                TextBundle::new(description, 16.0, GREY),
+                TextLayout::new_with_justify(Justify::Center),
+                Node {
+                    flex_grow: 1.0,
+                    ..default()
+                },
+            ),
+            TextBundle::new(price, 22.0, price_color),
+            (
+                Node {
+                    column_gap: Val::Px(10.0),
+                    ..default()
+                },
+                children![
+                    shop_button(ShopBuyButton(index), "Buy"),
+                    shop_button(ShopLockButton(index), lock_label),
+                ],
+            ),
+        ],
+    )
+}
+
+fn shop_button(marker: impl Component, label: &str) -> impl Bundle {
+    (
+        marker,
+        Button,
+        Node {
+            width: Val::Px(85.0),
+            height: Val::Px(36.0),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            border: UiRect::all(Val::Px(2.0)),
+            ..default()
+        },
+        BackgroundColor(BG_BUTTON),
+        BorderColor::all(GREY),
+        children![TextBundle::new(label, 18.0, Color::WHITE)],
+    )
+}
+
+/// "machine_gun" -> "Machine Gun"
+fn display_name(id: &str) -> String {
+    id.split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
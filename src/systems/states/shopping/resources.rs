@@ -0,0 +1,343 @@
+use crate::systems::constants::MAX_WEAPONS;
+use crate::systems::game::GameRng;
+use crate::systems::hud::components::DisplayStatKind;
+use crate::systems::states::waves::player::components::StatKind;
+use crate::systems::states::waves::weapons::kinds::WeaponKind;
+use crate::systems::states::waves::weapons::resources::WeaponsLibrary;
+use bevy::prelude::Resource;
+use rand::RngExt;
+
+/// Number of offers displayed by the shop.
+pub const SHOP_SLOTS: usize = 4;
+
+/// Odds for a restocked slot to offer a weapon rather than a passive item.
+pub const WEAPON_OFFER_CHANCE: f32 = 0.4;
+
+/// Reroll cost on the first reroll of a shop visit, before the wave is added.
+pub const REROLL_BASE_COST: u32 = 2;
+
+/// Extra cost for every reroll already done during the same shop visit.
+pub const REROLL_COST_STEP: u32 = 2;
+
+/// Stat bonus bought in the shop, applied like an upgrade card.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PassiveItem {
+    pub name: &'static str,
+    pub kind: StatKind,
+    pub value: f32,
+    pub price: u32,
+}
+
+impl PassiveItem {
+    pub fn description(&self) -> String {
+        let (_, name, _) = DisplayStatKind::from(self.kind).get_display_info();
+        match self.kind {
+            StatKind::MaxHealth => format!("+{:.0} max HP", self.value),
+            kind if kind.is_count() => format!("+{:.0} {}", self.value, name.to_lowercase()),
+            _ => format!("+{:.0}% {}", self.value * 100.0, name.to_lowercase()),
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct PassiveItemsPool {
+    pub items: Vec<PassiveItem>,
+}
+
+impl Default for PassiveItemsPool {
+    fn default() -> Self {
+        Self {
+            items: vec![
+                PassiveItem {
+                    name: "Whetstone",
+                    kind: StatKind::Damage,
+                    value: 0.10,
+                    price: 10,
+                },
+                PassiveItem {
+                    name: "Hair Trigger",
+                    kind: StatKind::FireRate,
+                    value: 0.10,
+                    price: 12,
+                },
+                PassiveItem {
+                    name: "Scope",
+                    kind: StatKind::Range,
+                    value: 0.15,
+                    price: 8,
+                },
+                PassiveItem {
+                    name: "Tough Hide",
+                    kind: StatKind::MaxHealth,
+                    value: 15.0,
+                    price: 10,
+                },
+                PassiveItem {
+                    name: "Light Boots",
+                    kind: StatKind::Speed,
+                    value: 0.10,
+                    price: 8,
+                },
+                PassiveItem {
+                    name: "Lucky Charm",
+                    kind: StatKind::CritChance,
+                    value: 0.05,
+                    price: 12,
+                },
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShopItem {
+    Weapon(WeaponKind),
+    Passive(PassiveItem),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ShopOffer {
+    pub item: ShopItem,
+    pub price: u32,
+    /// Kept through rerolls and into the next shop visit
+    pub locked: bool,
+    pub sold: bool,
+}
+
+/// Offers of the between-wave shop. Lives for the whole run so locked offers carry over.
+#[derive(Resource, Default)]
+pub struct Shop {
+    pub offers: Vec<ShopOffer>,
+    /// Rerolls done during the current shop visit
+    pub rerolls: u32,
+}
+
+impl Shop {
+    pub fn reroll_cost(&self, wave: u32) -> u32 {
+        REROLL_BASE_COST + wave + self.rerolls * REROLL_COST_STEP
+    }
+
+    /// Pays for the offer in `slot` and marks it sold. `None` when it is already sold, too
+    /// expensive, or a weapon while the player already carries `MAX_WEAPONS`.
+    pub fn buy(
+        &mut self,
+        slot: usize,
+        currency: &mut u32,
+        weapons_owned: usize,
+    ) -> Option<ShopItem> {
+        let offer = self.offers.get_mut(slot)?;
+        let weapon_limit =
+            matches!(offer.item, ShopItem::Weapon(_)) && weapons_owned >= MAX_WEAPONS;
+        if offer.sold || *currency < offer.price || weapon_limit {
+            return None;
+        }
+        *currency -= offer.price;
+        offer.sold = true;
+        offer.locked = false;
+        Some(offer.item)
+    }
+
+    pub fn toggle_lock(&mut self, slot: usize) {
+        if let Some(offer) = self.offers.get_mut(slot)
+            && !offer.sold
+        {
+            offer.locked ^= true;
+        }
+    }
+
+    /// Pays `reroll_cost` and restocks, every reroll of the visit making the next one pricier
+    pub fn reroll(
+        &mut self,
+        wave: u32,
+        currency: &mut u32,
+        library: &WeaponsLibrary,
+        passives: &PassiveItemsPool,
+        rng: &mut GameRng,
+    ) -> bool {
+        let cost = self.reroll_cost(wave);
+        if *currency < cost {
+            return false;
+        }
+        *currency -= cost;
+        self.rerolls += 1;
+        self.restock(wave, library, passives, rng);
+        true
+    }
+
+    /// Replaces every offer that is not locked (or already sold) with a new random one.
+    pub fn restock(
+        &mut self,
+        wave: u32,
+        library: &WeaponsLibrary,
+        passives: &PassiveItemsPool,
+        rng: &mut GameRng,
+    ) {
+        self.offers.resize_with(SHOP_SLOTS, || ShopOffer {
+            item: ShopItem::Passive(passives.items[0]),
+            price: 0,
+            locked: false,
+            sold: true,
+        });
+        for offer in &mut self.offers {
+            if offer.locked && !offer.sold {
+                continue;
+            }
+            let item = if !library.is_empty() && rng.random_bool(WEAPON_OFFER_CHANCE as f64) {
+                ShopItem::Weapon(WeaponKind(rng.random_range(0..library.len())))
+            } else {
+                ShopItem::Passive(passives.items[rng.random_range(0..passives.items.len())])
+            };
+            let base_price = match item {
+                ShopItem::Weapon(kind) => library.get(kind).price,
+                ShopItem::Passive(passive) => passive.price,
+            };
+            *offer = ShopOffer {
+                item,
+                price: wave_price(base_price, wave),
+                locked: false,
+                sold: false,
+            };
+        }
+    }
+}
+
+/// Prices go up by 10% every wave so the currency keeps its value late in a run.
+fn wave_price(base_price: u32, wave: u32) -> u32 {
+    (base_price as f32 * (1.0 + 0.1 * wave as f32)).round() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn library() -> WeaponsLibrary {
+        WeaponsLibrary::from_assets(&["pistol"])
+    }
+
+    fn offer(item: ShopItem, price: u32) -> ShopOffer {
+        ShopOffer {
+            item,
+            price,
+            locked: false,
+            sold: false,
+        }
+    }
+
+    fn passive() -> ShopItem {
+        ShopItem::Passive(PassiveItemsPool::default().items[0])
+    }
+
+    #[test]
+    fn prices_grow_ten_percent_per_wave() {
+        assert_eq!(wave_price(10, 0), 10);
+        assert_eq!(wave_price(10, 1), 11);
+        assert_eq!(wave_price(12, 5), 18);
+        assert_eq!(wave_price(8, 3), 10);
+    }
+
+    #[test]
+    fn restock_fills_every_slot_at_wave_prices() {
+        let library = library();
+        let passives = PassiveItemsPool::default();
+        let mut shop = Shop::default();
+        shop.restock(4, &library, &passives, &mut GameRng::new(Some(1)));
+
+        assert_eq!(shop.offers.len(), SHOP_SLOTS);
+        for offer in &shop.offers {
+            assert!(!offer.sold && !offer.locked);
+            let base = match offer.item {
+                ShopItem::Weapon(kind) => library.get(kind).price,
+                ShopItem::Passive(item) => item.price,
+            };
+            assert_eq!(offer.price, wave_price(base, 4));
+        }
+    }
+
+    #[test]
+    fn restock_keeps_locked_offers_only() {
+        let library = library();
+        let passives = PassiveItemsPool::default();
+        let mut rng = GameRng::new(Some(2));
+        let mut shop = Shop::default();
+        shop.restock(1, &library, &passives, &mut rng);
+        // A price no restock can produce tells the kept offers apart
+        for offer in &mut shop.offers {
+            offer.price = 999;
+        }
+        shop.offers[1].locked = true;
+        shop.offers[2].locked = true;
+        shop.offers[2].sold = true;
+        shop.restock(1, &library, &passives, &mut rng);
+
+        assert_eq!(shop.offers[1].price, 999);
+        assert!(shop.offers[1].locked);
+        for slot in [0, 2, 3] {
+            assert_ne!(shop.offers[slot].price, 999, "slot {slot} was kept");
+            assert!(!shop.offers[slot].locked && !shop.offers[slot].sold);
+        }
+    }
+
+    #[test]
+    fn every_reroll_of_a_visit_costs_more() {
+        let library = library();
+        let passives = PassiveItemsPool::default();
+        let mut rng = GameRng::new(Some(3));
+        let mut shop = Shop::default();
+        let mut currency = 100;
+
+        assert_eq!(shop.reroll_cost(3), REROLL_BASE_COST + 3);
+        assert!(shop.reroll(3, &mut currency, &library, &passives, &mut rng));
+        assert_eq!(currency, 100 - (REROLL_BASE_COST + 3));
+        assert_eq!(shop.reroll_cost(3), REROLL_BASE_COST + 3 + REROLL_COST_STEP);
+
+        let mut broke = shop.reroll_cost(3) - 1;
+        assert!(!shop.reroll(3, &mut broke, &library, &passives, &mut rng));
+        assert_eq!(shop.rerolls, 1);
+    }
+
+    #[test]
+    fn buying_pays_once_and_clears_the_lock() {
+        let mut shop = Shop {
+            offers: vec![offer(passive(), 10)],
+            rerolls: 0,
+        };
+        shop.toggle_lock(0);
+        let mut currency = 15;
+
+        assert_eq!(shop.buy(0, &mut currency, 0), Some(passive()));
+        assert_eq!(currency, 5);
+        assert!(shop.offers[0].sold && !shop.offers[0].locked);
+        assert_eq!(shop.buy(0, &mut currency, 0), None);
+        // Sold offers can't be locked anymore
+        shop.toggle_lock(0);
+        assert!(!shop.offers[0].locked);
+    }
+
+    #[test]
+    fn offers_out_of_budget_are_not_sold() {
+        let mut shop = Shop {
+            offers: vec![offer(passive(), 10)],
+            rerolls: 0,
+        };
+        let mut currency = 9;
+        assert_eq!(shop.buy(0, &mut currency, 0), None);
+        assert_eq!(currency, 9);
+        assert!(!shop.offers[0].sold);
+        assert_eq!(shop.buy(7, &mut currency, 0), None);
+    }
+
+    #[test]
+    fn weapons_stop_selling_at_max_weapons() {
+        let weapon = ShopItem::Weapon(WeaponKind(0));
+        let mut shop = Shop {
+            offers: vec![offer(weapon, 5), offer(passive(), 5)],
+            rerolls: 0,
+        };
+        let mut currency = 100;
+        assert_eq!(shop.buy(0, &mut currency, MAX_WEAPONS), None);
+        // Passive items are not limited
+        assert_eq!(shop.buy(1, &mut currency, MAX_WEAPONS), Some(passive()));
+        assert_eq!(shop.buy(0, &mut currency, MAX_WEAPONS - 1), Some(weapon));
+        assert_eq!(currency, 90);
+    }
+}
@@ -1,13 +1,112 @@
-use crate::systems::game::GameState;
+use crate::systems::game::{GameRng, GameState};
 use crate::systems::input::resources::ActionState;
-use crate::systems::states::shopping::components::NextWaveButton;
+use crate::systems::states::shopping::components::{
+    NextWaveButton, RerollButton, ShopBuyButton, ShopLockButton,
+};
+use crate::systems::states::shopping::resources::{PassiveItemsPool, Shop, ShopItem};
 use crate::systems::states::waves::components::Health;
 use crate::systems::states::waves::player::components::{Player, PlayerStats};
+use crate::systems::states::waves::player::currency::Currency;
 use crate::systems::states::waves::player::experience::PlayerExperience;
 use crate::systems::states::waves::resources::WaveManager;
-use bevy::prelude::{Changed, NextState, Query, Res, ResMut, With};
-use bevy::ui::Interaction;
-use std::time::Duration;
+use crate::systems::states::waves::weapons::components::{Weapon, WeaponCooldown};
+use crate::systems::states::waves::weapons::messages::WeaponSpawnedMessage;
+use crate::systems::states::waves::weapons::resources::WeaponsLibrary;
+use crate::systems::states::waves::weapons::systems::equip_weapon;
+use bevy::prelude::*;
+use bevy::time::TimerMode::Repeating;
+
+pub fn restock_shop(
+    mut shop: ResMut<Shop>,
+    wave_manager: Res<WaveManager>,
+    library: Res<WeaponsLibrary>,
+    passives: Res<PassiveItemsPool>,
+    mut rng: ResMut<GameRng>,
+) {
+    shop.rerolls = 0;
+    shop.restock(wave_manager.wave, &library, &passives, &mut rng);
+}
+
+/// Buy, lock and reroll, from the keyboard / gamepad shortcuts or the shop buttons
+#[allow(clippy::too_many_arguments)]
+pub fn handle_shop_actions(
+    mut commands: Commands,
+    actions: Res<ActionState>,
+    mut shop: ResMut<Shop>,
+    wave_manager: Res<WaveManager>,
+    library: Res<WeaponsLibrary>,
+    passives: Res<PassiveItemsPool>,
+    mut rng: ResMut<GameRng>,
+    player: Single<(Entity, &Children, &mut Currency, &mut PlayerStats), With<Player>>,
+    mut weapon_query: Query<(&mut Weapon, &mut WeaponCooldown)>,
+    buy_query: Query<(&Interaction, &ShopBuyButton), Changed<Interaction>>,
+    lock_query: Query<(&Interaction, &ShopLockButton), Changed<Interaction>>,
+    reroll_query: Query<&Interaction, (Changed<Interaction>, With<RerollButton>)>,
+    mut events: MessageWriter<WeaponSpawnedMessage>,
+) {
+    let (player, children, mut currency, mut stats) = player.into_inner();
+
+    let mut buy = actions.shop_buy;
+    let mut lock = actions.shop_lock;
+    for (interaction, button) in &buy_query {
+        if *interaction == Interaction::Pressed
+            && let Some(slot) = buy.get_mut(button.0)
+        {
+            *slot = true;
+        }
+    }
+    for (interaction, button) in &lock_query {
+        if *interaction == Interaction::Pressed
+            && let Some(slot) = lock.get_mut(button.0)
+        {
+            *slot = true;
+        }
+    }
+    let reroll = actions.shop_reroll || reroll_query.iter().any(|i| *i == Interaction::Pressed);
+
+    for (index, _) in lock.iter().enumerate().filter(|(_, pressed)| **pressed) {
+        shop.toggle_lock(index);
+    }
+
+    let mut weapons_count = weapon_query.iter_many(children).count();
+    for (index, _) in buy.iter().enumerate().filter(|(_, pressed)| **pressed) {
+        let Some(item) = shop.buy(index, &mut currency.0, weapons_count) else {
+            continue;
+        };
+        match item {
+            ShopItem::Weapon(kind) => {
+                events.write(equip_weapon(
+                    &mut commands,
+                    &library,
+                    kind,
+                    player,
+                    &stats,
+                    weapons_count,
+                ));
+                weapons_count += 1;
+            }
+            ShopItem::Passive(item) => {
+                stats.add(item.kind, item.value);
+                // Only the player's own weapons pick up the new stats
+                let mut weapons = weapon_query.iter_many_mut(children);
+                while let Some((mut weapon, mut cooldown)) = weapons.fetch_next() {
+                    weapon.apply_stats(&stats);
+                    cooldown.timer = Timer::from_seconds(weapon.cooldown(), Repeating);
+                }
+            }
+        }
+    }
+
+    if reroll {
+        shop.reroll(
+            wave_manager.wave,
+            &mut currency.0,
+            &library,
+            &passives,
+            &mut rng,
+        );
+    }
+}
 
 pub fn start_next_wave(
     actions: Res<ActionState>,
@@ -23,11 +122,7 @@ pub fn start_next_wave(
         return;
     }
 
-    let wave_duration = wave_manager.wave_timer.duration().as_secs_f32();
     wave_manager.wave += 1;
-    wave_manager
-        .wave_timer
-        .set_duration(Duration::from_secs_f32((wave_duration * 1.1).min(90.)));
 
     for (stats, mut xp, mut health) in &mut player_query {
         xp.new_levels = 0;
@@ -12,19 +12,11 @@ use bevy::prelude::{
 };
 use std::f32::consts::TAU;
 
-#[derive(Component, Debug)]
+#[derive(Component, Debug, Default)]
 pub struct UpgradeCardAnimation {
     pub timer: Timer,
 }
 
-impl Default for UpgradeCardAnimation {
-    fn default() -> Self {
-        Self {
-            timer: Timer::default(),
-        }
-    }
-}
-
 pub fn animate_upgrade_cards(
     mut card_query: Query<(
         &Children,
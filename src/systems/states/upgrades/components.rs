@@ -75,6 +75,7 @@ impl UpgradeCard {
         let (texture_index, name, color) = display.get_display_info();
         let description = match self.kind {
             StatKind::MaxHealth => format!("+{:.0} max HP", self.value),
+            kind if kind.is_count() => format!("+{:.0} {}", self.value, name.to_lowercase()),
             _ => format!("+{:.0}% {}", self.value * 100.0, name.to_lowercase()),
         };
         (texture_index, description, color)
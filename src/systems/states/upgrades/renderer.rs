@@ -1,6 +1,6 @@
 // src/systems/upgrades/renderer
 use crate::systems::constants::NB_UPDATES_PER_LEVEL;
-use crate::systems::game::{GameState, TextBundle};
+use crate::systems::game::{GameRng, GameState, TextBundle};
 use crate::systems::hud::resources::HUDTextureAtlas;
 use crate::systems::input::resources::{ActiveInputDevice, GamepadAsset};
 use crate::systems::states::upgrades::animations::UpgradeCardAnimation;
@@ -25,6 +25,7 @@ pub fn spawn_upgrades_selection_ui(
     player_query: Query<&player::experience::PlayerExperience, With<Player>>,
     sprites: Res<HUDTextureAtlas>,
     gamepad_asset: Res<GamepadAsset>,
+    mut rng: ResMut<GameRng>,
     mut next_state: ResMut<NextState<GameState>>,
 ) {
     let Ok(player_xp) = player_query.single() else {
@@ -34,7 +35,7 @@ pub fn spawn_upgrades_selection_ui(
         next_state.set(GameState::Shopping);
         return;
     }
-    let upgrades = upgrade_pool.generate_upgrades(NB_UPDATES_PER_LEVEL);
+    let upgrades = upgrade_pool.generate_upgrades(NB_UPDATES_PER_LEVEL, &mut rng);
 
     commands.spawn((
         UpgradeSelectionUI,
@@ -92,12 +93,13 @@ pub fn redraw_upgrades_selection(
     sprites: Res<HUDTextureAtlas>,
     gamepad_asset: Res<GamepadAsset>,
     mut redraw_cards_pool: ResMut<RedrawCardsPool>,
+    mut rng: ResMut<GameRng>,
 ) {
     if !redraw_cards_pool.0 {
         return;
     }
 
-    let upgrades = upgrade_pool.generate_upgrades(NB_UPDATES_PER_LEVEL);
+    let upgrades = upgrade_pool.generate_upgrades(NB_UPDATES_PER_LEVEL, &mut rng);
     commands.entity(ui.entity()).despawn_children();
     commands.entity(ui.entity()).with_children(|parent| {
         parent.spawn(TextBundle::new("LEVEL UP", 52.0, Color::WHITE));
@@ -125,7 +127,7 @@ fn deck_bundle(
                 ..default()
             },
             children![
-                card_bundle(0, *upgrades.get(0).unwrap(), sprites, gamepad_asset),
+                card_bundle(0, *upgrades.first().unwrap(), sprites, gamepad_asset),
                 card_bundle(1, *upgrades.get(1).unwrap(), sprites, gamepad_asset),
                 card_bundle(2, *upgrades.get(2).unwrap(), sprites, gamepad_asset),
                 card_bundle(3, *upgrades.get(3).unwrap(), sprites, gamepad_asset),
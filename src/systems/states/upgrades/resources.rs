@@ -1,3 +1,4 @@
+use crate::systems::game::GameRng;
 use crate::systems::states::upgrades::components::{UpgradeCard, UpgradeRarity};
 use crate::systems::states::waves::player::components::StatKind;
 use bevy::prelude::Resource;
@@ -19,16 +20,20 @@ impl Default for UpgradeCardsPool {
                 UpgradeCard::new(StatKind::Range, 0.25, UpgradeRarity::Common),
                 UpgradeCard::new(StatKind::MaxHealth, 20.0, UpgradeRarity::Legendary),
                 UpgradeCard::new(StatKind::Speed, 0.15, UpgradeRarity::Uncommon),
+                UpgradeCard::new(StatKind::CritChance, 0.05, UpgradeRarity::Uncommon),
+                UpgradeCard::new(StatKind::CritMultiplier, 0.25, UpgradeRarity::Rare),
+                UpgradeCard::new(StatKind::Pierce, 1.0, UpgradeRarity::Rare),
+                UpgradeCard::new(StatKind::Bounce, 1.0, UpgradeRarity::Rare),
+                UpgradeCard::new(StatKind::Chain, 1.0, UpgradeRarity::Legendary),
             ],
         }
     }
 }
 
 impl UpgradeCardsPool {
-    pub fn generate_upgrades(&self, count: usize) -> Vec<UpgradeCard> {
+    pub fn generate_upgrades(&self, count: usize, rng: &mut GameRng) -> Vec<UpgradeCard> {
         let weights: Vec<f32> = self.upgrades.iter().map(|u| u.rarity.get_odds()).collect();
 
-        let mut rng = rand::rng();
         let mut selected = Vec::new();
 
         for _ in 0..count {
@@ -4,7 +4,7 @@ use crate::systems::states::upgrades::animations::UpgradeCardAnimation;
 use crate::systems::states::upgrades::components::UpgradeCardState::*;
 use crate::systems::states::upgrades::components::*;
 use crate::systems::states::upgrades::resources::RedrawCardsPool;
-use crate::systems::states::waves::player::components::{Player, PlayerStats, StatKind};
+use crate::systems::states::waves::player::components::{Player, PlayerStats};
 use crate::systems::states::waves::player::experience::PlayerExperience;
 use crate::systems::states::waves::weapons::components::{Weapon, WeaponCooldown};
 use bevy::prelude::TimerMode::Once;
@@ -90,23 +90,12 @@ pub fn apply_active_upgrade_card(
         }
 
         // Apply stat bonus
-        match card.kind {
-            StatKind::Damage => stats.damage_multiplier += card.value,
-            StatKind::FireRate => stats.fire_rate_multiplier += card.value,
-            StatKind::Range => stats.range_multiplier += card.value,
-            StatKind::MaxHealth => stats.max_health += card.value,
-            StatKind::Speed => stats.speed_multiplier += card.value,
-        }
+        stats.add(card.kind, card.value);
 
         // Propagate to all weapons immediately
         for (mut weapon, mut cooldown) in weapon_query.iter_mut() {
-            weapon.damage_multiplier = stats.damage_multiplier;
-            weapon.fire_rate_multiplier = stats.fire_rate_multiplier;
-            weapon.range_multiplier = stats.range_multiplier;
-            cooldown.timer = Timer::from_seconds(
-                weapon.base_cooldown / weapon.fire_rate_multiplier,
-                Repeating,
-            );
+            weapon.apply_stats(&stats);
+            cooldown.timer = Timer::from_seconds(weapon.cooldown(), Repeating);
         }
 
         card.state = Applied;
@@ -1,46 +1,239 @@
-use crate::systems::game::MarkedForDespawn;
+use crate::systems::constants::tiles_to_pixels;
+use crate::systems::game::{GameRng, MarkedForDespawn};
 use crate::systems::states::waves::components::{Dying, Health};
 use crate::systems::states::waves::enemy::components::{Enemy, Hostile};
-use crate::systems::states::waves::player::components::Player;
+use crate::systems::states::waves::enemy::elites::{damage_enemy, Shield, Vampiric};
+use crate::systems::states::waves::enemy::resources::EnemyRegistry;
+use crate::systems::states::waves::player::components::{Dash, Player};
+use crate::systems::states::waves::spatial::{SpatialGrid, SPATIAL_CELL_SIZE};
+use crate::systems::states::waves::status::StatusEffects;
 use crate::systems::states::waves::weapons::components::Bullet;
+use crate::systems::states::waves::weapons::messages::{ChainLightningMessage, DamageDealtMessage};
 use bevy::prelude::*;
+use rand::RngExt;
 
 const COLLISION_RADIUS_SQ: f32 = 20.0 * 20.0;
+const PLAYER_HALF_WIDTH: f32 = 12.0;
+/// Distance (px) a ricochet looks for its next target
+const BOUNCE_RANGE: f32 = tiles_to_pixels(4.0);
+/// Distance (px) a chain lightning can jump between two enemies
+const CHAIN_RANGE: f32 = tiles_to_pixels(3.0);
+/// Share of the bullet damage dealt by every chain lightning jump
+const CHAIN_DAMAGE_FACTOR: f32 = 0.6;
 
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn check_bullet_enemy_collision(
     mut commands: Commands,
-    bullet_query: Query<(Entity, &GlobalTransform, &Bullet), Without<Hostile>>,
-    mut enemy_query: Query<(&GlobalTransform, &mut Health, &Enemy)>,
+    mut bullet_query: Query<
+        (Entity, &GlobalTransform, &mut Bullet),
+        (Without<Hostile>, Without<MarkedForDespawn>),
+    >,
+    mut enemy_query: Query<(
+        &GlobalTransform,
+        &mut Health,
+        &Enemy,
+        &mut StatusEffects,
+        Option<&mut Shield>,
+    )>,
+    registry: Res<EnemyRegistry>,
+    grid: Res<SpatialGrid>,
+    mut rng: ResMut<GameRng>,
+    mut damage_events: MessageWriter<DamageDealtMessage>,
+    mut chain_events: MessageWriter<ChainLightningMessage>,
 ) {
-    for (bullet_entity, bullet_transform, bullet) in &bullet_query {
+    // Enemies moved since the grid was filled, one cell of slack keeps them in reach
+    let reach = registry.max_radius() + SPATIAL_CELL_SIZE;
+    for (bullet_entity, bullet_transform, mut bullet) in &mut bullet_query {
         let bullet_pos = bullet_transform.translation();
 
-        for (enemy_transform, mut enemy_health, enemy) in &mut enemy_query {
-            let radius = enemy.kind.visual().radius;
-            let delta = bullet_pos.truncate() - enemy_transform.translation().truncate();
+        // Only enemies close enough to possibly touch the bullet, nearest first
+        let mut candidates: Vec<(Entity, Vec2)> =
+            grid.query_radius(bullet_pos.truncate(), reach).collect();
+        candidates.sort_by(|(_, a), (_, b)| {
+            a.distance_squared(bullet_pos.truncate())
+                .total_cmp(&b.distance_squared(bullet_pos.truncate()))
+        });
+
+        for (enemy_entity, _) in candidates {
+            if bullet.hits.contains(&enemy_entity) {
+                continue;
+            }
+            let Ok((enemy_transform, mut enemy_health, enemy, mut effects, mut shield)) =
+                enemy_query.get_mut(enemy_entity)
+            else {
+                continue;
+            };
+            // Already killed this frame by another bullet, it is only waiting to be despawned
+            if enemy_health.value <= 0.0 {
+                continue;
+            }
+            let radius = registry.get(enemy.kind).visual().radius;
+            let enemy_pos = enemy_transform.translation().truncate();
+            let delta = bullet_pos.truncate() - enemy_pos;
+            if delta.length_squared() >= radius * radius {
+                continue;
+            }
+
+            // Rolled per hit, so each enemy a piercing bullet goes through can crit
+            let critical =
+                bullet.crit_chance > 0.0 && rng.random_bool(bullet.crit_chance.min(1.0) as f64);
+            let damage = if critical {
+                bullet.current_damage() * bullet.crit_multiplier
+            } else {
+                bullet.current_damage()
+            };
+            damage_enemy(&mut enemy_health, shield.as_deref_mut(), damage);
+            damage_events.write(DamageDealtMessage {
+                amount: damage,
+                position: enemy_pos,
+                critical,
+                effect: None,
+            });
+            for effect in &bullet.effects {
+                if effect.chance >= 1.0 || rng.random_bool(effect.chance.max(0.0) as f64) {
+                    effects.apply(effect);
+                }
+            }
+            bullet.hits.push(enemy_entity);
+
+            if bullet.chains > 0 {
+                chain_lightning(
+                    &mut bullet,
+                    enemy_pos,
+                    &grid,
+                    &mut enemy_query,
+                    &mut damage_events,
+                    &mut chain_events,
+                );
+            }
 
-            if delta.length_squared() < radius * radius {
-                commands.entity(bullet_entity).insert(MarkedForDespawn);
-                enemy_health.value = (enemy_health.value - bullet.damage).max(0.0);
+            if bullet.pierce > 0 {
+                bullet.pierce -= 1;
+                continue;
+            }
+            if bullet.bounces > 0
+                && let Some(target) =
+                    nearest_unhit(&bullet, enemy_pos, BOUNCE_RANGE, &grid, &enemy_query)
+            {
+                bullet.bounces -= 1;
+                bullet.direction = (target - bullet_pos.truncate()).normalize_or(bullet.direction);
                 break;
             }
+            commands.entity(bullet_entity).insert(MarkedForDespawn);
+            break;
         }
     }
 }
 
+/// Jumps from the enemy at `from` to the nearest enemies the bullet has not hit yet, one after
+/// the other, dealing a fraction of the bullet damage to each.
+fn chain_lightning(
+    bullet: &mut Bullet,
+    mut from: Vec2,
+    grid: &SpatialGrid,
+    enemy_query: &mut Query<(
+        &GlobalTransform,
+        &mut Health,
+        &Enemy,
+        &mut StatusEffects,
+        Option<&mut Shield>,
+    )>,
+    damage_events: &mut MessageWriter<DamageDealtMessage>,
+    chain_events: &mut MessageWriter<ChainLightningMessage>,
+) {
+    let damage = bullet.current_damage() * CHAIN_DAMAGE_FACTOR;
+    for _ in 0..bullet.chains {
+        let Some((target, target_pos)) = grid
+            .query_radius(from, CHAIN_RANGE)
+            .filter(|(entity, _)| !bullet.hits.contains(entity))
+            .filter(|(entity, _)| {
+                enemy_query
+                    .get(*entity)
+                    .is_ok_and(|(_, health, _, _, _)| health.value > 0.0)
+            })
+            .min_by(|(_, a), (_, b)| {
+                a.distance_squared(from)
+                    .total_cmp(&b.distance_squared(from))
+            })
+        else {
+            return;
+        };
+        let Ok((_, mut health, _, _, mut shield)) = enemy_query.get_mut(target) else {
+            return;
+        };
+        damage_enemy(&mut health, shield.as_deref_mut(), damage);
+        damage_events.write(DamageDealtMessage {
+            amount: damage,
+            position: target_pos,
+            critical: false,
+            effect: None,
+        });
+        chain_events.write(ChainLightningMessage {
+            from,
+            to: target_pos,
+        });
+        bullet.hits.push(target);
+        from = target_pos;
+    }
+}
+
+/// Position of the nearest living enemy within `range` the bullet has not hit yet
+fn nearest_unhit(
+    bullet: &Bullet,
+    from: Vec2,
+    range: f32,
+    grid: &SpatialGrid,
+    enemy_query: &Query<(
+        &GlobalTransform,
+        &mut Health,
+        &Enemy,
+        &mut StatusEffects,
+        Option<&mut Shield>,
+    )>,
+) -> Option<Vec2> {
+    grid.query_radius(from, range)
+        .filter(|(entity, _)| !bullet.hits.contains(entity))
+        .filter(|(entity, _)| {
+            enemy_query
+                .get(*entity)
+                .is_ok_and(|(_, health, _, _, _)| health.value > 0.0)
+        })
+        .map(|(_, position)| position)
+        .min_by(|a, b| {
+            a.distance_squared(from)
+                .total_cmp(&b.distance_squared(from))
+        })
+}
+
+#[allow(clippy::type_complexity)]
 pub fn check_player_enemy_collision(
     mut commands: Commands,
-    enemy_query: Query<(&GlobalTransform, &Enemy)>,
-    bullet_query: Query<(Entity, &GlobalTransform, &Bullet), With<Hostile>>,
+    mut enemy_query: Query<(
+        &GlobalTransform,
+        &Enemy,
+        &StatusEffects,
+        Option<&mut Vampiric>,
+    )>,
+    bullet_query: Query<
+        (Entity, &GlobalTransform, &Bullet),
+        (With<Hostile>, Without<MarkedForDespawn>),
+    >,
     mut player_query: Query<
-        (Entity, &GlobalTransform, &mut Health),
+        (Entity, &GlobalTransform, &mut Health, &Dash),
         (With<Player>, Without<Dying>),
     >,
+    registry: Res<EnemyRegistry>,
+    grid: Res<SpatialGrid>,
     time: Res<Time>,
 ) {
-    let Ok((player_entity, player_transform, mut player_health)) = player_query.single_mut() else {
+    let Ok((player_entity, player_transform, mut player_health, dash)) = player_query.single_mut()
+    else {
         return;
     };
+    // Enemies and their bullets pass through a dashing player
+    if dash.is_invulnerable() {
+        return;
+    }
     let player_pos = player_transform.translation().truncate();
 
     // Check enemy bullets
@@ -48,7 +241,7 @@ pub fn check_player_enemy_collision(
         let delta = player_pos - bullet_transform.translation().truncate();
         if delta.length_squared() < COLLISION_RADIUS_SQ {
             commands.entity(bullet_entity).insert(MarkedForDespawn);
-            player_health.value = (player_health.value - bullet.damage).max(0.0);
+            player_health.value = (player_health.value - bullet.current_damage()).max(0.0);
             if player_health.value <= 0.0 {
                 commands.entity(player_entity).insert(Dying {});
             }
@@ -56,12 +249,25 @@ pub fn check_player_enemy_collision(
     }
 
     // Check enemy body
-    for (enemy_transform, enemy) in &enemy_query {
-        let radius = enemy.kind.visual().radius + 12.0; // player half-width
+    let reach = registry.max_radius() + PLAYER_HALF_WIDTH;
+    for (enemy_entity, _) in grid.query_radius(player_pos, reach) {
+        let Ok((enemy_transform, enemy, effects, vampiric)) = enemy_query.get_mut(enemy_entity)
+        else {
+            continue;
+        };
+        // A frozen enemy doesn't attack, touching it is harmless
+        if effects.is_frozen() {
+            continue;
+        }
+        let radius = registry.get(enemy.kind).visual().radius + PLAYER_HALF_WIDTH;
         let distance_sq = player_pos.distance_squared(enemy_transform.translation().truncate());
 
         if distance_sq < radius * radius {
-            player_health.value -= enemy.damage * time.delta_secs();
+            let damage = enemy.damage * time.delta_secs();
+            player_health.value -= damage;
+            if let Some(mut vampiric) = vampiric {
+                vampiric.drained += damage;
+            }
             if player_health.value <= 0.0 {
                 player_health.value = 0.0;
                 commands.entity(player_entity).insert(Dying {});
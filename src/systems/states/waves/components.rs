@@ -1,3 +1,4 @@
+use bevy::math::Vec2;
 use bevy::prelude::Component;
 #[derive(Component)]
 pub struct LevelBackground;
@@ -23,6 +24,7 @@ pub struct Dying;
 pub struct BackgroundMusic;
 
 #[derive(Component, PartialEq, Clone, Copy, Debug, Default, Hash, Eq)]
+#[allow(clippy::upper_case_acronyms)]
 pub enum Action {
     #[default]
     IDLE,
@@ -32,6 +34,7 @@ pub enum Action {
 }
 
 #[derive(Component, Clone, Copy, Debug, Eq, Default, PartialEq, Hash)]
+#[allow(clippy::upper_case_acronyms)]
 pub enum Direction {
     #[default]
     EAST,
@@ -43,3 +46,34 @@ pub enum Direction {
     SOUTHWEST,
     WEST,
 }
+
+impl Direction {
+    /// Unit vector pointing toward this direction
+    pub fn vector(&self) -> Vec2 {
+        match self {
+            Direction::EAST => Vec2::X,
+            Direction::NORTH => Vec2::Y,
+            Direction::NORTHEAST => Vec2::new(1.0, 1.0).normalize(),
+            Direction::NORTHWEST => Vec2::new(-1.0, 1.0).normalize(),
+            Direction::SOUTH => Vec2::NEG_Y,
+            Direction::SOUTHEAST => Vec2::new(1.0, -1.0).normalize(),
+            Direction::SOUTHWEST => Vec2::new(-1.0, -1.0).normalize(),
+            Direction::WEST => Vec2::NEG_X,
+        }
+    }
+
+    pub fn iterator() -> impl Iterator<Item = Direction> {
+        [
+            Direction::EAST,
+            Direction::NORTH,
+            Direction::NORTHEAST,
+            Direction::NORTHWEST,
+            Direction::SOUTH,
+            Direction::SOUTHEAST,
+            Direction::SOUTHWEST,
+            Direction::WEST,
+        ]
+        .iter()
+        .copied()
+    }
+}
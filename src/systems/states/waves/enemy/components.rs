@@ -5,6 +5,7 @@ use bevy::prelude::{Component, Timer, TimerMode, Vec2};
 pub struct Spawning {
     pub timer: Timer,
     pub kind: EnemyKind,
+    pub elite: bool,
 }
 
 #[derive(Component)]
@@ -13,11 +14,21 @@ pub struct Enemy {
     pub speed: f32,
     pub kind: EnemyKind,
     pub xp_reward: u32,
+    pub currency_reward: u32,
 }
 
-#[derive(Component)]
+/// Keeps enemies from overlapping, see `movement::separate_enemies`
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Steering {
+    pub radius: f32,
+    pub separation_weight: f32,
+    pub mass: f32,
+}
+
+#[derive(Component, Clone, Copy, Debug)]
 pub struct Splitter {
     pub split_count: u32,
+    pub split_into: EnemyKind,
 }
 #[derive(Component)]
 pub struct RangedAttack {
@@ -50,3 +61,13 @@ impl Default for BossAttack {
         }
     }
 }
+
+/// Pulsing ring drawn behind an elite, one per modifier
+#[derive(Component)]
+pub struct EliteAura;
+
+/// Blast of an explosive elite, grows and fades out
+#[derive(Component)]
+pub struct Explosion {
+    pub lifetime: Timer,
+}
@@ -0,0 +1,352 @@
+use crate::systems::constants::tiles_to_pixels;
+use crate::systems::game::GameRng;
+use crate::systems::states::waves::components::{Dying, Health};
+use crate::systems::states::waves::enemy::components::Splitter;
+use crate::systems::states::waves::enemy::kinds::{EnemyKind, EnemyStats};
+use crate::systems::states::waves::enemy::messages::{EnemyDeathMessage, ExplosionMessage};
+use crate::systems::states::waves::player::components::{Dash, Player};
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use rand::RngExt;
+
+/// Odds of a regular spawn being an elite: base, growth per wave and cap
+const ELITE_BASE_CHANCE: f32 = 0.01;
+const ELITE_CHANCE_PER_WAVE: f32 = 0.004;
+const MAX_ELITE_CHANCE: f32 = 0.12;
+/// Odds of every modifier past the first, one more slot opens every `WAVES_PER_SLOT` waves
+const EXTRA_MODIFIER_CHANCE: f64 = 0.35;
+const WAVES_PER_SLOT: u32 = 4;
+const MAX_MODIFIERS: usize = 3;
+
+/// Elites are this many times sturdier than the regular version
+const ELITE_HEALTH_MULTIPLIER: f32 = 3.0;
+/// Extra multiple of the base rewards granted by every modifier
+const REWARD_PER_MODIFIER: u32 = 2;
+
+/// Shield capacity as a share of the health
+const SHIELD_RATIO: f32 = 0.5;
+const HASTE_MULTIPLIER: f32 = 1.4;
+/// Health healed back per point of contact damage dealt to the player
+const VAMPIRIC_RATIO: f32 = 1.5;
+const EXPLOSION_RADIUS: f32 = tiles_to_pixels(1.5);
+/// Explosion damage as a multiple of the contact damage (which is dealt per second)
+const EXPLOSION_DAMAGE_RATIO: f32 = 1.5;
+const SPLITTING_COUNT: u32 = 2;
+/// Share of the max health regenerated per second
+const REGENERATION_RATIO: f32 = 0.05;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EliteModifier {
+    /// Absorbs damage before the health is touched
+    Shielded,
+    /// Moves faster
+    Hasted,
+    /// Heals by touching the player
+    Vampiric,
+    /// Blows up on death, hurting the player nearby
+    Explosive,
+    /// Splits into regular copies of itself on death
+    Splitting,
+    /// Slowly heals back to full health
+    Regenerating,
+}
+
+impl EliteModifier {
+    const ALL: [EliteModifier; 6] = [
+        EliteModifier::Shielded,
+        EliteModifier::Hasted,
+        EliteModifier::Vampiric,
+        EliteModifier::Explosive,
+        EliteModifier::Splitting,
+        EliteModifier::Regenerating,
+    ];
+
+    /// Color of the aura drawn around an elite with this modifier
+    pub fn color(&self) -> Color {
+        match self {
+            EliteModifier::Shielded => Color::srgb(0.4, 0.8, 1.0),
+            EliteModifier::Hasted => Color::srgb(1.0, 0.95, 0.3),
+            EliteModifier::Vampiric => Color::srgb(0.8, 0.05, 0.2),
+            EliteModifier::Explosive => Color::srgb(1.0, 0.45, 0.1),
+            EliteModifier::Splitting => Color::srgb(0.6, 1.0, 0.4),
+            EliteModifier::Regenerating => Color::srgb(0.3, 1.0, 0.7),
+        }
+    }
+}
+
+/// Enemy rolled as an elite, the modifiers themselves are separate components.
+#[derive(Component, Debug, Clone)]
+pub struct Elite {
+    pub modifiers: Vec<EliteModifier>,
+}
+
+/// Takes the damage before the health does, see `damage_enemy`
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Shield {
+    pub value: f32,
+}
+
+impl Shield {
+    /// Soaks up as much of `damage` as the shield has left, returns the rest
+    pub fn absorb(&mut self, damage: f32) -> f32 {
+        let absorbed = damage.clamp(0.0, self.value);
+        self.value -= absorbed;
+        damage - absorbed
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Hasted;
+
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Vampiric {
+    pub max_health: f32,
+    /// Health drained from the player this frame, filled by the contact collision
+    pub drained: f32,
+}
+
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Explosive {
+    pub radius: f32,
+    pub damage: f32,
+}
+
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Regenerating {
+    pub max_health: f32,
+    pub per_second: f32,
+}
+
+/// Modifiers of a new enemy, empty for a regular one. `forced` elites (from a `WaveScript`
+/// event) always get at least one.
+pub fn roll_modifiers(forced: bool, wave: u32, rng: &mut GameRng) -> Vec<EliteModifier> {
+    let chance = (ELITE_BASE_CHANCE + wave as f32 * ELITE_CHANCE_PER_WAVE).min(MAX_ELITE_CHANCE);
+    if !forced && !rng.random_bool(chance as f64) {
+        return Vec::new();
+    }
+    let slots = (1 + (wave / WAVES_PER_SLOT) as usize).min(MAX_MODIFIERS);
+    let mut pool = EliteModifier::ALL.to_vec();
+    let mut modifiers = Vec::new();
+    while modifiers.len() < slots && !pool.is_empty() {
+        if !modifiers.is_empty() && !rng.random_bool(EXTRA_MODIFIER_CHANCE) {
+            break;
+        }
+        modifiers.push(pool.swap_remove(rng.random_range(0..pool.len())));
+    }
+    modifiers
+}
+
+/// Stats of an elite: sturdier, rewards growing with every modifier, faster when hasted
+pub fn elite_stats(stats: EnemyStats, modifiers: &[EliteModifier]) -> EnemyStats {
+    if modifiers.is_empty() {
+        return stats;
+    }
+    let reward_multiplier = 1 + REWARD_PER_MODIFIER * modifiers.len() as u32;
+    let speed = if modifiers.contains(&EliteModifier::Hasted) {
+        stats.speed * HASTE_MULTIPLIER
+    } else {
+        stats.speed
+    };
+    EnemyStats {
+        health: stats.health * ELITE_HEALTH_MULTIPLIER,
+        speed,
+        xp_reward: stats.xp_reward * reward_multiplier,
+        currency_reward: stats.currency_reward * reward_multiplier,
+        ..stats
+    }
+}
+
+/// Adds the `Elite` marker and one component per modifier.
+/// `splitter` is the split-on-death behaviour of the definition, if it has one.
+pub fn insert_modifiers(
+    entity_cmd: &mut EntityCommands,
+    modifiers: Vec<EliteModifier>,
+    kind: EnemyKind,
+    stats: &EnemyStats,
+    splitter: Option<Splitter>,
+) {
+    if modifiers.is_empty() {
+        return;
+    }
+    for modifier in &modifiers {
+        match modifier {
+            EliteModifier::Shielded => entity_cmd.insert(Shield {
+                value: stats.health * SHIELD_RATIO,
+            }),
+            EliteModifier::Hasted => entity_cmd.insert(Hasted),
+            EliteModifier::Vampiric => entity_cmd.insert(Vampiric {
+                max_health: stats.health,
+                drained: 0.0,
+            }),
+            EliteModifier::Explosive => entity_cmd.insert(Explosive {
+                radius: EXPLOSION_RADIUS,
+                damage: stats.contact_damage * EXPLOSION_DAMAGE_RATIO,
+            }),
+            // Enemies splitting already split into more of their usual spawn
+            EliteModifier::Splitting => entity_cmd.insert(match splitter {
+                Some(splitter) => Splitter {
+                    split_count: splitter.split_count + SPLITTING_COUNT,
+                    ..splitter
+                },
+                None => Splitter {
+                    split_count: SPLITTING_COUNT,
+                    split_into: kind,
+                },
+            }),
+            EliteModifier::Regenerating => entity_cmd.insert(Regenerating {
+                max_health: stats.health,
+                per_second: stats.health * REGENERATION_RATIO,
+            }),
+        };
+    }
+    entity_cmd.insert(Elite { modifiers });
+}
+
+/// Deals `damage` to an enemy, its shield (if any) taking the hit first.
+pub fn damage_enemy(health: &mut Health, shield: Option<&mut Shield>, damage: f32) {
+    let damage = match shield {
+        Some(shield) => shield.absorb(damage),
+        None => damage,
+    };
+    health.value = (health.value - damage).max(0.0);
+}
+
+pub fn drain_life(mut query: Query<(&mut Vampiric, &mut Health)>) {
+    for (mut vampiric, mut health) in &mut query {
+        if vampiric.drained > 0.0 && health.value > 0.0 {
+            health.value =
+                (health.value + vampiric.drained * VAMPIRIC_RATIO).min(vampiric.max_health);
+        }
+        vampiric.drained = 0.0;
+    }
+}
+
+pub fn regenerate(mut query: Query<(&Regenerating, &mut Health)>, time: Res<Time>) {
+    for (regenerating, mut health) in &mut query {
+        if health.value > 0.0 && health.value < regenerating.max_health {
+            health.value = (health.value + regenerating.per_second * time.delta_secs())
+                .min(regenerating.max_health);
+        }
+    }
+}
+
+/// Explosive elites hurt the player caught in the blast when they die.
+#[allow(clippy::type_complexity)]
+pub fn explode_on_death(
+    mut commands: Commands,
+    mut msg_reader: MessageReader<EnemyDeathMessage>,
+    mut explosions: MessageWriter<ExplosionMessage>,
+    mut player_query: Query<
+        (Entity, &GlobalTransform, &mut Health, &Dash),
+        (With<Player>, Without<Dying>),
+    >,
+) {
+    for msg in msg_reader.read() {
+        let Some(explosive) = msg.explosive else {
+            continue;
+        };
+        let position = msg.position.truncate();
+        explosions.write(ExplosionMessage {
+            position,
+            radius: explosive.radius,
+        });
+        let Ok((player_entity, player_transform, mut health, dash)) = player_query.single_mut()
+        else {
+            continue;
+        };
+        if dash.is_invulnerable()
+            || player_transform.translation().truncate().distance(position) > explosive.radius
+        {
+            continue;
+        }
+        health.value = (health.value - explosive.damage).max(0.0);
+        if health.value <= 0.0 {
+            commands.entity(player_entity).insert(Dying {});
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats() -> EnemyStats {
+        EnemyStats {
+            health: 10.0,
+            speed: 100.0,
+            contact_damage: 4.0,
+            xp_reward: 2,
+            currency_reward: 1,
+        }
+    }
+
+    #[test]
+    fn regular_spawns_are_rarely_elite() {
+        let mut rng = GameRng::new(Some(1));
+        let elites = (0..10_000)
+            .filter(|_| !roll_modifiers(false, 1, &mut rng).is_empty())
+            .count();
+        // 1.4% at wave 1
+        assert!((50..250).contains(&elites), "{elites} elites");
+    }
+
+    #[test]
+    fn forced_elites_get_distinct_modifiers_within_their_slots() {
+        let mut rng = GameRng::new(Some(2));
+        for wave in [1, 4, 8, 40] {
+            let slots = (1 + (wave / WAVES_PER_SLOT) as usize).min(MAX_MODIFIERS);
+            for _ in 0..500 {
+                let modifiers = roll_modifiers(true, wave, &mut rng);
+                assert!((1..=slots).contains(&modifiers.len()), "{modifiers:?}");
+                for (i, modifier) in modifiers.iter().enumerate() {
+                    assert!(!modifiers[i + 1..].contains(modifier), "{modifiers:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn late_elites_can_stack_every_slot() {
+        let mut rng = GameRng::new(Some(3));
+        let stacked = (0..1_000).any(|_| roll_modifiers(true, 40, &mut rng).len() == MAX_MODIFIERS);
+        assert!(stacked);
+    }
+
+    #[test]
+    fn regular_stats_are_untouched() {
+        let regular = elite_stats(stats(), &[]);
+        assert_eq!(regular.health, 10.0);
+        assert_eq!(regular.speed, 100.0);
+        assert_eq!(regular.xp_reward, 2);
+        assert_eq!(regular.currency_reward, 1);
+    }
+
+    #[test]
+    fn elites_are_sturdier_and_pay_more_per_modifier() {
+        let elite = elite_stats(stats(), &[EliteModifier::Shielded, EliteModifier::Vampiric]);
+        assert_eq!(elite.health, 10.0 * ELITE_HEALTH_MULTIPLIER);
+        assert_eq!(elite.speed, 100.0);
+        assert_eq!(elite.contact_damage, 4.0);
+        assert_eq!(elite.xp_reward, 2 * 5);
+        assert_eq!(elite.currency_reward, 5);
+    }
+
+    #[test]
+    fn hasted_elites_are_faster() {
+        let elite = elite_stats(stats(), &[EliteModifier::Hasted]);
+        assert_eq!(elite.speed, 100.0 * HASTE_MULTIPLIER);
+    }
+
+    #[test]
+    fn shield_takes_overkill_damage() {
+        let mut health = Health { value: 10.0 };
+        let mut shield = Shield { value: 50.0 };
+        damage_enemy(&mut health, Some(&mut shield), 40.0);
+        assert_eq!(shield.value, 10.0);
+        assert_eq!(health.value, 10.0);
+
+        damage_enemy(&mut health, Some(&mut shield), 15.0);
+        assert_eq!(shield.value, 0.0);
+        assert_eq!(health.value, 5.0);
+    }
+}
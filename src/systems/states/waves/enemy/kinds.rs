@@ -1,23 +1,14 @@
-use crate::systems::constants::{ENEMY_BASE_DAMAGE, ENEMY_BASE_XP, ENEMY_HEALTH, ENEMY_SPEED};
-use crate::systems::states::waves::enemy::kinds::EnemyKind::{
-    Basic, Boss, Fast, MiniBoss, Ranged, SmallSplitter, Splitter, Tank,
+use crate::systems::constants::{
+    ENEMY_BASE_CURRENCY, ENEMY_BASE_DAMAGE, ENEMY_BASE_XP, ENEMY_HEALTH, ENEMY_SPEED,
 };
+use bevy::asset::Asset;
 use bevy::color::Color;
-use bevy::prelude::Srgba;
-use rand::RngExt;
+use bevy::reflect::TypePath;
+use serde::Deserialize;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
-pub enum EnemyKind {
-    #[default]
-    Basic,
-    Fast,
-    Tank,
-    Splitter,
-    SmallSplitter,
-    Ranged,
-    MiniBoss,
-    Boss,
-}
+/// Index of an `EnemyDefinition` inside the `EnemyRegistry`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EnemyKind(pub usize);
 
 pub struct EnemyVisual {
     pub radius: f32,
@@ -29,82 +20,111 @@ pub struct EnemyStats {
     pub speed: f32,
     pub contact_damage: f32,
     pub xp_reward: u32,
+    pub currency_reward: u32,
+}
+
+/// One enemy type, loaded from `assets/enemies/<id>.enemy.ron`.
+/// Stat ratios are multipliers of the `ENEMY_*` base constants.
+#[derive(Asset, TypePath, Deserialize, Debug, Clone)]
+pub struct EnemyDefinition {
+    pub id: String,
+    pub radius: f32,
+    /// sRGB components in 0..1
+    pub color: (f32, f32, f32),
+    pub health_ratio: f32,
+    pub speed_ratio: f32,
+    pub damage_ratio: f32,
+    pub xp_ratio: u32,
+    /// Entry in the regular spawn pool; `None` for enemies that only appear as bosses or splits.
+    #[serde(default)]
+    pub spawn: Option<SpawnRule>,
+    #[serde(default)]
+    pub ranged: Option<RangedDefinition>,
+    #[serde(default)]
+    pub split: Option<SplitDefinition>,
+    /// Chase / charge / radial burst pattern of the boss.
+    #[serde(default)]
+    pub charge_attack: bool,
+    /// How the enemy keeps its distance from the others, the default fits regular enemies.
+    #[serde(default)]
+    pub steering: SteeringDefinition,
+    pub animation: EnemyAnimationDefinition,
 }
 
-impl EnemyKind {
-    pub fn iterator() -> impl Iterator<Item = EnemyKind> {
-        [
-            Basic,
-            Fast,
-            Tank,
-            Splitter,
-            SmallSplitter,
-            Ranged,
-            MiniBoss,
-            Boss,
-        ]
-        .iter()
-        .copied()
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct SteeringDefinition {
+    /// Strength of the push away from overlapping enemies, 0 lets the enemy stack
+    pub separation_weight: f32,
+    /// Heavier enemies shove lighter ones aside and barely move when pushed
+    pub mass: f32,
+}
+
+impl Default for SteeringDefinition {
+    fn default() -> Self {
+        Self {
+            separation_weight: 1.0,
+            mass: 1.0,
+        }
     }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SpawnRule {
+    pub unlock_wave: u32,
+    pub weight: f32,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct RangedDefinition {
+    pub cooldown: f32,
+    pub preferred_distance: f32,
+    /// Projectile damage as a multiple of the contact damage.
+    pub projectile_damage_ratio: f32,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SplitDefinition {
+    pub count: u32,
+    /// `id` of the definition spawned on death.
+    pub into: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct EnemyAnimationDefinition {
+    pub spritesheet: String,
+    pub tile_size: u32,
+    pub columns: u32,
+    pub rows: u32,
+    pub first: usize,
+    pub last: usize,
+    pub frame_ms: u64,
+    /// The art faces west instead of east, so the per-direction flip is inverted.
+    #[serde(default)]
+    pub faces_west: bool,
+    /// Square display size in pixels, `None` keeps the tile size.
+    #[serde(default)]
+    pub custom_size: Option<f32>,
+}
+
+impl EnemyDefinition {
     pub fn visual(&self) -> EnemyVisual {
-        let (radius, color) = match self {
-            EnemyKind::Basic => (15.0, Color::srgb(1.0, 0.3, 0.3)),
-            EnemyKind::Fast => (10.0, Color::srgb(1.0, 0.85, 0.1)),
-            EnemyKind::Tank => (22.0, Color::srgb(0.5, 0.3, 0.85)),
-            EnemyKind::Splitter => (18.0, Color::srgb(0.2, 0.85, 0.4)),
-            EnemyKind::SmallSplitter => (8.0, Color::Srgba(Srgba::hex("FF00FF").unwrap())),
-            EnemyKind::Ranged => (13.0, Color::srgb(0.1, 0.75, 0.95)),
-            EnemyKind::MiniBoss => (35.0, Color::srgb(1.0, 0.45, 0.0)),
-            EnemyKind::Boss => (55.0, Color::srgb(0.85, 0.1, 0.9)),
-        };
-        EnemyVisual { radius, color }
+        let (r, g, b) = self.color;
+        EnemyVisual {
+            radius: self.radius,
+            color: Color::srgb(r, g, b),
+        }
     }
 
     pub fn stats(&self, wave: u32) -> EnemyStats {
         let wave_scale = 1.0 + wave as f32 * 0.12;
-        let (health_ratio, speed_ratio, damage_ratio, xp_ratio) = match self {
-            Basic => (1.0, 1.0, 1.0, 1),
-            Fast => (0.5, 1.5, 0.6, 1),
-            Tank => (2.0, 0.5, 2.0, 3),
-            Splitter => (1.2, 0.85, 0.8, 2),
-            SmallSplitter => (0.3, 1.3, 0.4, 1),
-            Ranged => (0.8, 0.6, 0.3, 2), // low contact dmg, shoots instead
-            MiniBoss => (8.0, 0.65, 3.0, 10),
-            Boss => (25.0, 0.4, 5.0, 50),
-        };
         EnemyStats {
-            health: ENEMY_HEALTH * health_ratio * wave_scale,
-            speed: ENEMY_SPEED * speed_ratio,
-            contact_damage: ENEMY_BASE_DAMAGE * damage_ratio,
-            xp_reward: ENEMY_BASE_XP * xp_ratio,
-        }
-    }
-
-    /// Weighted random pick for the regular spawn timer based on wave
-    pub fn random_for_wave(wave: u32) -> Self {
-        let mut pool: Vec<(EnemyKind, f32)> = vec![(Basic, 60.0)];
-        if wave >= 2 {
-            pool.push((Fast, 25.0));
-        }
-        if wave >= 3 {
-            pool.push((Tank, 15.0));
-        }
-        if wave >= 4 {
-            pool.push((Splitter, 15.0));
-        }
-        if wave >= 5 {
-            pool.push((Ranged, 20.0));
-        }
-
-        let total: f32 = pool.iter().map(|(_, w)| w).sum();
-        let mut rng = rand::rng();
-        let mut roll: f32 = rng.random_range(0.0..total);
-        for (kind, weight) in &pool {
-            roll -= weight;
-            if roll <= 0.0 {
-                return *kind;
-            }
+            health: ENEMY_HEALTH * self.health_ratio * wave_scale,
+            speed: ENEMY_SPEED * self.speed_ratio,
+            contact_damage: ENEMY_BASE_DAMAGE * self.damage_ratio,
+            xp_reward: ENEMY_BASE_XP * self.xp_ratio,
+            // Tougher enemies are worth more in the shop, same ratio as the experience
+            currency_reward: ENEMY_BASE_CURRENCY * self.xp_ratio,
         }
-        Basic
     }
 }
@@ -1,3 +1,5 @@
+use crate::systems::states::waves::enemy::components::Splitter;
+use crate::systems::states::waves::enemy::elites::Explosive;
 use crate::systems::states::waves::enemy::kinds::EnemyKind;
 use bevy::prelude::*;
 
@@ -5,7 +7,9 @@ use bevy::prelude::*;
 pub struct EnemyDeathMessage {
     pub position: Vec3,
     pub xp_reward: u32,
-    pub split_count: u32, // 0 for non-splitters
+    pub currency_reward: u32,
+    pub splitter: Option<Splitter>,
+    pub explosive: Option<Explosive>,
 }
 
 #[derive(Message, Debug)]
@@ -20,3 +24,10 @@ pub struct EnemySpawnedMessage {
     pub kind: EnemyKind,
     pub transform: Transform,
 }
+
+/// An explosive elite blew up
+#[derive(Message, Debug)]
+pub struct ExplosionMessage {
+    pub position: Vec2,
+    pub radius: f32,
+}
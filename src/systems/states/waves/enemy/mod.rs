@@ -1,4 +1,5 @@
 pub mod components;
+pub mod elites;
 pub mod kinds;
 pub mod messages;
 pub mod movement;
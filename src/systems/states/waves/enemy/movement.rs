@@ -1,16 +1,31 @@
 use crate::systems::states::waves::components::Direction;
-use crate::systems::states::waves::enemy::components::{BossAttack, Enemy, RangedAttack};
+use crate::systems::states::waves::enemy::components::{BossAttack, Enemy, RangedAttack, Steering};
+use crate::systems::states::waves::enemy::resources::EnemyRegistry;
+use crate::systems::states::waves::navigation::FlowField;
 use crate::systems::states::waves::player::components::Player;
+use crate::systems::states::waves::spatial::SpatialGrid;
+use crate::systems::states::waves::status::StatusEffects;
 use bevy::math::Vec2;
-use bevy::prelude::{GlobalTransform, Query, Res, Time, Transform, With, Without};
+use bevy::prelude::{Entity, GlobalTransform, Query, Res, Time, Transform, With, Without};
 
+/// Share of the overlap between two enemies resolved per second
+const SEPARATION_RATE: f32 = 10.0;
+/// Pixels an enemy can be pushed per second, so a crowd spreads out instead of exploding
+const MAX_SEPARATION_SPEED: f32 = 400.0;
+
+/// Chasing and kiting follow the `FlowField`, so enemies walk around obstacles.
+#[allow(clippy::type_complexity)]
 pub fn move_to_player(
     mut enemy_query: Query<
-        (&mut Transform, &mut Direction, &Enemy),
+        (&mut Transform, &mut Direction, &Enemy, &StatusEffects),
         (Without<RangedAttack>, Without<BossAttack>),
     >,
-    mut ranged_enemy_query: Query<(&mut Transform, &Enemy, &RangedAttack), Without<BossAttack>>,
+    mut ranged_enemy_query: Query<
+        (&mut Transform, &Enemy, &RangedAttack, &StatusEffects),
+        Without<BossAttack>,
+    >,
     player_query: Query<&GlobalTransform, (With<Player>, Without<Enemy>)>,
+    flow_field: Res<FlowField>,
     time: Res<Time>,
 ) {
     let Ok(player_transform) = player_query.single() else {
@@ -20,25 +35,80 @@ pub fn move_to_player(
     let player_pos = player_transform.translation().truncate();
 
     // Basic
-    for (mut transform, mut direction, enemy) in &mut enemy_query {
+    for (mut transform, mut direction, enemy, effects) in &mut enemy_query {
         let current_pos = transform.translation.truncate();
-        let new_direction = (player_pos - current_pos).normalize_or_zero();
+        let new_direction = flow_field.direction(current_pos, player_pos);
         *direction = get_direction(new_direction);
-        transform.translation += (new_direction * enemy.speed * time.delta_secs()).extend(1.0);
+        let speed = enemy.speed * effects.speed_multiplier();
+        transform.translation += (new_direction * speed * time.delta_secs()).extend(1.0);
     }
 
     // Ranged
-    for (mut transform, enemy, ranged) in &mut ranged_enemy_query {
-        let to_player = player_pos - transform.translation.truncate();
-        let distance = to_player.length();
-        let direction = to_player.normalize_or_zero();
+    for (mut transform, enemy, ranged, effects) in &mut ranged_enemy_query {
+        let current_pos = transform.translation.truncate();
+        let distance = current_pos.distance(player_pos);
+        let speed = enemy.speed * effects.speed_multiplier();
 
         let preferred = ranged.preferred_distance;
         if distance > preferred + 60.0 {
-            transform.translation += (direction * enemy.speed * time.delta_secs()).extend(1.0);
+            let direction = flow_field.direction(current_pos, player_pos);
+            transform.translation += (direction * speed * time.delta_secs()).extend(1.0);
         } else if distance < preferred - 60.0 {
-            transform.translation -=
-                (direction * enemy.speed * 0.7 * time.delta_secs()).extend(1.0);
+            let direction = flow_field.away(current_pos, player_pos);
+            transform.translation += (direction * speed * 0.7 * time.delta_secs()).extend(1.0);
+        }
+    }
+}
+
+/// Pushes overlapping enemies apart until they are `Steering::radius` away from each other.
+/// Each one of a pair takes the share of the push matching the other's mass, so a heavy enemy
+/// shoves light ones aside while barely moving itself.
+pub fn separate_enemies(
+    mut query: Query<(Entity, &mut Transform, &Steering), With<Enemy>>,
+    grid: Res<SpatialGrid>,
+    registry: Res<EnemyRegistry>,
+    time: Res<Time>,
+) {
+    let rate = (SEPARATION_RATE * time.delta_secs()).min(1.0);
+    let max_push = MAX_SEPARATION_SPEED * time.delta_secs();
+    let mut pushes: Vec<(Entity, Vec2)> = Vec::new();
+
+    for (entity, transform, steering) in &query {
+        if steering.separation_weight <= 0.0 {
+            continue;
+        }
+        let position = transform.translation.truncate();
+        let mut push = Vec2::ZERO;
+        // Neighbours come from the grid, their current position from the query
+        for (neighbour, _) in grid.query_radius(position, steering.radius + registry.max_radius()) {
+            if neighbour == entity {
+                continue;
+            }
+            let Ok((_, other_transform, other)) = query.get(neighbour) else {
+                continue;
+            };
+            let offset = position - other_transform.translation.truncate();
+            let distance = offset.length();
+            let overlap = steering.radius + other.radius - distance;
+            if overlap <= 0.0 {
+                continue;
+            }
+            // Enemies on the exact same spot are split along an arbitrary but stable axis
+            let away = offset
+                .try_normalize()
+                .unwrap_or_else(|| Vec2::from_angle(entity.index_u32() as f32));
+            let share = other.mass / (steering.mass + other.mass);
+            push += away * overlap * share;
+        }
+        if push != Vec2::ZERO {
+            let push = (push * steering.separation_weight * rate).clamp_length_max(max_push);
+            pushes.push((entity, push));
+        }
+    }
+
+    for (entity, push) in pushes {
+        if let Ok((_, mut transform, _)) = query.get_mut(entity) {
+            transform.translation += push.extend(0.0);
         }
     }
 }
@@ -56,3 +126,122 @@ pub fn get_direction(translation: Vec2) -> Direction {
         _ => Direction::EAST,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::states::waves::enemy::kinds::EnemyDefinition;
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy::prelude::{Time, World};
+    use std::time::Duration;
+
+    fn definition(id: &str) -> EnemyDefinition {
+        let path = format!(
+            "{}/assets/enemies/{}.enemy.ron",
+            env!("CARGO_MANIFEST_DIR"),
+            id
+        );
+        ron::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    fn world() -> World {
+        let mut world = World::new();
+        let registry = EnemyRegistry::new(["basic", "tank", "fast"].map(definition).to_vec());
+        world.insert_resource(registry);
+        world.insert_resource(SpatialGrid::default());
+        let mut time = Time::<()>::default();
+        time.advance_by(Duration::from_millis(20));
+        world.insert_resource(time);
+        world
+    }
+
+    fn spawn(world: &mut World, id: &str, position: Vec2) -> Entity {
+        let registry = world.resource::<EnemyRegistry>();
+        let kind = registry.find(id).unwrap();
+        let definition = registry.get(kind);
+        let steering = Steering {
+            radius: definition.visual().radius,
+            separation_weight: definition.steering.separation_weight,
+            mass: definition.steering.mass,
+        };
+        let enemy = Enemy {
+            damage: 0.0,
+            speed: 0.0,
+            kind,
+            xp_reward: 0,
+            currency_reward: 0,
+        };
+        world
+            .spawn((
+                enemy,
+                steering,
+                Transform::from_translation(position.extend(0.0)),
+            ))
+            .id()
+    }
+
+    /// Fills the grid like `rebuild_spatial_grid` does every frame, then separates once.
+    fn step(world: &mut World) {
+        let positions: Vec<(Entity, Vec2)> = world
+            .query::<(Entity, &Transform)>()
+            .iter(world)
+            .map(|(entity, transform)| (entity, transform.translation.truncate()))
+            .collect();
+        let mut grid = world.resource_mut::<SpatialGrid>();
+        grid.clear();
+        for (entity, position) in positions {
+            grid.insert(entity, position);
+        }
+        world.run_system_once(separate_enemies).unwrap();
+    }
+
+    fn position(world: &World, entity: Entity) -> Vec2 {
+        world
+            .get::<Transform>(entity)
+            .unwrap()
+            .translation
+            .truncate()
+    }
+
+    #[test]
+    fn overlapping_enemies_spread_out_to_their_radius() {
+        let mut world = world();
+        let a = spawn(&mut world, "basic", Vec2::new(0.0, 0.0));
+        let b = spawn(&mut world, "basic", Vec2::new(5.0, 0.0));
+        for _ in 0..60 {
+            step(&mut world);
+        }
+
+        let radius = definition("basic").radius;
+        let (a, b) = (position(&world, a), position(&world, b));
+        assert!(a.distance(b) > 2.0 * radius - 0.5, "{a} {b}");
+        // Same mass, both moved the same distance along the axis they overlapped on
+        assert!((a.x + b.x - 5.0).abs() < 1e-3);
+        assert_eq!((a.y, b.y), (0.0, 0.0));
+    }
+
+    #[test]
+    fn enemies_on_the_same_spot_still_split() {
+        let mut world = world();
+        let a = spawn(&mut world, "basic", Vec2::ZERO);
+        let b = spawn(&mut world, "basic", Vec2::ZERO);
+        step(&mut world);
+        assert_ne!(position(&world, a), position(&world, b));
+    }
+
+    #[test]
+    fn heavy_enemies_shove_light_ones_aside() {
+        let mut world = world();
+        let tank = spawn(&mut world, "tank", Vec2::new(0.0, 0.0));
+        let fast = spawn(&mut world, "fast", Vec2::new(10.0, 0.0));
+        step(&mut world);
+
+        let tank_moved = position(&world, tank).distance(Vec2::new(0.0, 0.0));
+        let fast_moved = position(&world, fast).distance(Vec2::new(10.0, 0.0));
+        assert!(tank_moved > 0.0);
+        assert!(
+            fast_moved > 4.0 * tank_moved,
+            "fast moved {fast_moved}, tank moved {tank_moved}"
+        );
+    }
+}
@@ -1,14 +1,28 @@
 use crate::systems::animations::animation::SpriteAnimation;
 use crate::systems::animations::animator::SpriteAnimator;
+use crate::systems::game::GameState;
 use crate::systems::states::waves::components::Direction::EAST;
-use crate::systems::states::waves::enemy::components::Spawning;
-use crate::systems::states::waves::enemy::messages::{EnemySpawnedMessage, EnemySpawningMessage};
-use crate::systems::states::waves::enemy::resources::EnemyAnimations;
+use crate::systems::states::waves::enemy::components::{EliteAura, Enemy, Explosion, Spawning};
+use crate::systems::states::waves::enemy::elites::Elite;
+use crate::systems::states::waves::enemy::messages::{
+    EnemySpawnedMessage, EnemySpawningMessage, ExplosionMessage,
+};
+use crate::systems::states::waves::enemy::resources::{EnemyAnimations, EnemyRegistry};
 use bevy::asset::Assets;
 use bevy::math::Vec3;
 use bevy::mesh::{Mesh, Mesh2d};
 use bevy::prelude::*;
 
+/// Elites are drawn bigger than the regular enemies of their kind
+const ELITE_SCALE: f32 = 1.25;
+/// Aura radius as a multiple of the enemy radius, the next ring is `AURA_RING_STEP` wider
+const AURA_RADIUS_FACTOR: f32 = 1.2;
+const AURA_RING_STEP: f32 = 0.2;
+const AURA_ALPHA: f32 = 0.35;
+const EXPLOSION_LIFETIME: f32 = 0.35;
+const EXPLOSION_COLOR: Color = Color::srgba(1.0, 0.5, 0.1, 0.7);
+const EXPLOSION_Z: f32 = 90.0;
+
 pub fn update_spawning(mut warning_query: Query<(&mut Spawning, &mut Transform)>, time: Res<Time>) {
     for (mut spawning, mut transform) in &mut warning_query {
         spawning.timer.tick(time.delta());
@@ -24,9 +38,10 @@ pub fn handle_enemy_spawning(
     mut events: MessageReader<EnemySpawningMessage>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
+    registry: Res<EnemyRegistry>,
 ) {
     for spawning in events.read() {
-        let visual = spawning.kind.visual();
+        let visual = registry.get(spawning.kind).visual();
         let mesh_handle = meshes.add(Circle::new(visual.radius * 2.0));
         let material_handle = materials.add(visual.color.with_alpha(0.35));
         commands
@@ -40,20 +55,26 @@ pub fn handle_enemy_spawned(
     anims: Res<EnemyAnimations>,
     sprites: Res<Assets<SpriteAnimation>>,
     mut events: MessageReader<EnemySpawnedMessage>,
+    elite_query: Query<(), With<Elite>>,
 ) {
     for enemy in events.read() {
         let kind = enemy.kind;
         let shadow = anims.shadow_texture.clone();
         let handle = anims
             .get(kind, EAST)
-            .expect(format!("{:?}/EAST must be registered", kind).as_str());
+            .unwrap_or_else(|| panic!("{:?}/EAST must be registered", kind));
         let animation = sprites.get(handle.id()).cloned().unwrap();
 
         commands.entity(enemy.entity).remove::<Mesh2d>();
         commands
             .entity(enemy.entity)
             .remove::<MeshMaterial2d<ColorMaterial>>();
-        let transform = enemy.transform.with_scale(Vec3::splat(2.0));
+        let scale = if elite_query.contains(enemy.entity) {
+            2.0 * ELITE_SCALE
+        } else {
+            2.0
+        };
+        let transform = enemy.transform.with_scale(Vec3::splat(scale));
         let mut shadow_image = Sprite::from_image(shadow);
         shadow_image.custom_size = animation.to_sprite().custom_size;
 
@@ -65,3 +86,79 @@ pub fn handle_enemy_spawned(
         ));
     }
 }
+
+pub fn spawn_elite_auras(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    registry: Res<EnemyRegistry>,
+    query: Query<(Entity, &Elite, &Enemy), Added<Elite>>,
+) {
+    for (entity, elite, enemy) in &query {
+        // Children are drawn in the space of the scaled enemy sprite
+        let radius = registry.get(enemy.kind).visual().radius / (2.0 * ELITE_SCALE);
+        for (ring, modifier) in elite.modifiers.iter().enumerate() {
+            let ring_radius = radius * (AURA_RADIUS_FACTOR + ring as f32 * AURA_RING_STEP);
+            commands.entity(entity).with_child((
+                EliteAura,
+                Mesh2d(meshes.add(Annulus::new(ring_radius * 0.8, ring_radius))),
+                MeshMaterial2d(materials.add(modifier.color().with_alpha(AURA_ALPHA))),
+                Transform::from_xyz(0.0, 0.0, -0.5 - ring as f32 * 0.01),
+            ));
+        }
+    }
+}
+
+pub fn animate_elite_auras(mut query: Query<&mut Transform, With<EliteAura>>, time: Res<Time>) {
+    let pulse = 1.0 + (time.elapsed_secs() * 4.0).sin() * 0.08;
+    for mut transform in &mut query {
+        transform.scale = Vec3::splat(pulse);
+    }
+}
+
+pub fn spawn_explosions(
+    mut commands: Commands,
+    mut events: MessageReader<ExplosionMessage>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for explosion in events.read() {
+        commands.spawn((
+            Explosion {
+                lifetime: Timer::from_seconds(EXPLOSION_LIFETIME, TimerMode::Once),
+            },
+            DespawnOnExit(GameState::InWave),
+            Mesh2d(meshes.add(Circle::new(explosion.radius))),
+            MeshMaterial2d(materials.add(EXPLOSION_COLOR)),
+            Transform::from_translation(explosion.position.extend(EXPLOSION_Z))
+                .with_scale(Vec3::splat(0.3)),
+        ));
+    }
+}
+
+pub fn animate_explosions(
+    mut commands: Commands,
+    mut query: Query<(
+        Entity,
+        &mut Explosion,
+        &mut Transform,
+        &MeshMaterial2d<ColorMaterial>,
+    )>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    time: Res<Time>,
+) {
+    for (entity, mut explosion, mut transform, material) in &mut query {
+        explosion.lifetime.tick(time.delta());
+        if explosion.lifetime.is_finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let progress = explosion.lifetime.fraction();
+        transform.scale = Vec3::splat(0.3 + 0.7 * progress);
+        if let Some(material) = materials.get_mut(&material.0) {
+            material
+                .color
+                .set_alpha(EXPLOSION_COLOR.alpha() * (1.0 - progress));
+        }
+    }
+}
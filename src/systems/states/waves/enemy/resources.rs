@@ -1,20 +1,123 @@
 use crate::systems::animations::animation::{SpriteAnimation, Spritesheet};
+use crate::systems::game::GameRng;
 use crate::systems::states::waves::components::Direction;
-use crate::systems::states::waves::enemy::kinds::EnemyKind;
+use crate::systems::states::waves::enemy::kinds::{EnemyDefinition, EnemyKind};
 use bevy::asset::{AssetServer, Assets, Handle};
 use bevy::image::{Image, TextureAtlasLayout};
 use bevy::math::UVec2;
 use bevy::prelude::*;
+use rand::RngExt;
 use std::collections::HashMap;
 use std::time::Duration;
 
-const BASIC_MOB_SPRITESHEET_PATH: &str = "spritesheet/enemy/Pink_Monster.png";
-const FAST_MOB_SPRITESHEET_PATH: &str = "spritesheet/enemy/Owlet_Monster_Walk_6.png";
-const TANK_MOB_SPRITESHEET_PATH: &str = "spritesheet/enemy/Dude_Monster_Run_6.png";
-const MINIBOSS_MOB_SPRITESHEET_PATH: &str = "spritesheet/enemy/miniboss.png";
-const SPLITTER_MOB_SPRITESHEET_PATH: &str = "spritesheet/enemy/blob2.png";
 const SHADOW_SPRITE_PATH: &str = "spritesheet/player/shadow_sprite.png";
 
+/// Every loaded `EnemyDefinition`, sorted by id so that `EnemyKind` indices (and therefore
+/// seeded runs) don't depend on the order files were read from disk.
+#[derive(Resource)]
+pub struct EnemyRegistry {
+    definitions: Vec<EnemyDefinition>,
+    by_id: HashMap<String, EnemyKind>,
+    max_radius: f32,
+    max_preferred_distance: f32,
+}
+
+impl EnemyRegistry {
+    pub fn new(mut definitions: Vec<EnemyDefinition>) -> Self {
+        // The clips play `first..=last`, an inverted range has no frame to show
+        definitions.retain(|definition| {
+            let valid = definition.animation.first <= definition.animation.last;
+            if !valid {
+                warn!(
+                    "Enemy '{}' dropped: animation frame 'last' comes before 'first'",
+                    definition.id
+                );
+            }
+            valid
+        });
+        definitions.sort_by(|a, b| a.id.cmp(&b.id));
+        let by_id = definitions
+            .iter()
+            .enumerate()
+            .map(|(index, definition)| (definition.id.clone(), EnemyKind(index)))
+            .collect::<HashMap<_, _>>();
+        for definition in &definitions {
+            if let Some(split) = &definition.split
+                && !by_id.contains_key(&split.into)
+            {
+                warn!(
+                    "Enemy '{}' splits into unknown enemy '{}'",
+                    definition.id, split.into
+                );
+            }
+        }
+        let max_radius = definitions
+            .iter()
+            .map(|definition| definition.radius)
+            .fold(0.0, f32::max);
+        let max_preferred_distance = definitions
+            .iter()
+            .filter_map(|definition| definition.ranged.as_ref())
+            .map(|ranged| ranged.preferred_distance)
+            .fold(0.0, f32::max);
+        Self {
+            definitions,
+            by_id,
+            max_radius,
+            max_preferred_distance,
+        }
+    }
+
+    pub fn get(&self, kind: EnemyKind) -> &EnemyDefinition {
+        &self.definitions[kind.0]
+    }
+
+    pub fn find(&self, id: &str) -> Option<EnemyKind> {
+        self.by_id.get(id).copied()
+    }
+
+    /// Largest collision radius of any enemy, the reach needed for spatial queries
+    pub fn max_radius(&self) -> f32 {
+        self.max_radius
+    }
+
+    /// Largest `preferred_distance` of any ranged enemy, 0 when none can shoot
+    pub fn max_preferred_distance(&self) -> f32 {
+        self.max_preferred_distance
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (EnemyKind, &EnemyDefinition)> {
+        self.definitions
+            .iter()
+            .enumerate()
+            .map(|(index, definition)| (EnemyKind(index), definition))
+    }
+
+    /// Weighted random pick among the enemies unlocked at `wave`, for scripts without a pool
+    pub fn random_for_wave(&self, wave: u32, rng: &mut GameRng) -> Option<EnemyKind> {
+        let pool: Vec<(EnemyKind, f32)> = self
+            .iter()
+            .filter_map(|(kind, definition)| {
+                let rule = definition.spawn.as_ref()?;
+                (wave >= rule.unlock_wave).then_some((kind, rule.weight))
+            })
+            .collect();
+
+        let total: f32 = pool.iter().map(|(_, w)| w).sum();
+        if total <= 0.0 {
+            return None;
+        }
+        let mut roll: f32 = rng.random_range(0.0..total);
+        for (kind, weight) in &pool {
+            roll -= weight;
+            if roll <= 0.0 {
+                return Some(*kind);
+            }
+        }
+        pool.last().map(|(kind, _)| *kind)
+    }
+}
+
 #[derive(Resource)]
 pub struct EnemyAnimations {
     map: HashMap<(EnemyKind, Direction), Handle<SpriteAnimation>>,
@@ -25,174 +128,106 @@ impl EnemyAnimations {
     pub fn get(&self, kind: EnemyKind, dir: Direction) -> Option<Handle<SpriteAnimation>> {
         self.map.get(&(kind, dir)).cloned()
     }
+}
 
-    pub fn get_image_handle(assets: &AssetServer, kind: EnemyKind) -> Handle<Image> {
-        match kind {
-            EnemyKind::Basic => assets.load(BASIC_MOB_SPRITESHEET_PATH),
-            EnemyKind::Fast => assets.load(FAST_MOB_SPRITESHEET_PATH),
-            EnemyKind::Tank => assets.load(TANK_MOB_SPRITESHEET_PATH),
-            EnemyKind::MiniBoss => assets.load(MINIBOSS_MOB_SPRITESHEET_PATH),
-            EnemyKind::Boss => assets.load(MINIBOSS_MOB_SPRITESHEET_PATH),
-            EnemyKind::Splitter | EnemyKind::SmallSplitter => {
-                assets.load(SPLITTER_MOB_SPRITESHEET_PATH)
-            }
-            _ => assets.load(BASIC_MOB_SPRITESHEET_PATH),
+// Rows of the enemy spritesheets face east; these directions play them mirrored.
+const FLIPPED_DIRECTIONS: [Direction; 4] = [
+    Direction::SOUTH,
+    Direction::WEST,
+    Direction::NORTHWEST,
+    Direction::SOUTHWEST,
+];
+
+/// Builds the clips for every registered enemy once its definitions are loaded.
+pub fn build_enemy_animations(
+    mut commands: Commands,
+    registry: Res<EnemyRegistry>,
+    asset_server: Res<AssetServer>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut animations: ResMut<Assets<SpriteAnimation>>,
+) {
+    let mut map = HashMap::new();
+    for (kind, definition) in registry.iter() {
+        let anim = &definition.animation;
+        let image = asset_server.load(anim.spritesheet.clone());
+        let layout = layouts.add(TextureAtlasLayout::from_grid(
+            UVec2::splat(anim.tile_size),
+            anim.columns,
+            anim.rows,
+            None,
+            None,
+        ));
+        for dir in Direction::iterator() {
+            let reversed = FLIPPED_DIRECTIONS.contains(&dir);
+            let animation = SpriteAnimation {
+                spritesheet: Spritesheet {
+                    image: image.clone(),
+                    layout: layout.clone(),
+                    first: anim.first,
+                    last: anim.last,
+                    flip_x: reversed != anim.faces_west,
+                    custom_size: anim.custom_size.map(Vec2::splat),
+                },
+                frame_interval: Duration::from_millis(anim.frame_ms),
+                repeat: true,
+            };
+            map.insert((kind, dir), animations.add(animation));
         }
     }
 
-    pub fn get_layout(
-        texture_atlas_layout: &mut Assets<TextureAtlasLayout>,
-        kind: EnemyKind,
-    ) -> Handle<TextureAtlasLayout> {
-        match kind {
-            EnemyKind::Basic => texture_atlas_layout.add(TextureAtlasLayout::from_grid(
-                UVec2::splat(32),
-                8,
-                1,
-                None,
-                None,
-            )),
-            EnemyKind::Fast | EnemyKind::Tank => texture_atlas_layout.add(
-                TextureAtlasLayout::from_grid(UVec2::splat(32), 6, 1, None, None),
-            ),
-            EnemyKind::MiniBoss | EnemyKind::Boss => texture_atlas_layout.add(
-                TextureAtlasLayout::from_grid(UVec2::splat(64), 6, 1, None, None),
-            ),
-            EnemyKind::Splitter | EnemyKind::SmallSplitter => texture_atlas_layout.add(
-                TextureAtlasLayout::from_grid(UVec2::splat(32), 8, 3, None, None),
-            ),
-            EnemyKind::Ranged => texture_atlas_layout.add(TextureAtlasLayout::from_grid(
-                UVec2::splat(32),
-                8,
-                1,
-                None,
-                None,
-            )),
+    commands.insert_resource(EnemyAnimations {
+        map,
+        shadow_texture: asset_server.load(SHADOW_SPRITE_PATH),
+    });
+}
+
+/// Asset-free stand-in for headless runs, one looping clip per kind and direction.
+pub fn build_placeholder_enemy_animations(
+    mut commands: Commands,
+    registry: Res<EnemyRegistry>,
+    mut animations: ResMut<Assets<SpriteAnimation>>,
+) {
+    let mut map = HashMap::new();
+    for (kind, definition) in registry.iter() {
+        let frames = definition.animation.last - definition.animation.first + 1;
+        for dir in Direction::iterator() {
+            let anim = SpriteAnimation::from_row(Handle::default(), Handle::default(), 0, frames)
+                .looping(true);
+            map.insert((kind, dir), animations.add(anim));
         }
     }
+    commands.insert_resource(EnemyAnimations {
+        map,
+        shadow_texture: Handle::default(),
+    });
 }
 
-impl FromWorld for EnemyAnimations {
-    fn from_world(world: &mut World) -> Self {
-        let direction_rows: &[(Direction, bool)] = &[
-            (Direction::SOUTH, true),
-            (Direction::WEST, true),
-            (Direction::NORTHWEST, true),
-            (Direction::SOUTHWEST, true),
-            (Direction::NORTH, false),
-            (Direction::NORTHEAST, false),
-            (Direction::EAST, false),
-            (Direction::SOUTHEAST, false),
-        ];
-        let mut map = HashMap::new();
-        for kind in EnemyKind::iterator() {
-            for (dir, reversed) in direction_rows {
-                let image = { Self::get_image_handle(world.resource::<AssetServer>(), kind) };
-                let layout = {
-                    Self::get_layout(
-                        world.resource_mut::<Assets<TextureAtlasLayout>>().as_mut(),
-                        kind,
-                    )
-                };
-                let animation = match kind {
-                    EnemyKind::Splitter => SpriteAnimation {
-                        spritesheet: Spritesheet {
-                            image,
-                            layout,
-                            first: 0,
-                            last: 22,
-                            flip_x: *reversed,
-                            custom_size: Some(Vec2::splat(32.)),
-                        },
-                        frame_interval: Duration::from_millis(120),
-                        repeat: true,
-                    },
-                    EnemyKind::SmallSplitter => SpriteAnimation {
-                        spritesheet: Spritesheet {
-                            image,
-                            layout,
-                            first: 0,
-                            last: 22,
-                            flip_x: *reversed,
-                            custom_size: Some(Vec2::splat(16.)),
-                        },
-                        frame_interval: Duration::from_millis(120),
-                        repeat: true,
-                    },
-                    EnemyKind::MiniBoss => SpriteAnimation {
-                        frame_interval: Duration::from_millis(120),
-                        spritesheet: Spritesheet {
-                            first: 0,
-                            image,
-                            layout,
-                            last: 5,
-                            flip_x: !*reversed,
-                            custom_size: None,
-                        },
-                        repeat: true,
-                    },
-                    EnemyKind::Boss => SpriteAnimation {
-                        frame_interval: Duration::from_millis(120),
-                        spritesheet: Spritesheet {
-                            first: 0,
-                            image,
-                            layout,
-                            last: 5,
-                            flip_x: !*reversed,
-                            custom_size: Some(Vec2::splat(96.)),
-                        },
-                        repeat: true,
-                    },
-                    EnemyKind::Basic | EnemyKind::Ranged => SpriteAnimation {
-                        frame_interval: Duration::from_millis(120),
-                        spritesheet: Spritesheet {
-                            first: 0,
-                            image,
-                            layout,
-                            last: 7,
-                            flip_x: *reversed,
-                            custom_size: None,
-                        },
-                        repeat: true,
-                    },
-                    EnemyKind::Fast => SpriteAnimation {
-                        frame_interval: Duration::from_millis(60),
-                        spritesheet: Spritesheet {
-                            first: 0,
-                            image,
-                            layout,
-                            last: 5,
-                            flip_x: *reversed,
-                            custom_size: None,
-                        },
-                        repeat: true,
-                    },
-                    EnemyKind::Tank => SpriteAnimation {
-                        frame_interval: Duration::from_millis(120),
-                        spritesheet: Spritesheet {
-                            first: 0,
-                            image,
-                            layout,
-                            last: 5,
-                            flip_x: *reversed,
-                            custom_size: None,
-                        },
-                        repeat: true,
-                    },
-                };
-
-                map.insert(
-                    (kind, *dir),
-                    world
-                        .resource_mut::<Assets<SpriteAnimation>>()
-                        .add(animation),
-                );
-            }
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        EnemyAnimations {
-            map,
-            shadow_texture: world.resource::<AssetServer>().load(SHADOW_SPRITE_PATH),
-        }
+    fn definition(id: &str, first: usize, last: usize) -> EnemyDefinition {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/assets/enemies/basic.enemy.ron"
+        );
+        let mut definition: EnemyDefinition =
+            ron::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        definition.id = id.to_string();
+        definition.animation.first = first;
+        definition.animation.last = last;
+        definition
+    }
+
+    #[test]
+    fn inverted_frame_ranges_are_dropped() {
+        let registry = EnemyRegistry::new(vec![
+            definition("single_frame", 3, 3),
+            definition("inverted", 4, 2),
+            definition("looping", 0, 7),
+        ]);
+        assert!(registry.find("inverted").is_none());
+        let ids: Vec<_> = registry.iter().map(|(_, d)| d.id.as_str()).collect();
+        assert_eq!(ids, ["looping", "single_frame"]);
     }
 }
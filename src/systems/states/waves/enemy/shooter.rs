@@ -1,25 +1,45 @@
-use crate::systems::game::GameState;
+use crate::systems::constants::BULLET_SPEED;
 use crate::systems::states::waves::components::Direction;
 use crate::systems::states::waves::enemy::components::{
     BossAttack, BossPhase, Enemy, Hostile, RangedAttack,
 };
 use crate::systems::states::waves::enemy::movement::get_direction;
+use crate::systems::states::waves::enemy::resources::EnemyRegistry;
 use crate::systems::states::waves::player::components::Player;
-use crate::systems::states::waves::weapons::components::{Bullet, WeaponKind};
+use crate::systems::states::waves::spatial::{SpatialGrid, SPATIAL_CELL_SIZE};
+use crate::systems::states::waves::status::StatusEffects;
+use crate::systems::states::waves::weapons::components::Bullet;
+use crate::systems::states::waves::weapons::resources::{BulletPool, ProjectileAssets};
 use bevy::asset::Assets;
 use bevy::color::Color;
 use bevy::math::Vec2;
-use bevy::mesh::{Mesh, Mesh2d};
+use bevy::mesh::Mesh;
 use bevy::prelude::{
-    Circle, ColorMaterial, Commands, DespawnOnExit, GlobalTransform, MeshMaterial2d, Query, Res,
-    ResMut, Time, Timer, TimerMode, Transform, With, Without,
+    ColorMaterial, Commands, GlobalTransform, Query, Res, ResMut, Time, Timer, TimerMode,
+    Transform, With, Without,
 };
 
+/// Ranged enemies open fire within this multiple of their `preferred_distance`
+const SHOOTING_RANGE_FACTOR: f32 = 1.5;
+
+/// Only the ranged enemies the grid finds within shooting range of the player are visited,
+/// so a crowd of far away shooters costs nothing.
+#[allow(clippy::too_many_arguments)]
 pub fn update_enemy_shoot(
     mut commands: Commands,
-    mut attacker_query: Query<(&GlobalTransform, &mut Direction, &Enemy, &mut RangedAttack)>,
+    mut attacker_query: Query<(
+        &GlobalTransform,
+        &mut Direction,
+        &Enemy,
+        &mut RangedAttack,
+        &StatusEffects,
+    )>,
     player_query: Query<&GlobalTransform, (With<Player>, Without<Enemy>)>,
+    grid: Res<SpatialGrid>,
     time: Res<Time>,
+    registry: Res<EnemyRegistry>,
+    mut pool: ResMut<BulletPool>,
+    mut projectile_assets: ResMut<ProjectileAssets>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
 ) {
@@ -27,12 +47,22 @@ pub fn update_enemy_shoot(
         return;
     };
     let player_pos = player_transform.translation();
-
-    for (transform, mut direction, enemy, mut ranged) in &mut attacker_query {
+    // The grid was filled before this frame's movement, one cell of slack covers it
+    let reach = registry.max_preferred_distance() * SHOOTING_RANGE_FACTOR + SPATIAL_CELL_SIZE;
+    for (entity, _) in grid.query_radius(player_pos.truncate(), reach) {
+        let Ok((transform, mut direction, enemy, mut ranged, effects)) =
+            attacker_query.get_mut(entity)
+        else {
+            continue;
+        };
+        // Frozen shooters hold their fire
+        if effects.is_frozen() {
+            continue;
+        }
         let enemy_pos = transform.translation();
         let to_player = player_pos - enemy_pos;
 
-        if to_player.length() > ranged.preferred_distance * 1.5 {
+        if to_player.length() > ranged.preferred_distance * SHOOTING_RANGE_FACTOR {
             continue;
         }
         *direction = get_direction(to_player.truncate());
@@ -43,28 +73,34 @@ pub fn update_enemy_shoot(
         }
 
         let direction = to_player.truncate().normalize_or_zero();
-        let proj_color = enemy.kind.visual().color;
+        let proj_color = registry.get(enemy.kind).visual().color;
 
-        commands.spawn((
+        pool.spawn(
+            &mut commands,
             Transform::from_translation(enemy_pos),
-            Bullet {
-                kind: WeaponKind::Pistol,
-                direction,
-                damage: ranged.projectile_damage,
-            },
-            Hostile,
-            Mesh2d(meshes.add(Circle::new(5.0))),
-            MeshMaterial2d(materials.add(proj_color)),
-            DespawnOnExit(GameState::InWave),
-        ));
+            (
+                Bullet::new(direction, ranged.projectile_damage, BULLET_SPEED),
+                Hostile,
+                projectile_assets.get(5.0, proj_color, &mut meshes, &mut materials),
+            ),
+        );
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn update_boss_shoot(
     mut commands: Commands,
-    mut boss_query: Query<(&mut Transform, &mut Direction, &Enemy, &mut BossAttack)>,
+    mut boss_query: Query<(
+        &mut Transform,
+        &mut Direction,
+        &Enemy,
+        &mut BossAttack,
+        &StatusEffects,
+    )>,
     player_query: Query<&GlobalTransform, (With<Player>, Without<Enemy>)>,
     time: Res<Time>,
+    mut pool: ResMut<BulletPool>,
+    mut projectile_assets: ResMut<ProjectileAssets>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
 ) {
@@ -73,7 +109,12 @@ pub fn update_boss_shoot(
     };
     let player_pos = player_transform.translation();
 
-    for (mut transform, mut direction, enemy, mut boss) in &mut boss_query {
+    for (mut transform, mut direction, enemy, mut boss, effects) in &mut boss_query {
+        // A frozen boss stays in its current phase until it thaws
+        if effects.is_frozen() {
+            continue;
+        }
+        let speed = enemy.speed * effects.speed_multiplier();
         let to_player = (player_pos - transform.translation).truncate();
         boss.phase_timer.tick(time.delta());
         *direction = get_direction(to_player);
@@ -82,7 +123,7 @@ pub fn update_boss_shoot(
             // ── Chasing: slow approach; after timer, lock a charge direction
             BossPhase::Chasing => {
                 let dir = to_player.normalize_or_zero();
-                transform.translation += dir.extend(0.0) * enemy.speed * time.delta_secs();
+                transform.translation += dir.extend(0.0) * speed * time.delta_secs();
 
                 if boss.phase_timer.just_finished() {
                     boss.charge_direction = to_player.normalize_or_zero();
@@ -94,27 +135,26 @@ pub fn update_boss_shoot(
             // ── Charging: rush forward; fire 8-way spread on completion
             BossPhase::Charging => {
                 transform.translation +=
-                    boss.charge_direction.extend(0.0) * enemy.speed * 4.5 * time.delta_secs();
+                    boss.charge_direction.extend(0.0) * speed * 4.5 * time.delta_secs();
 
                 if boss.phase_timer.just_finished() {
                     // Fire a radial spread
                     let boss_pos = transform.translation;
                     let proj_color = Color::srgb(0.95, 0.2, 0.95);
+                    let projectile =
+                        projectile_assets.get(7.0, proj_color, &mut meshes, &mut materials);
                     for i in 0..8u32 {
                         let angle = (i as f32 / 8.0) * std::f32::consts::TAU;
                         let dir = Vec2::new(angle.cos(), angle.sin());
-                        commands.spawn((
+                        pool.spawn(
+                            &mut commands,
                             Transform::from_translation(boss_pos),
-                            Bullet {
-                                kind: WeaponKind::Shotgun,
-                                direction: dir,
-                                damage: enemy.damage * 0.6,
-                            },
-                            Hostile,
-                            Mesh2d(meshes.add(Circle::new(7.0))),
-                            MeshMaterial2d(materials.add(proj_color)),
-                            DespawnOnExit(GameState::InWave),
-                        ));
+                            (
+                                Bullet::new(dir, enemy.damage * 0.6, BULLET_SPEED),
+                                Hostile,
+                                projectile.clone(),
+                            ),
+                        );
                     }
 
                     boss.phase = BossPhase::Cooldown;
@@ -125,7 +165,7 @@ pub fn update_boss_shoot(
             // ── Cooldown: slow drift toward player; then resume chasing
             BossPhase::Cooldown => {
                 let dir = to_player.normalize_or_zero();
-                transform.translation += dir.extend(0.0) * enemy.speed * 0.4 * time.delta_secs();
+                transform.translation += dir.extend(0.0) * speed * 0.4 * time.delta_secs();
 
                 if boss.phase_timer.just_finished() {
                     boss.phase = BossPhase::Chasing;
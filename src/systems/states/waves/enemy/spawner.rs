@@ -1,53 +1,131 @@
-use crate::systems::constants::{tiles_to_pixels, ENEMY_SPAWN_TIME_IN_S, GAME_AREA, SPAWN_RATE};
-use crate::systems::game::GameState;
+use crate::systems::constants::{tiles_to_pixels, ENEMY_SPAWN_TIME_IN_S, GAME_AREA};
+use crate::systems::game::{GameRng, GameState};
 use crate::systems::states::waves::components::{Direction, Dying, Health};
 use crate::systems::states::waves::enemy::components::{
-    BossAttack, Enemy, RangedAttack, Spawning, Splitter,
+    BossAttack, Enemy, RangedAttack, Spawning, Splitter, Steering,
 };
-use crate::systems::states::waves::enemy::kinds::EnemyKind;
+use crate::systems::states::waves::enemy::elites;
+use crate::systems::states::waves::enemy::kinds::{EnemyKind, EnemyStats};
 use crate::systems::states::waves::enemy::messages::{EnemySpawnedMessage, EnemySpawningMessage};
+use crate::systems::states::waves::enemy::resources::EnemyRegistry;
 use crate::systems::states::waves::player::components::Player;
 use crate::systems::states::waves::resources::WaveManager;
+use crate::systems::states::waves::script::{WaveAction, WaveScripts};
+use crate::systems::states::waves::status::StatusEffects;
 use bevy::prelude::*;
 use rand::distr::weighted::WeightedIndex;
 use rand::distr::Distribution;
 use rand::RngExt;
 
+/// Half-size (px) of the square a horde is scattered over
+const HORDE_SPREAD: f32 = tiles_to_pixels(1.5);
+/// Regular spawns bought in a single frame, the rest of the budget waits for the next ones
+const MAX_SPAWNS_PER_FRAME: u32 = 16;
+
+/// Spends the spawn budget of the current `WaveScript` on enemies from its pool.
+#[allow(clippy::too_many_arguments)]
 pub fn create_enemy_spawning(
     mut commands: Commands,
     mut wave_manager: ResMut<WaveManager>,
     player_query: Query<&GlobalTransform, (With<Player>, Without<Dying>)>,
     mut events: MessageWriter<EnemySpawningMessage>,
+    mut rng: ResMut<GameRng>,
+    registry: Res<EnemyRegistry>,
+    scripts: Res<WaveScripts>,
     time: Res<Time>,
 ) {
-    wave_manager.enemy_spawn_timer.tick(time.delta());
-    if !wave_manager.enemy_spawn_timer.is_finished() {
+    let Ok(player_transform) = player_query.single() else {
         return;
+    };
+    let player_pos = player_transform.translation().truncate();
+    let wave = wave_manager.wave;
+    let (script, budget_scale) = scripts.for_wave(wave);
+    let progress = wave_manager.wave_timer.fraction();
+    wave_manager.spawn_budget += script.budget_at(progress) * budget_scale * time.delta_secs();
+
+    for _ in 0..MAX_SPAWNS_PER_FRAME {
+        // An expensive roll is kept until it is affordable, so cheap enemies don't win by default
+        let Some((kind, cost)) = wave_manager
+            .next_spawn
+            .take()
+            .or_else(|| script.pick_enemy(&registry, wave, &mut rng))
+        else {
+            return;
+        };
+        if wave_manager.spawn_budget < cost {
+            wave_manager.next_spawn = Some((kind, cost));
+            return;
+        }
+        wave_manager.spawn_budget -= cost;
+        let spawn_pos = generate_spawn_position(player_pos, &mut rng);
+        spawn_warning(&mut commands, &mut events, kind, spawn_pos, false);
     }
+}
+
+/// Fires the timed events of the current `WaveScript` once the wave clock reaches them.
+#[allow(clippy::too_many_arguments)]
+pub fn run_wave_events(
+    mut commands: Commands,
+    mut wave_manager: ResMut<WaveManager>,
+    player_query: Query<&GlobalTransform, (With<Player>, Without<Dying>)>,
+    mut spawning_events: MessageWriter<EnemySpawningMessage>,
+    mut spawned_events: MessageWriter<EnemySpawnedMessage>,
+    mut rng: ResMut<GameRng>,
+    registry: Res<EnemyRegistry>,
+    scripts: Res<WaveScripts>,
+) {
     let Ok(player_transform) = player_query.single() else {
         return;
     };
-
+    let player_pos = player_transform.translation().truncate();
     let wave = wave_manager.wave;
-    let kind = EnemyKind::random_for_wave(wave);
-    let spawn_pos = generate_spawn_position(player_transform.translation().truncate());
-
-    let entity = commands
-        .spawn((
-            Transform::from_translation(spawn_pos.extend(0.0)),
-            Spawning {
-                timer: Timer::from_seconds(ENEMY_SPAWN_TIME_IN_S, TimerMode::Once),
-                kind,
-            },
-            DespawnOnExit(GameState::InWave),
-        ))
-        .id();
-    events.write(EnemySpawningMessage { entity, kind });
+    let (script, _) = scripts.for_wave(wave);
+    let elapsed = wave_manager.wave_timer.elapsed_secs();
 
-    let base_rate = SPAWN_RATE / wave as f32;
-    wave_manager
-        .enemy_spawn_timer
-        .set_duration(std::time::Duration::from_secs_f32(base_rate.max(0.01)));
+    while let Some(event) = script.events.get(wave_manager.next_event)
+        && event.at <= elapsed
+    {
+        wave_manager.next_event += 1;
+        match &event.action {
+            WaveAction::Horde { enemy, count } => {
+                let Some(kind) = registry.find(enemy) else {
+                    continue;
+                };
+                let center = generate_spawn_position(player_pos, &mut rng);
+                for _ in 0..*count {
+                    let offset = Vec2::new(
+                        rng.random_range(-HORDE_SPREAD..HORDE_SPREAD),
+                        rng.random_range(-HORDE_SPREAD..HORDE_SPREAD),
+                    );
+                    let spawn_pos = (center + offset).clamp(GAME_AREA.min, GAME_AREA.max);
+                    spawn_warning(&mut commands, &mut spawning_events, kind, spawn_pos, false);
+                }
+            }
+            WaveAction::Elite { enemy, count } => {
+                let Some(kind) = registry.find(enemy) else {
+                    continue;
+                };
+                for _ in 0..*count {
+                    let spawn_pos = generate_spawn_position(player_pos, &mut rng);
+                    spawn_warning(&mut commands, &mut spawning_events, kind, spawn_pos, true);
+                }
+            }
+            WaveAction::Boss { enemy } => {
+                let Some(kind) = registry.find(enemy) else {
+                    continue;
+                };
+                let spawn_pos = generate_spawn_position(player_pos, &mut rng);
+                spawn_boss(
+                    &mut commands,
+                    &mut spawned_events,
+                    &registry,
+                    kind,
+                    wave,
+                    spawn_pos,
+                );
+            }
+        }
+    }
 }
 
 pub fn spawn_enemies(
@@ -56,6 +134,8 @@ pub fn spawn_enemies(
     mut events: MessageWriter<EnemySpawnedMessage>,
     time: Res<Time>,
     wave_manager: Res<WaveManager>,
+    registry: Res<EnemyRegistry>,
+    mut rng: ResMut<GameRng>,
 ) {
     for (entity, mut spawning, transform) in &mut pre_spawn_query {
         spawning.timer.tick(time.delta());
@@ -65,7 +145,8 @@ pub fn spawn_enemies(
         let transform = *transform;
         let kind = spawning.kind;
         let wave = wave_manager.wave;
-        let stats = kind.stats(wave);
+        let modifiers = elites::roll_modifiers(spawning.elite, wave, &mut rng);
+        let stats = elites::elite_stats(registry.get(kind).stats(wave), &modifiers);
 
         let mut entity_cmd = commands.entity(entity);
         entity_cmd.remove::<Spawning>();
@@ -75,26 +156,18 @@ pub fn spawn_enemies(
                 speed: stats.speed,
                 kind,
                 xp_reward: stats.xp_reward,
+                currency_reward: stats.currency_reward,
             },
             Direction::EAST,
             Health {
                 value: stats.health,
             },
+            StatusEffects::default(),
         ));
 
-        match kind {
-            EnemyKind::Splitter => {
-                entity_cmd.insert(Splitter { split_count: 4 });
-            }
-            EnemyKind::Ranged => {
-                entity_cmd.insert(RangedAttack {
-                    timer: Timer::from_seconds(2.5, TimerMode::Repeating),
-                    preferred_distance: 300.0,
-                    projectile_damage: stats.contact_damage * 2.5,
-                });
-            }
-            _ => {}
-        }
+        insert_behaviours(&mut entity_cmd, &registry, kind, &stats);
+        let splitter = definition_splitter(&registry, kind);
+        elites::insert_modifiers(&mut entity_cmd, modifiers, kind, &stats, splitter);
         events.write(EnemySpawnedMessage {
             entity,
             kind,
@@ -102,30 +175,41 @@ pub fn spawn_enemies(
         });
     }
 }
-pub fn spawn_boss(
-    mut commands: Commands,
-    wave_manager: Res<WaveManager>,
-    player_query: Query<&GlobalTransform, (With<Player>, Without<Dying>)>,
-    mut events: MessageWriter<EnemySpawnedMessage>,
-) {
-    let wave = wave_manager.wave;
-
-    let kind = if wave % 5 == 0 {
-        EnemyKind::Boss
-    } else if wave % 3 == 0 {
-        EnemyKind::MiniBoss
-    } else {
-        return;
-    };
-
-    let Ok(player_transform) = player_query.single() else {
-        return;
-    };
+// helper functions
 
-    let spawn_pos = generate_spawn_position(player_transform.translation().truncate());
-    let stats = kind.stats(wave);
+/// Spawn warning of an enemy, turned into the enemy itself by `spawn_enemies`
+fn spawn_warning(
+    commands: &mut Commands,
+    events: &mut MessageWriter<EnemySpawningMessage>,
+    kind: EnemyKind,
+    position: Vec2,
+    elite: bool,
+) {
+    let entity = commands
+        .spawn((
+            Transform::from_translation(position.extend(0.0)),
+            Spawning {
+                timer: Timer::from_seconds(ENEMY_SPAWN_TIME_IN_S, TimerMode::Once),
+                kind,
+                elite,
+            },
+            DespawnOnExit(GameState::InWave),
+        ))
+        .id();
+    events.write(EnemySpawningMessage { entity, kind });
+}
 
-    let transform = Transform::from_translation(spawn_pos.extend(0.0));
+/// Bosses make their entrance right away, without a spawn warning
+fn spawn_boss(
+    commands: &mut Commands,
+    events: &mut MessageWriter<EnemySpawnedMessage>,
+    registry: &EnemyRegistry,
+    kind: EnemyKind,
+    wave: u32,
+    position: Vec2,
+) {
+    let stats = registry.get(kind).stats(wave);
+    let transform = Transform::from_translation(position.extend(0.0));
     let mut entity_cmd = commands.spawn((
         transform,
         Direction::EAST,
@@ -134,25 +218,16 @@ pub fn spawn_boss(
             speed: stats.speed,
             kind,
             xp_reward: stats.xp_reward,
+            currency_reward: stats.currency_reward,
         },
         Health {
             value: stats.health,
         },
+        StatusEffects::default(),
+        DespawnOnExit(GameState::InWave),
     ));
 
-    match kind {
-        EnemyKind::Boss => {
-            entity_cmd.insert(BossAttack::default());
-        }
-        EnemyKind::MiniBoss => {
-            entity_cmd.insert(RangedAttack {
-                timer: Timer::from_seconds(2.0, TimerMode::Repeating),
-                preferred_distance: 350.0,
-                projectile_damage: stats.contact_damage * 0.7,
-            });
-        }
-        _ => {}
-    }
+    insert_behaviours(&mut entity_cmd, registry, kind, &stats);
 
     events.write(EnemySpawnedMessage {
         entity: entity_cmd.id(),
@@ -161,9 +236,44 @@ pub fn spawn_boss(
     });
 }
 
-// helper functions
+/// Split-on-death behaviour of the enemy's definition, if its split target exists
+fn definition_splitter(registry: &EnemyRegistry, kind: EnemyKind) -> Option<Splitter> {
+    let split = registry.get(kind).split.as_ref()?;
+    Some(Splitter {
+        split_count: split.count,
+        split_into: registry.find(&split.into)?,
+    })
+}
+
+/// Adds the optional attack / death components described by the enemy's definition
+fn insert_behaviours(
+    entity_cmd: &mut EntityCommands,
+    registry: &EnemyRegistry,
+    kind: EnemyKind,
+    stats: &EnemyStats,
+) {
+    let definition = registry.get(kind);
+    entity_cmd.insert(Steering {
+        radius: definition.radius,
+        separation_weight: definition.steering.separation_weight,
+        mass: definition.steering.mass.max(f32::EPSILON),
+    });
+    if let Some(splitter) = definition_splitter(registry, kind) {
+        entity_cmd.insert(splitter);
+    }
+    if let Some(ranged) = &definition.ranged {
+        entity_cmd.insert(RangedAttack {
+            timer: Timer::from_seconds(ranged.cooldown, TimerMode::Repeating),
+            preferred_distance: ranged.preferred_distance,
+            projectile_damage: stats.contact_damage * ranged.projectile_damage_ratio,
+        });
+    }
+    if definition.charge_attack {
+        entity_cmd.insert(BossAttack::default());
+    }
+}
 
-fn generate_spawn_position(player_pos: Vec2) -> Vec2 {
+fn generate_spawn_position(player_pos: Vec2, rng: &mut GameRng) -> Vec2 {
     const MIN_SPAWN_DISTANCE: f32 = tiles_to_pixels(3.0);
     const EDGE_MARGIN: f32 = tiles_to_pixels(0.5);
 
@@ -180,7 +290,7 @@ fn generate_spawn_position(player_pos: Vec2) -> Vec2 {
 
     // Compute for region around the intersection of the spawn spot and the safe spot
     let intersection = spawning_rect.intersect(safe_rect);
-    let regions = vec![
+    let regions = [
         // LEFT side
         Rect::from_corners(
             spawning_rect.min,
@@ -209,10 +319,9 @@ fn generate_spawn_position(player_pos: Vec2) -> Vec2 {
         .map(|region| region.width() * region.height())
         .collect();
 
-    let mut rng = rand::rng();
     let chosen_region = regions[WeightedIndex::new(&regions_weight)
         .unwrap()
-        .sample(&mut rng)];
+        .sample(&mut **rng)];
 
     Vec2::new(
         rng.random_range(chosen_region.min.x..chosen_region.max.x),
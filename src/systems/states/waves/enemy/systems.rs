@@ -1,7 +1,7 @@
 use crate::systems::game::{GameState, MarkedForDespawn};
 use crate::systems::states::waves::components::Health;
 use crate::systems::states::waves::enemy::components::{Enemy, Spawning, Splitter};
-use crate::systems::states::waves::enemy::kinds::EnemyKind;
+use crate::systems::states::waves::enemy::elites::Explosive;
 use crate::systems::states::waves::enemy::messages::EnemyDeathMessage;
 use bevy::math::Vec2;
 use bevy::prelude::{
@@ -9,17 +9,27 @@ use bevy::prelude::{
     TimerMode, Transform,
 };
 
+#[allow(clippy::type_complexity)]
 pub fn check_if_dead(
     mut commands: Commands,
     mut message_writer: MessageWriter<EnemyDeathMessage>,
-    query: Query<(Entity, &Health, &Enemy, &GlobalTransform, Option<&Splitter>)>,
+    query: Query<(
+        Entity,
+        &Health,
+        &Enemy,
+        &GlobalTransform,
+        Option<&Splitter>,
+        Option<&Explosive>,
+    )>,
 ) {
-    for (entity, health, enemy, transform, splitter) in query.iter() {
+    for (entity, health, enemy, transform, splitter, explosive) in query.iter() {
         if health.value <= 0.0 {
             message_writer.write(EnemyDeathMessage {
                 position: transform.translation(),
                 xp_reward: enemy.xp_reward,
-                split_count: splitter.map(|s| s.split_count).unwrap_or(0),
+                currency_reward: enemy.currency_reward,
+                splitter: splitter.copied(),
+                explosive: explosive.copied(),
             });
             commands.entity(entity).insert(MarkedForDespawn);
         }
@@ -31,12 +41,12 @@ pub fn handle_splitter_death(
     mut msg_reader: MessageReader<EnemyDeathMessage>,
 ) {
     for msg in msg_reader.read() {
-        if msg.split_count == 0 {
+        let Some(splitter) = msg.splitter else {
             continue;
-        }
+        };
 
-        for i in 0..msg.split_count {
-            let angle = (i as f32 / msg.split_count as f32) * std::f32::consts::TAU;
+        for i in 0..splitter.split_count {
+            let angle = (i as f32 / splitter.split_count as f32) * std::f32::consts::TAU;
             let offset = Vec2::new(angle.cos(), angle.sin()) * 30.0;
             let spawn_pos = msg.position.truncate() + offset;
 
@@ -44,7 +54,8 @@ pub fn handle_splitter_death(
                 Transform::from_translation(spawn_pos.extend(0.0)),
                 Spawning {
                     timer: Timer::from_seconds(0.3, TimerMode::Once),
-                    kind: EnemyKind::SmallSplitter,
+                    kind: splitter.split_into,
+                    elite: false,
                 },
                 DespawnOnExit(GameState::InWave),
             ));
@@ -2,8 +2,13 @@ pub mod camera;
 pub mod collision;
 pub mod components;
 pub mod enemy;
+pub mod navigation;
+pub mod obstacles;
 pub mod player;
 pub mod renderer;
 pub mod resources;
+pub mod script;
+pub mod spatial;
+pub mod status;
 pub mod systems;
 pub mod weapons;
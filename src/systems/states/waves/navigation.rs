@@ -0,0 +1,309 @@
+use crate::systems::constants::{GAME_AREA, TILES_X, TILES_Y, TILE_SIZE};
+use crate::systems::states::waves::obstacles::ObstacleMap;
+use crate::systems::states::waves::player::components::Player;
+use bevy::prelude::*;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// Path costs, diagonals being roughly `sqrt(2)` times longer
+const STRAIGHT_COST: u32 = 10;
+const DIAGONAL_COST: u32 = 14;
+const UNREACHABLE: u32 = u32::MAX;
+
+const NEIGHBOURS: [IVec2; 8] = [
+    IVec2::new(1, 0),
+    IVec2::new(-1, 0),
+    IVec2::new(0, 1),
+    IVec2::new(0, -1),
+    IVec2::new(1, 1),
+    IVec2::new(1, -1),
+    IVec2::new(-1, 1),
+    IVec2::new(-1, -1),
+];
+
+/// Distance to the player over the `TILES_X` × `TILES_Y` grid, avoiding the cells taken by
+/// blocking obstacles. Every cell stores the direction of its best neighbour, so each enemy
+/// only does a lookup no matter how many share the field.
+#[derive(Resource)]
+pub struct FlowField {
+    cols: i32,
+    rows: i32,
+    blocked: Vec<bool>,
+    distances: Vec<u32>,
+    directions: Vec<Vec2>,
+    /// Cell the field leads to, `None` until the first build
+    target: Option<IVec2>,
+}
+
+impl Default for FlowField {
+    fn default() -> Self {
+        let cells = (TILES_X * TILES_Y) as usize;
+        Self {
+            cols: TILES_X as i32,
+            rows: TILES_Y as i32,
+            blocked: vec![false; cells],
+            distances: vec![UNREACHABLE; cells],
+            directions: vec![Vec2::ZERO; cells],
+            target: None,
+        }
+    }
+}
+
+impl FlowField {
+    /// Direction to follow from `position` to reach `target`. Straight at it when nothing
+    /// stands in the way, once in the same cell, or when there is no known path.
+    pub fn direction(&self, position: Vec2, target: Vec2) -> Vec2 {
+        let straight = (target - position).normalize_or_zero();
+        let Some(target_cell) = self.target else {
+            return straight;
+        };
+        let cell = self.cell(position);
+        let index = self.index(cell);
+        // No detour needed: the path is as short as on an empty grid
+        if self.distances[index] == octile_distance(cell, target_cell) {
+            return straight;
+        }
+        let flow = self.directions[index];
+        if flow == Vec2::ZERO {
+            straight
+        } else {
+            flow
+        }
+    }
+
+    /// Direction leading away from the target along walkable cells, for kiting enemies.
+    /// Falls back to straight away from `target` when no neighbour is farther.
+    pub fn away(&self, position: Vec2, target: Vec2) -> Vec2 {
+        let straight = (position - target).normalize_or_zero();
+        if self.target.is_none() {
+            return straight;
+        }
+        let cell = self.cell(position);
+        let own = self.distances[self.index(cell)];
+        self.walkable_neighbours(cell)
+            .filter(|(_, neighbour)| {
+                let distance = self.distances[self.index(*neighbour)];
+                distance != UNREACHABLE && (own == UNREACHABLE || distance > own)
+            })
+            // Among the farther cells, prefer the one closest to straight away
+            .max_by(|(a, _), (b, _)| {
+                a.as_vec2()
+                    .normalize()
+                    .dot(straight)
+                    .total_cmp(&b.as_vec2().normalize().dot(straight))
+            })
+            .map_or(straight, |(offset, _)| offset.as_vec2().normalize())
+    }
+
+    fn cell(&self, position: Vec2) -> IVec2 {
+        let local = ((position - GAME_AREA.min) / TILE_SIZE).floor().as_ivec2();
+        local.clamp(IVec2::ZERO, IVec2::new(self.cols - 1, self.rows - 1))
+    }
+
+    fn index(&self, cell: IVec2) -> usize {
+        (cell.y * self.cols + cell.x) as usize
+    }
+
+    fn center(cell: IVec2) -> Vec2 {
+        GAME_AREA.min + (cell.as_vec2() + Vec2::splat(0.5)) * TILE_SIZE
+    }
+
+    fn contains(&self, cell: IVec2) -> bool {
+        cell.x >= 0 && cell.y >= 0 && cell.x < self.cols && cell.y < self.rows
+    }
+
+    fn is_walkable(&self, cell: IVec2) -> bool {
+        self.contains(cell) && !self.blocked[self.index(cell)]
+    }
+
+    /// In-bounds, unblocked neighbours of `cell` as (offset, cell).
+    /// Diagonals need both adjacent sides free, so paths never clip an obstacle's corner.
+    fn walkable_neighbours(&self, cell: IVec2) -> impl Iterator<Item = (IVec2, IVec2)> + '_ {
+        NEIGHBOURS.iter().filter_map(move |offset| {
+            let neighbour = cell + *offset;
+            let diagonal_clear = offset.x == 0
+                || offset.y == 0
+                || (self.is_walkable(cell + IVec2::new(offset.x, 0))
+                    && self.is_walkable(cell + IVec2::new(0, offset.y)));
+            (self.is_walkable(neighbour) && diagonal_clear).then_some((*offset, neighbour))
+        })
+    }
+
+    /// Marks every cell overlapped by a blocking obstacle.
+    fn rebuild_blocked(&mut self, obstacles: &ObstacleMap) {
+        for y in 0..self.rows {
+            for x in 0..self.cols {
+                let cell = IVec2::new(x, y);
+                let index = self.index(cell);
+                let min = Self::center(cell) - Vec2::splat(TILE_SIZE / 2.0);
+                let bounds = Rect::from_corners(min, min + Vec2::splat(TILE_SIZE));
+                self.blocked[index] = obstacles.blocks_rect(bounds);
+            }
+        }
+    }
+
+    /// Dijkstra from `target` over the walkable cells, then points every cell at its
+    /// closest-to-target neighbour.
+    fn rebuild(&mut self, target: IVec2) {
+        self.target = Some(target);
+        self.distances.fill(UNREACHABLE);
+        let mut queue = BinaryHeap::new();
+        let start = self.index(target);
+        self.distances[start] = 0;
+        queue.push(Reverse((0, target.x, target.y)));
+
+        while let Some(Reverse((distance, x, y))) = queue.pop() {
+            let cell = IVec2::new(x, y);
+            if distance > self.distances[self.index(cell)] {
+                continue;
+            }
+            for (offset, neighbour) in self.walkable_neighbours(cell).collect::<Vec<_>>() {
+                let step = if offset.x != 0 && offset.y != 0 {
+                    DIAGONAL_COST
+                } else {
+                    STRAIGHT_COST
+                };
+                let index = self.index(neighbour);
+                if distance + step < self.distances[index] {
+                    self.distances[index] = distance + step;
+                    queue.push(Reverse((distance + step, neighbour.x, neighbour.y)));
+                }
+            }
+        }
+
+        for y in 0..self.rows {
+            for x in 0..self.cols {
+                let cell = IVec2::new(x, y);
+                let own = self.distances[self.index(cell)];
+                // Blocked cells have no distance of their own but still lead out to the path
+                let best = self
+                    .walkable_neighbours(cell)
+                    .map(|(offset, neighbour)| (offset, self.distances[self.index(neighbour)]))
+                    .filter(|(_, distance)| *distance < own)
+                    .min_by_key(|(_, distance)| *distance);
+                let index = self.index(cell);
+                self.directions[index] =
+                    best.map_or(Vec2::ZERO, |(offset, _)| offset.as_vec2().normalize());
+            }
+        }
+    }
+}
+
+/// Path cost between two cells with no obstacle in between
+fn octile_distance(a: IVec2, b: IVec2) -> u32 {
+    let delta = (a - b).abs();
+    let diagonal = delta.x.min(delta.y) as u32;
+    let straight = delta.x.max(delta.y) as u32 - diagonal;
+    diagonal * DIAGONAL_COST + straight * STRAIGHT_COST
+}
+
+/// Rebuilds the field when the player steps into another cell or the obstacles change.
+pub fn update_flow_field(
+    mut field: ResMut<FlowField>,
+    obstacles: Res<ObstacleMap>,
+    player: Option<Single<&GlobalTransform, With<Player>>>,
+) {
+    let Some(player) = player else {
+        return;
+    };
+    let target = field.cell(player.translation().truncate());
+    if obstacles.is_changed() {
+        field.rebuild_blocked(&obstacles);
+    } else if field.target == Some(target) {
+        return;
+    }
+    field.rebuild(target);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(blocked: &[IVec2], target: IVec2) -> FlowField {
+        let mut field = FlowField::default();
+        for cell in blocked {
+            let index = field.index(*cell);
+            field.blocked[index] = true;
+        }
+        field.rebuild(target);
+        field
+    }
+
+    /// Cells visited when following the field from `from`, up to `target`.
+    fn follow(field: &FlowField, from: IVec2, target: IVec2) -> Vec<IVec2> {
+        let mut path = vec![from];
+        let mut cell = from;
+        while cell != target {
+            assert!(path.len() < 20, "no way to the target: {path:?}");
+            let offset = field
+                .direction(FlowField::center(cell), FlowField::center(target))
+                .round()
+                .as_ivec2();
+            assert!(
+                field
+                    .walkable_neighbours(cell)
+                    .any(|(step, _)| step == offset),
+                "step {offset} from {cell} is blocked or cuts a corner"
+            );
+            cell += offset;
+            path.push(cell);
+        }
+        path
+    }
+
+    #[test]
+    fn open_ground_goes_straight_at_the_target() {
+        let target = IVec2::new(5, 5);
+        let field = field(&[], target);
+        let position = FlowField::center(IVec2::new(9, 7)) + Vec2::new(3.0, -11.0);
+        let goal = FlowField::center(target);
+        assert_eq!(
+            field.direction(position, goal),
+            (goal - position).normalize()
+        );
+        assert_eq!(field.distances[field.index(IVec2::new(9, 7))], 48);
+    }
+
+    #[test]
+    fn paths_go_around_a_blocked_cell_without_cutting_corners() {
+        let target = IVec2::new(5, 5);
+        let wall = IVec2::new(4, 5);
+        let field = field(&[wall], target);
+
+        let from = IVec2::new(3, 5);
+        assert!(field.distances[field.index(from)] > octile_distance(from, target));
+        let path = follow(&field, from, target);
+        assert!(!path.contains(&wall));
+        // Around the wall: up or down, across, then back in
+        assert_eq!(path.len(), 5, "{path:?}");
+    }
+
+    #[test]
+    fn blocked_cells_lead_out_to_the_path() {
+        let target = IVec2::new(5, 5);
+        let wall = IVec2::new(4, 5);
+        let field = field(&[wall], target);
+
+        let out = field.directions[field.index(wall)];
+        assert_ne!(out, Vec2::ZERO);
+        let next = wall + out.round().as_ivec2();
+        assert!(field.is_walkable(next));
+        assert!(field.distances[field.index(next)] < UNREACHABLE);
+    }
+
+    #[test]
+    fn an_enclosed_pocket_falls_back_to_straight() {
+        let target = IVec2::new(5, 5);
+        let pocket = IVec2::new(12, 9);
+        let walls: Vec<IVec2> = NEIGHBOURS.iter().map(|offset| pocket + *offset).collect();
+        let field = field(&walls, target);
+
+        assert_eq!(field.distances[field.index(pocket)], UNREACHABLE);
+        let position = FlowField::center(pocket);
+        let goal = FlowField::center(target);
+        assert_eq!(
+            field.direction(position, goal),
+            (goal - position).normalize()
+        );
+    }
+}
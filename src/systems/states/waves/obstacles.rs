@@ -0,0 +1,278 @@
+use crate::systems::constants::{tiles_to_pixels, GAME_AREA};
+use crate::systems::game::{GameRng, GameState, MarkedForDespawn};
+use crate::systems::states::waves::enemy::components::{Enemy, Steering};
+use crate::systems::states::waves::player::components::Player;
+use crate::systems::states::waves::systems::y_sort_depth;
+use crate::systems::states::waves::weapons::components::Bullet;
+use bevy::prelude::*;
+use rand::RngExt;
+
+/// Nothing is placed this close to the player when a wave starts
+const CLEAR_ZONE_RADIUS: f32 = tiles_to_pixels(3.0);
+/// Free space kept between two obstacles, so there is always a way through
+const MIN_GAP: f32 = tiles_to_pixels(1.0);
+const PLACEMENT_ATTEMPTS: u32 = 20;
+/// Collision radius of the player against obstacles
+const PLAYER_RADIUS: f32 = 12.0;
+/// Overlap (px) below which an obstacle is not considered to take a navigation cell
+const GRAZE_TOLERANCE: f32 = 8.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObstacleKind {
+    Tree,
+    Rock,
+    /// Can be walked and shot through, but hides the enemies inside it
+    Bush,
+}
+
+impl ObstacleKind {
+    const ALL: [ObstacleKind; 3] = [ObstacleKind::Tree, ObstacleKind::Rock, ObstacleKind::Bush];
+
+    pub fn blocks(&self) -> bool {
+        !matches!(self, ObstacleKind::Bush)
+    }
+
+    /// Collision radius (px) around the base of the prop
+    pub fn radius(&self) -> f32 {
+        match self {
+            ObstacleKind::Tree => 20.0,
+            ObstacleKind::Rock => 20.0,
+            ObstacleKind::Bush => 30.0,
+        }
+    }
+
+    /// Props of this kind placed on the map every wave
+    fn count(&self) -> u32 {
+        match self {
+            ObstacleKind::Tree => 12,
+            ObstacleKind::Rock => 8,
+            ObstacleKind::Bush => 10,
+        }
+    }
+}
+
+/// One image of `assets/sprites`, either a single frame or a horizontal strip of frames.
+pub struct Prop {
+    pub kind: ObstacleKind,
+    pub sprite: &'static str,
+    pub frame_size: UVec2,
+    pub frames: u32,
+    pub scale: f32,
+    /// Sprite anchor on the y axis, so the base of the prop sits on the collision shape
+    pub anchor_y: f32,
+}
+
+pub const PROPS: [Prop; 12] = [
+    tree("sprites/Trees/Tree1.png", 256, -0.38),
+    tree("sprites/Trees/Tree2.png", 256, -0.38),
+    tree("sprites/Trees/Tree3.png", 192, -0.33),
+    tree("sprites/Trees/Tree4.png", 192, -0.33),
+    rock("sprites/Rocks/Rock1.png"),
+    rock("sprites/Rocks/Rock2.png"),
+    rock("sprites/Rocks/Rock3.png"),
+    rock("sprites/Rocks/Rock4.png"),
+    bush("sprites/Bushes/Bushe1.png"),
+    bush("sprites/Bushes/Bushe2.png"),
+    bush("sprites/Bushes/Bushe3.png"),
+    bush("sprites/Bushes/Bushe4.png"),
+];
+
+const fn tree(sprite: &'static str, height: u32, anchor_y: f32) -> Prop {
+    Prop {
+        kind: ObstacleKind::Tree,
+        sprite,
+        frame_size: UVec2::new(192, height),
+        frames: 8,
+        scale: 0.75,
+        anchor_y,
+    }
+}
+
+const fn rock(sprite: &'static str) -> Prop {
+    Prop {
+        kind: ObstacleKind::Rock,
+        sprite,
+        frame_size: UVec2::splat(64),
+        frames: 1,
+        scale: 1.25,
+        anchor_y: -0.15,
+    }
+}
+
+const fn bush(sprite: &'static str) -> Prop {
+    Prop {
+        kind: ObstacleKind::Bush,
+        sprite,
+        frame_size: UVec2::splat(128),
+        frames: 8,
+        scale: 0.75,
+        anchor_y: -0.08,
+    }
+}
+
+/// Prop placed on the map, its translation is the center of the collision shape.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Obstacle {
+    /// Index in `PROPS`
+    pub prop: usize,
+}
+
+/// Enemy standing in a bush, drawn faded out.
+#[derive(Component)]
+pub struct InBush;
+
+#[derive(Debug, Clone, Copy)]
+struct PlacedObstacle {
+    position: Vec2,
+    radius: f32,
+    kind: ObstacleKind,
+}
+
+/// Collision shapes of the obstacles of the current wave.
+#[derive(Resource, Default)]
+pub struct ObstacleMap {
+    obstacles: Vec<PlacedObstacle>,
+}
+
+impl ObstacleMap {
+    fn blocking(&self) -> impl Iterator<Item = &PlacedObstacle> {
+        self.obstacles
+            .iter()
+            .filter(|obstacle| obstacle.kind.blocks())
+    }
+
+    /// `position` moved out of every blocking obstacle a circle of `radius` overlaps
+    pub fn resolve(&self, mut position: Vec2, radius: f32) -> Vec2 {
+        for obstacle in self.blocking() {
+            let offset = position - obstacle.position;
+            let min_distance = obstacle.radius + radius;
+            let distance = offset.length();
+            if distance < min_distance {
+                position = obstacle.position + offset.normalize_or(Vec2::Y) * min_distance;
+            }
+        }
+        position
+    }
+
+    pub fn blocks_point(&self, position: Vec2) -> bool {
+        self.blocking()
+            .any(|obstacle| obstacle.position.distance_squared(position) < obstacle.radius.powi(2))
+    }
+
+    pub fn is_in_bush(&self, position: Vec2) -> bool {
+        self.obstacles.iter().any(|obstacle| {
+            obstacle.kind == ObstacleKind::Bush
+                && obstacle.position.distance_squared(position) < obstacle.radius.powi(2)
+        })
+    }
+
+    /// Whether a blocking obstacle covers a meaningful part of `bounds`, grazing it by less
+    /// than `GRAZE_TOLERANCE` does not count
+    pub fn blocks_rect(&self, bounds: Rect) -> bool {
+        self.blocking().any(|obstacle| {
+            let closest = obstacle.position.clamp(bounds.min, bounds.max);
+            closest.distance(obstacle.position) < obstacle.radius - GRAZE_TOLERANCE
+        })
+    }
+
+    fn fits(&self, position: Vec2, radius: f32) -> bool {
+        self.obstacles.iter().all(|obstacle| {
+            obstacle.position.distance(position) >= obstacle.radius + radius + MIN_GAP
+        })
+    }
+}
+
+/// Scatters trees, rocks and bushes over `GAME_AREA`, away from the player.
+pub fn populate_obstacles(
+    mut commands: Commands,
+    mut map: ResMut<ObstacleMap>,
+    mut rng: ResMut<GameRng>,
+    player: Option<Single<&Transform, With<Player>>>,
+) {
+    let player_pos = player.map_or(Vec2::ZERO, |transform| transform.translation.truncate());
+    let area = Rect::from_corners(
+        GAME_AREA.min + Vec2::splat(tiles_to_pixels(1.0)),
+        GAME_AREA.max - Vec2::splat(tiles_to_pixels(1.0)),
+    );
+    map.obstacles.clear();
+
+    for kind in ObstacleKind::ALL {
+        let props: Vec<usize> = (0..PROPS.len())
+            .filter(|index| PROPS[*index].kind == kind)
+            .collect();
+        for _ in 0..kind.count() {
+            let placed = (0..PLACEMENT_ATTEMPTS)
+                .map(|_| {
+                    Vec2::new(
+                        rng.random_range(area.min.x..area.max.x),
+                        rng.random_range(area.min.y..area.max.y),
+                    )
+                })
+                .find(|position| {
+                    position.distance(player_pos) > CLEAR_ZONE_RADIUS + kind.radius()
+                        && map.fits(*position, kind.radius())
+                });
+            let Some(position) = placed else {
+                continue;
+            };
+            let prop = props[rng.random_range(0..props.len())];
+            map.obstacles.push(PlacedObstacle {
+                position,
+                radius: kind.radius(),
+                kind,
+            });
+            commands.spawn((
+                Obstacle { prop },
+                Transform::from_translation(position.extend(y_sort_depth(position.y)))
+                    .with_scale(Vec3::splat(PROPS[prop].scale)),
+                DespawnOnExit(GameState::InWave),
+            ));
+        }
+    }
+}
+
+/// Keeps the player and the enemies out of trees and rocks.
+#[allow(clippy::type_complexity)]
+pub fn collide_with_obstacles(
+    map: Res<ObstacleMap>,
+    mut query: Query<(&mut Transform, Option<&Steering>), Or<(With<Player>, With<Enemy>)>>,
+) {
+    for (mut transform, steering) in &mut query {
+        let radius = steering.map_or(PLAYER_RADIUS, |steering| steering.radius);
+        let position = transform.translation.truncate();
+        let resolved = map.resolve(position, radius);
+        if resolved != position {
+            transform.translation.x = resolved.x;
+            transform.translation.y = resolved.y;
+        }
+    }
+}
+
+/// Trees and rocks stop every bullet, the player's and the enemies'.
+#[allow(clippy::type_complexity)]
+pub fn block_bullets(
+    mut commands: Commands,
+    map: Res<ObstacleMap>,
+    bullet_query: Query<(Entity, &Transform), (With<Bullet>, Without<MarkedForDespawn>)>,
+) {
+    for (entity, transform) in &bullet_query {
+        if map.blocks_point(transform.translation.truncate()) {
+            commands.entity(entity).insert(MarkedForDespawn);
+        }
+    }
+}
+
+pub fn hide_in_bushes(
+    mut commands: Commands,
+    map: Res<ObstacleMap>,
+    enemy_query: Query<(Entity, &Transform, Has<InBush>), With<Enemy>>,
+) {
+    for (entity, transform, hidden) in &enemy_query {
+        let in_bush = map.is_in_bush(transform.translation.truncate());
+        if in_bush && !hidden {
+            commands.entity(entity).insert(InBush);
+        } else if !in_bush && hidden {
+            commands.entity(entity).remove::<InBush>();
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use crate::systems::states::waves::components::Health;
+use crate::systems::states::waves::enemy::messages::EnemyDeathMessage;
+use crate::systems::states::waves::player::components::{
+    Dash, Player, PlayerBundle, PlayerStats, StatKind,
+};
+use bevy::asset::Asset;
+use bevy::color::Color;
+use bevy::prelude::*;
+use bevy::reflect::TypePath;
+use serde::Deserialize;
+
+/// Always playable, every other character has to be unlocked from the profile.
+pub const DEFAULT_CHARACTER: &str = "wanderer";
+
+/// Spritesheets making up `PlayerAnimations`, paths relative to `assets/`.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct CharacterSprites {
+    pub idle: String,
+    pub walk: String,
+    pub dash: String,
+    pub death: String,
+    pub shadow: String,
+    /// sRGB components in 0..1 multiplied with every frame
+    pub tint: (f32, f32, f32),
+}
+
+impl Default for CharacterSprites {
+    fn default() -> Self {
+        Self {
+            idle: "spritesheet/player/Idle_spritesheet_8x6.png".to_string(),
+            walk: "spritesheet/player/walk_spritesheet_8x6.png".to_string(),
+            dash: "spritesheet/player/dash_spritesheet_8x6.png".to_string(),
+            death: "spritesheet/player/death_spritesheet_8x6.png".to_string(),
+            shadow: "spritesheet/player/shadow_sprite.png".to_string(),
+            tint: (1.0, 1.0, 1.0),
+        }
+    }
+}
+
+impl CharacterSprites {
+    pub fn tint(&self) -> Color {
+        let (r, g, b) = self.tint;
+        Color::srgb(r, g, b)
+    }
+}
+
+/// Mechanic unique to a character, kept on the player for the whole run.
+#[derive(Component, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub enum CharacterPassive {
+    #[default]
+    None,
+    /// Every weapon fires this many more projectiles per shot
+    ExtraProjectiles(u32),
+    /// Health restored for every enemy killed, up to the max health
+    LifeOnKill(f32),
+    /// Multiplies the dash cooldown
+    QuickDash(f32),
+}
+
+impl CharacterPassive {
+    pub fn description(&self) -> String {
+        match self {
+            CharacterPassive::None => "No passive".to_string(),
+            CharacterPassive::ExtraProjectiles(count) => {
+                format!("+{} projectile on all weapons", count)
+            }
+            CharacterPassive::LifeOnKill(value) => format!("Heals {:.0} HP per kill", value),
+            CharacterPassive::QuickDash(factor) => {
+                format!("-{:.0}% dash cooldown", (1.0 - factor) * 100.0)
+            }
+        }
+    }
+}
+
+/// One playable character, loaded from `assets/characters/<id>.character.ron`.
+#[derive(Asset, TypePath, Deserialize, Debug, Clone)]
+pub struct CharacterDefinition {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    #[serde(default)]
+    pub sprites: CharacterSprites,
+    /// Added to the default `PlayerStats`, like upgrade cards
+    #[serde(default)]
+    pub stat_bonuses: Vec<(StatKind, f32)>,
+    /// `WeaponDefinition` ids, empty uses the starting weapon picked in the profile
+    #[serde(default)]
+    pub starting_weapons: Vec<String>,
+    #[serde(default)]
+    pub passive: CharacterPassive,
+}
+
+impl Default for CharacterDefinition {
+    fn default() -> Self {
+        Self {
+            id: DEFAULT_CHARACTER.to_string(),
+            name: "Wanderer".to_string(),
+            description: String::new(),
+            sprites: CharacterSprites::default(),
+            stat_bonuses: Vec::new(),
+            starting_weapons: Vec::new(),
+            passive: CharacterPassive::None,
+        }
+    }
+}
+
+impl CharacterDefinition {
+    pub fn stats(&self) -> PlayerStats {
+        let mut stats = PlayerStats::default();
+        for (kind, value) in &self.stat_bonuses {
+            stats.add(*kind, *value);
+        }
+        if let CharacterPassive::ExtraProjectiles(count) = self.passive {
+            stats.extra_projectiles += count;
+        }
+        stats
+    }
+
+    /// A fresh player of this character, at full health.
+    pub fn player_bundle(&self) -> PlayerBundle {
+        let stats = self.stats();
+        let dash = match self.passive {
+            CharacterPassive::QuickDash(factor) => Dash::with_cooldown_factor(factor),
+            _ => Dash::default(),
+        };
+        PlayerBundle {
+            health: Health {
+                value: stats.max_health,
+            },
+            stats,
+            dash,
+            passive: self.passive,
+            ..default()
+        }
+    }
+}
+
+/// Every loaded `CharacterDefinition`, the default character first and the others by id.
+#[derive(Resource, Default)]
+pub struct CharactersLibrary {
+    definitions: Vec<CharacterDefinition>,
+}
+
+impl CharactersLibrary {
+    pub fn new(mut definitions: Vec<CharacterDefinition>) -> Self {
+        definitions.sort_by(|a, b| {
+            (a.id != DEFAULT_CHARACTER, &a.id).cmp(&(b.id != DEFAULT_CHARACTER, &b.id))
+        });
+        Self { definitions }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &CharacterDefinition> {
+        self.definitions.iter()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&CharacterDefinition> {
+        self.definitions.get(index)
+    }
+
+    pub fn find(&self, id: &str) -> Option<&CharacterDefinition> {
+        self.definitions
+            .iter()
+            .find(|definition| definition.id == id)
+    }
+
+    /// `id`, or the built-in default when it is not (or no longer) loaded.
+    pub fn get_or_default(&self, id: &str) -> CharacterDefinition {
+        self.find(id)
+            .or_else(|| self.find(DEFAULT_CHARACTER))
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+/// Character the next run starts with, picked on the character select screen.
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct SelectedCharacter(pub String);
+
+impl Default for SelectedCharacter {
+    fn default() -> Self {
+        Self(DEFAULT_CHARACTER.to_string())
+    }
+}
+
+pub fn heal_on_kill(
+    mut msg_reader: MessageReader<EnemyDeathMessage>,
+    player: Single<(&CharacterPassive, &PlayerStats, &mut Health), With<Player>>,
+) {
+    let kills = msg_reader.read().count();
+    let (passive, stats, mut health) = player.into_inner();
+    let CharacterPassive::LifeOnKill(value) = *passive else {
+        return;
+    };
+    // A dying player stays dead
+    if kills == 0 || health.value <= 0.0 {
+        return;
+    }
+    health.value = (health.value + value * kills as f32).min(stats.max_health);
+}
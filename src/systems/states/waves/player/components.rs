@@ -1,6 +1,16 @@
+use crate::systems::constants::tiles_to_pixels;
 use crate::systems::states::waves::components::{Action, Direction, Health};
+use crate::systems::states::waves::player::characters::CharacterPassive;
+use crate::systems::states::waves::player::currency::Currency;
 use crate::systems::states::waves::player::experience::PlayerExperience;
-use bevy::prelude::{Bundle, Component};
+use crate::systems::states::waves::player::resources::{
+    BASE_CRIT_CHANCE, BASE_CRIT_MULTIPLIER, DASH_COOLDOWN, DASH_DISTANCE, DASH_DURATION,
+    DASH_INVULNERABILITY,
+};
+use bevy::math::Vec2;
+use bevy::prelude::{Bundle, Component, Timer, TimerMode};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 #[derive(Component)]
 pub struct Player;
@@ -11,9 +21,12 @@ pub struct PlayerBundle {
     pub player: Player,
     pub health: Health,
     pub xp: PlayerExperience,
+    pub currency: Currency,
     pub stats: PlayerStats,
     pub action: Action,
     pub direction: Direction,
+    pub dash: Dash,
+    pub passive: CharacterPassive,
 }
 
 impl Default for PlayerBundle {
@@ -22,30 +35,142 @@ impl Default for PlayerBundle {
             player: Player,
             health: Default::default(),
             xp: Default::default(),
+            currency: Default::default(),
             stats: Default::default(),
             action: Action::IDLE,
             direction: Direction::EAST,
+            dash: Default::default(),
+            passive: Default::default(),
         }
     }
 }
 
+/// Short burst of movement in a locked direction, with invulnerability frames
+#[derive(Component, Debug, Clone)]
+pub struct Dash {
+    pub cooldown: Timer,
+    pub duration: Timer,
+    pub invulnerability: Timer,
+    /// Pixels covered by one dash
+    pub distance: f32,
+    pub direction: Vec2,
+}
+
+impl Default for Dash {
+    fn default() -> Self {
+        Self {
+            cooldown: finished_timer(DASH_COOLDOWN),
+            duration: finished_timer(DASH_DURATION),
+            invulnerability: finished_timer(DASH_INVULNERABILITY),
+            distance: DASH_DISTANCE,
+            direction: Vec2::ZERO,
+        }
+    }
+}
+
+impl Dash {
+    /// Dash whose cooldown is `factor` times the default one, ready right away
+    pub fn with_cooldown_factor(factor: f32) -> Self {
+        Self {
+            cooldown: finished_timer(DASH_COOLDOWN * factor),
+            ..Self::default()
+        }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.cooldown.is_finished()
+    }
+
+    pub fn is_dashing(&self) -> bool {
+        !self.duration.is_finished()
+    }
+
+    pub fn is_invulnerable(&self) -> bool {
+        !self.invulnerability.is_finished()
+    }
+
+    /// Pixels per second while dashing
+    pub fn speed(&self) -> f32 {
+        self.distance / self.duration.duration().as_secs_f32()
+    }
+
+    pub fn start(&mut self, direction: Vec2) {
+        self.direction = direction;
+        self.cooldown.reset();
+        self.duration.reset();
+        self.invulnerability.reset();
+    }
+
+    pub fn tick(&mut self, delta: Duration) {
+        self.cooldown.tick(delta);
+        self.duration.tick(delta);
+        self.invulnerability.tick(delta);
+    }
+}
+
+fn finished_timer(seconds: f32) -> Timer {
+    let mut timer = Timer::from_seconds(seconds, TimerMode::Once);
+    timer.finish();
+    timer
+}
+
 /// Core player statistics that affect gameplay
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
 pub enum StatKind {
     Damage,
     FireRate,
     Range,
     MaxHealth,
     Speed,
+    CritChance,
+    CritMultiplier,
+    Pierce,
+    Bounce,
+    Chain,
+}
+
+impl StatKind {
+    /// Stats counted in whole units rather than multipliers
+    pub fn is_count(&self) -> bool {
+        matches!(self, StatKind::Pierce | StatKind::Bounce | StatKind::Chain)
+    }
 }
 
-#[derive(Component, Clone, Debug)]
+#[derive(Component, Clone, Debug, Serialize, Deserialize)]
 pub struct PlayerStats {
     pub damage_multiplier: f32,
     pub fire_rate_multiplier: f32,
     pub range_multiplier: f32,
     pub max_health: f32,
     pub speed_multiplier: f32,
+    /// Distance (px) at which experience orbs start flying toward the player
+    pub pickup_radius: f32,
+    /// Projectiles added to every shot of every weapon
+    #[serde(default)]
+    pub extra_projectiles: u32,
+    /// Odds (0..1) of every hit dealing critical damage
+    #[serde(default = "default_crit_chance")]
+    pub crit_chance: f32,
+    /// Damage multiplier of a critical hit
+    #[serde(default = "default_crit_multiplier")]
+    pub crit_multiplier: f32,
+    /// Enemies added to the pierce of every weapon
+    #[serde(default)]
+    pub extra_pierce: u32,
+    /// Ricochets added to every weapon
+    #[serde(default)]
+    pub extra_bounces: u32,
+    /// Chain lightning jumps added to every weapon
+    #[serde(default)]
+    pub extra_chains: u32,
+}
+
+fn default_crit_chance() -> f32 {
+    BASE_CRIT_CHANCE
+}
+
+fn default_crit_multiplier() -> f32 {
+    BASE_CRIT_MULTIPLIER
 }
 
 impl Default for PlayerStats {
@@ -56,6 +181,13 @@ impl Default for PlayerStats {
             range_multiplier: 1.0,
             max_health: 100.0,
             speed_multiplier: 1.0,
+            pickup_radius: tiles_to_pixels(1.5),
+            extra_projectiles: 0,
+            crit_chance: BASE_CRIT_CHANCE,
+            crit_multiplier: BASE_CRIT_MULTIPLIER,
+            extra_pierce: 0,
+            extra_bounces: 0,
+            extra_chains: 0,
         }
     }
 }
@@ -69,6 +201,26 @@ impl PlayerStats {
             StatKind::Range => self.range_multiplier,
             StatKind::MaxHealth => self.max_health,
             StatKind::Speed => self.speed_multiplier,
+            StatKind::CritChance => self.crit_chance,
+            StatKind::CritMultiplier => self.crit_multiplier,
+            StatKind::Pierce => self.extra_pierce as f32,
+            StatKind::Bounce => self.extra_bounces as f32,
+            StatKind::Chain => self.extra_chains as f32,
+        }
+    }
+
+    pub fn add(&mut self, kind: StatKind, value: f32) {
+        match kind {
+            StatKind::Damage => self.damage_multiplier += value,
+            StatKind::FireRate => self.fire_rate_multiplier += value,
+            StatKind::Range => self.range_multiplier += value,
+            StatKind::MaxHealth => self.max_health += value,
+            StatKind::Speed => self.speed_multiplier += value,
+            StatKind::CritChance => self.crit_chance = (self.crit_chance + value).min(1.0),
+            StatKind::CritMultiplier => self.crit_multiplier += value,
+            StatKind::Pierce => self.extra_pierce = add_count(self.extra_pierce, value),
+            StatKind::Bounce => self.extra_bounces = add_count(self.extra_bounces, value),
+            StatKind::Chain => self.extra_chains = add_count(self.extra_chains, value),
         }
     }
 
@@ -76,7 +228,13 @@ impl PlayerStats {
     pub fn format_value(&self, kind: StatKind) -> String {
         match kind {
             StatKind::MaxHealth => format!("{:.0}", self.max_health),
+            StatKind::CritChance => format!("{:.0}%", self.crit_chance * 100.0),
+            kind if kind.is_count() => format!("+{:.0}", self.get_value(kind)),
             _ => format!("x{:.2}", self.get_value(kind)),
         }
     }
 }
+
+fn add_count(count: u32, value: f32) -> u32 {
+    (count as f32 + value).round().max(0.0) as u32
+}
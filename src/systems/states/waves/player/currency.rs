@@ -0,0 +1,19 @@
+use crate::systems::states::waves::enemy::messages::EnemyDeathMessage;
+use crate::systems::states::waves::player::components::Player;
+use bevy::prelude::*;
+
+/// Money earned from kills during the waves and spent in the shop
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Currency(pub u32);
+
+pub fn earn_currency(
+    mut msg_reader: MessageReader<EnemyDeathMessage>,
+    mut player_query: Query<&mut Currency, With<Player>>,
+) {
+    let Ok(mut currency) = player_query.single_mut() else {
+        return;
+    };
+    for event in msg_reader.read() {
+        currency.0 += event.currency_reward;
+    }
+}
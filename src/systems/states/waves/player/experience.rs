@@ -1,10 +1,26 @@
 use crate::systems::constants::NEXT_LEVEL_RATIO_PERCENT;
+use crate::systems::game::GameState;
+use crate::systems::states::waves::components::Dying;
 use crate::systems::states::waves::enemy::messages::EnemyDeathMessage;
 use crate::systems::states::waves::player::components::Player;
 use crate::systems::states::waves::player::components::PlayerStats;
+use crate::systems::states::waves::resources::WaveManager;
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Component, Debug)]
+/// Distance (px) at which a flying orb is absorbed by the player.
+const ORB_COLLECT_DISTANCE: f32 = 16.0;
+
+/// Speed (px/s) of an orb when the player first comes within pickup radius.
+const ORB_BASE_SPEED: f32 = 200.0;
+
+/// Orbs keep speeding up so they always catch up with a running player.
+const ORB_ACCELERATION: f32 = 900.0;
+
+/// Drawn under the bullets, above the background.
+const ORB_Z: f32 = 0.5;
+
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
 pub struct PlayerExperience {
     pub value: u32,
     pub level: u32,
@@ -20,22 +36,104 @@ impl Default for PlayerExperience {
         }
     }
 }
+
+impl PlayerExperience {
+    pub fn gain(&mut self, value: u32, stats: &mut PlayerStats) {
+        self.value += value;
+
+        // Level up check
+        if self.value >= self.level * NEXT_LEVEL_RATIO_PERCENT {
+            self.level += 1;
+            self.new_levels += 1;
+            stats.max_health += 10.0;
+        }
+    }
+}
+
+/// Experience dropped by a dead enemy, waiting for the player to come and pick it up
+#[derive(Component, Debug)]
+pub struct ExperienceOrb {
+    pub value: u32,
+    /// Stays at zero until the player comes within pickup radius, then the orb flies to them
+    pub speed: f32,
+}
+
 pub fn handle_enemy_death(
+    mut commands: Commands,
     mut msg_reader: MessageReader<EnemyDeathMessage>,
-    mut player_query: Query<(&mut PlayerExperience, &mut PlayerStats), With<Player>>,
 ) {
     for event in msg_reader.read() {
-        let Ok((experience, stats)) = &mut player_query.single_mut() else {
-            return;
-        };
-        println!("{:?}", event);
+        if event.xp_reward == 0 {
+            continue;
+        }
+        commands.spawn((
+            ExperienceOrb {
+                value: event.xp_reward,
+                speed: 0.0,
+            },
+            Transform::from_translation(event.position.truncate().extend(ORB_Z)),
+            DespawnOnExit(GameState::InWave),
+        ));
+    }
+}
 
-        // Level up check
-        experience.value += event.xp_reward;
-        if experience.value >= experience.level * NEXT_LEVEL_RATIO_PERCENT {
-            experience.level += 1;
-            experience.new_levels += 1;
-            stats.max_health += 10.0;
+/// Orbs within pickup radius fly toward the player and are collected on contact
+#[allow(clippy::type_complexity)]
+pub fn attract_experience_orbs(
+    mut commands: Commands,
+    mut orb_query: Query<(Entity, &mut Transform, &mut ExperienceOrb)>,
+    mut player_query: Query<
+        (&GlobalTransform, &mut PlayerExperience, &mut PlayerStats),
+        (With<Player>, Without<Dying>),
+    >,
+    time: Res<Time>,
+) {
+    let Ok((player_transform, mut experience, mut stats)) = player_query.single_mut() else {
+        return;
+    };
+    let player_pos = player_transform.translation().truncate();
+
+    for (entity, mut transform, mut orb) in &mut orb_query {
+        let to_player = player_pos - transform.translation.truncate();
+        let distance = to_player.length();
+
+        if distance <= ORB_COLLECT_DISTANCE {
+            experience.gain(orb.value, &mut stats);
+            commands.entity(entity).despawn();
+            continue;
+        }
+        if orb.speed == 0.0 {
+            if distance > stats.pickup_radius {
+                continue;
+            }
+            orb.speed = ORB_BASE_SPEED;
         }
+
+        orb.speed += ORB_ACCELERATION * time.delta_secs();
+        let step = (orb.speed * time.delta_secs()).min(distance);
+        transform.translation += (to_player / distance * step).extend(0.0);
+    }
+}
+
+/// Orbs still lying around when the wave timer runs out go straight to the player
+#[allow(clippy::type_complexity)]
+pub fn collect_remaining_orbs(
+    mut commands: Commands,
+    wave_manager: Res<WaveManager>,
+    orb_query: Query<(Entity, &ExperienceOrb)>,
+    mut player_query: Query<
+        (&mut PlayerExperience, &mut PlayerStats),
+        (With<Player>, Without<Dying>),
+    >,
+) {
+    if !wave_manager.wave_timer.just_finished() {
+        return;
+    }
+    let Ok((mut experience, mut stats)) = player_query.single_mut() else {
+        return;
+    };
+    for (entity, orb) in &orb_query {
+        experience.gain(orb.value, &mut stats);
+        commands.entity(entity).despawn();
     }
 }
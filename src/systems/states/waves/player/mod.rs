@@ -1,4 +1,6 @@
+pub mod characters;
 pub mod components;
+pub mod currency;
 pub mod experience;
 pub mod movement;
 pub mod resources;
@@ -1,36 +1,61 @@
 use crate::systems::constants::GAME_AREA;
 use crate::systems::input::resources::ActionState;
 use crate::systems::states::waves::components::{Action, Direction, Dying};
-use crate::systems::states::waves::player::components::Player;
 use crate::systems::states::waves::player::components::PlayerStats;
+use crate::systems::states::waves::player::components::{Dash, Player};
 use crate::systems::states::waves::player::resources::PLAYER_SPEED;
 use bevy::math::Vec2;
 use bevy::prelude::{Query, Res, Time, Transform, With, Without};
 
+#[allow(clippy::type_complexity)]
 pub fn update_position(
     actions: Res<ActionState>,
     mut player_query: Query<
-        (&mut Transform, &PlayerStats, &mut Direction, &mut Action),
+        (
+            &mut Transform,
+            &PlayerStats,
+            &mut Direction,
+            &mut Action,
+            &mut Dash,
+        ),
         (With<Player>, Without<Dying>),
     >,
     time: Res<Time>,
 ) {
-    let Ok((mut transform, stats, mut current_direction, mut action)) = player_query.single_mut()
+    let Ok((mut transform, stats, mut current_direction, mut action, mut dash)) =
+        player_query.single_mut()
     else {
         return;
     };
 
     let direction = actions.movement;
+    dash.tick(time.delta());
+
+    // Dash toward the movement direction, or straight ahead when standing still
+    if actions.dash && dash.is_ready() && !dash.is_dashing() {
+        let dash_direction = if direction != Vec2::ZERO {
+            direction
+        } else {
+            current_direction.vector()
+        };
+        dash.start(dash_direction);
+    }
 
     // Update direction enum for animation system
-    if let Some(new_direction) = get_direction(direction) {
-        if *current_direction != new_direction {
-            *current_direction = new_direction;
-        }
+    if let Some(new_direction) = get_direction(direction)
+        && *current_direction != new_direction
+        && !dash.is_dashing()
+    {
+        *current_direction = new_direction;
     }
 
-    // Apply movement
-    if direction != Vec2::ZERO {
+    // Apply movement, a dash overrides the player's input until it ends
+    if dash.is_dashing() {
+        transform.translation += dash.direction.extend(0.0) * dash.speed() * time.delta_secs();
+        if *action != Action::DASHING {
+            *action = Action::DASHING;
+        }
+    } else if direction != Vec2::ZERO {
         transform.translation +=
             direction.extend(0.0) * PLAYER_SPEED * stats.speed_multiplier * time.delta_secs();
         if *action != Action::WALKING {
@@ -1,25 +1,39 @@
 use crate::systems::animations::animation::SpriteAnimation;
 use crate::systems::animations::animator::count_frames;
+use crate::systems::constants::tiles_to_pixels;
 use crate::systems::states::waves::components::Action::IDLE;
 use crate::systems::states::waves::components::{Action, Direction};
+use crate::systems::states::waves::player::characters::CharacterSprites;
 use bevy::asset::{AssetServer, Assets, Handle};
 use bevy::image::{Image, TextureAtlasLayout};
 use bevy::math::UVec2;
-use bevy::prelude::{FromWorld, Resource, World};
+use bevy::prelude::{Color, FromWorld, Mut, Resource, World};
 use std::collections::HashMap;
 use std::time::Duration;
 
 pub const PLAYER_SPEED: f32 = 200.0;
-const IDLE_SPRITESHEET_PATH: &str = "spritesheet/player/Idle_spritesheet_8x6.png";
-const WALK_SPRITESHEET_PATH: &str = "spritesheet/player/walk_spritesheet_8x6.png";
-const DASH_SPRITESHEET_PATH: &str = "spritesheet/player/dash_spritesheet_8x6.png";
-const DEATH_SPRITESHEET_PATH: &str = "spritesheet/player/death_spritesheet_8x6.png";
-const SHADOW_SPRITE_PATH: &str = "spritesheet/player/shadow_sprite.png";
+pub const DASH_COOLDOWN: f32 = 1.5;
+pub const DASH_DURATION: f32 = 0.2;
+pub const DASH_DISTANCE: f32 = tiles_to_pixels(2.5);
+/// Outlasts the dash itself so the player can't be hit right as it ends
+pub const DASH_INVULNERABILITY: f32 = 0.35;
+pub const BASE_CRIT_CHANCE: f32 = 0.05;
+pub const BASE_CRIT_MULTIPLIER: f32 = 1.5;
+// (action, frame duration in ms, looping)
+const ACTION_CONFIGS: &[(Action, u64, bool)] = &[
+    (Action::IDLE, 120, true),
+    (Action::WALKING, 120, true),
+    (Action::DASHING, 120, false),
+    (Action::DYING, 300, false),
+];
 
+/// Animations of the selected character, rebuilt whenever another character is picked.
 #[derive(Resource)]
 pub struct PlayerAnimations {
     map: HashMap<(Action, Direction), Handle<SpriteAnimation>>,
     pub shadow_texture: Handle<Image>,
+    /// Applied to the player sprite, the animator only swaps images
+    pub tint: Color,
 }
 
 impl PlayerAnimations {
@@ -27,12 +41,16 @@ impl PlayerAnimations {
         self.map.get(&(action, dir)).cloned()
     }
 
-    pub fn get_image_handle(assets: &AssetServer, action: Action) -> Handle<Image> {
+    pub fn get_image_handle(
+        assets: &AssetServer,
+        sprites: &CharacterSprites,
+        action: Action,
+    ) -> Handle<Image> {
         match action {
-            Action::IDLE => assets.load(IDLE_SPRITESHEET_PATH),
-            Action::WALKING => assets.load(WALK_SPRITESHEET_PATH),
-            Action::DASHING => assets.load(DASH_SPRITESHEET_PATH),
-            Action::DYING => assets.load(DEATH_SPRITESHEET_PATH),
+            Action::IDLE => assets.load(&sprites.idle),
+            Action::WALKING => assets.load(&sprites.walk),
+            Action::DASHING => assets.load(&sprites.dash),
+            Action::DYING => assets.load(&sprites.death),
         }
     }
     pub fn get_layout(
@@ -70,23 +88,15 @@ impl PlayerAnimations {
             )),
         }
     }
-}
 
-impl FromWorld for PlayerAnimations {
-    fn from_world(world: &mut World) -> Self {
-        let layout = {
-            Self::get_layout(
-                world.resource_mut::<Assets<TextureAtlasLayout>>().as_mut(),
-                IDLE,
-            )
-        };
-
-        let configs: &[(Action, u64, bool)] = &[
-            (Action::IDLE, 120, true),
-            (Action::WALKING, 120, true),
-            (Action::DASHING, 120, false),
-            (Action::DYING, 300, false),
-        ];
+    pub fn load(
+        asset_server: &AssetServer,
+        layouts: &mut Assets<TextureAtlasLayout>,
+        animations: &mut Assets<SpriteAnimation>,
+        sprites: &CharacterSprites,
+    ) -> Self {
+        let layout = Self::get_layout(layouts, IDLE);
+        let nb_frames = count_frames(layouts, &layout);
 
         let direction_rows: &[(Direction, usize)] = &[
             (Direction::SOUTH, 0),
@@ -100,25 +110,60 @@ impl FromWorld for PlayerAnimations {
         ];
 
         let mut map = HashMap::new();
-        for (action, frame_ms, looping) in configs {
+        for (action, frame_ms, looping) in ACTION_CONFIGS {
+            let image = Self::get_image_handle(asset_server, sprites, *action);
             for (dir, row) in direction_rows {
-                let image = { Self::get_image_handle(world.resource::<AssetServer>(), *action) };
-                let nb_frames =
-                    count_frames(world.resource::<Assets<TextureAtlasLayout>>(), &layout);
                 let anim =
                     SpriteAnimation::from_row(image.clone(), layout.clone(), *row, nb_frames)
                         .with_duration(Duration::from_millis(*frame_ms))
                         .looping(*looping);
-                map.insert(
-                    (*action, *dir),
-                    world.resource_mut::<Assets<SpriteAnimation>>().add(anim),
-                );
+                map.insert((*action, *dir), animations.add(anim));
             }
         }
 
         PlayerAnimations {
             map,
-            shadow_texture: world.resource::<AssetServer>().load(SHADOW_SPRITE_PATH),
+            shadow_texture: asset_server.load(&sprites.shadow),
+            tint: sprites.tint(),
         }
     }
+
+    /// Asset-free stand-in for headless runs: every clip points at default handles so the
+    /// animator still ticks (and emits `AnimationEnded`) without any image on disk.
+    pub fn placeholder(animations: &mut Assets<SpriteAnimation>) -> Self {
+        const PLACEHOLDER_FRAMES: usize = 8;
+        let mut map = HashMap::new();
+        for (action, frame_ms, looping) in ACTION_CONFIGS {
+            for dir in Direction::iterator() {
+                let anim = SpriteAnimation::from_row(
+                    Handle::default(),
+                    Handle::default(),
+                    0,
+                    PLACEHOLDER_FRAMES,
+                )
+                .with_duration(Duration::from_millis(*frame_ms))
+                .looping(*looping);
+                map.insert((*action, dir), animations.add(anim));
+            }
+        }
+        PlayerAnimations {
+            map,
+            shadow_texture: Handle::default(),
+            tint: Color::WHITE,
+        }
+    }
+}
+
+impl FromWorld for PlayerAnimations {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>().clone();
+        world.resource_scope(|world, mut layouts: Mut<Assets<TextureAtlasLayout>>| {
+            Self::load(
+                &asset_server,
+                &mut layouts,
+                &mut world.resource_mut::<Assets<SpriteAnimation>>(),
+                &CharacterSprites::default(),
+            )
+        })
+    }
 }
@@ -1,21 +1,46 @@
 use crate::systems::animations::animator::SpriteAnimator;
 use crate::systems::constants::{tiles_to_pixels, TILES_X, TILES_Y};
+use crate::systems::game::GameRng;
 use crate::systems::states::waves::components::{
     Action, Direction, Dying, LevelBackground, LevelOverlay,
 };
 use crate::systems::states::waves::enemy::components::Enemy;
 use crate::systems::states::waves::enemy::resources::EnemyAnimations;
+use crate::systems::states::waves::obstacles::{InBush, Obstacle, PROPS};
 use crate::systems::states::waves::player::components::Player;
+use crate::systems::states::waves::player::experience::ExperienceOrb;
 use crate::systems::states::waves::player::resources::PlayerAnimations;
-use crate::systems::states::waves::resources::TilesTextureAtlas;
+use crate::systems::states::waves::resources::{ObstacleSprites, TilesTextureAtlas};
+use crate::systems::states::waves::status::StatusEffects;
 use bevy::camera::Camera2d;
 use bevy::ecs::relationship::RelationshipSourceCollection;
 use bevy::image::TextureAtlas;
 use bevy::prelude::*;
+use bevy::sprite::Anchor;
 use rand::RngExt;
 
-pub fn spawn_background(mut commands: Commands, atlas: Res<TilesTextureAtlas>) {
-    let mut rng = rand::rng();
+const ORB_COLOR: Color = Color::srgb(0.80, 0.60, 1.00);
+const ORB_SIZE: f32 = 8.0;
+
+pub fn render_experience_orbs(
+    mut commands: Commands,
+    orb_query: Query<(Entity, &ExperienceOrb), Added<ExperienceOrb>>,
+) {
+    for (entity, orb) in &orb_query {
+        // Bigger rewards make bigger orbs
+        let size = ORB_SIZE + (orb.value as f32).sqrt();
+        commands
+            .entity(entity)
+            .insert(Sprite::from_color(ORB_COLOR, Vec2::splat(size)));
+    }
+}
+
+pub fn spawn_background(
+    mut commands: Commands,
+    atlas: Res<TilesTextureAtlas>,
+    mut rng: ResMut<GameRng>,
+) {
+    let rng = rng.presentation();
     for x in 0..TILES_X + 1 {
         for y in 0..TILES_Y + 1 {
             let pos_x = tiles_to_pixels(x as f32 - TILES_X as f32 / 2.0);
@@ -66,7 +91,7 @@ pub fn animate_game_over(
         camera_transform.translation.y += 1.;
     }
 
-    for mut bg in background.iter_mut() {
+    if let Some(mut bg) = background.iter_mut().next() {
         let alpha = bg.0.alpha();
         if alpha < DYING_OVERLAY_TARGET_ALPHA {
             bg.0.set_alpha(
@@ -74,10 +99,10 @@ pub fn animate_game_over(
                     .min(DYING_OVERLAY_TARGET_ALPHA),
             );
         }
-        return;
     }
 }
 
+#[allow(clippy::type_complexity)]
 pub fn animate_player(
     player_anims: Res<PlayerAnimations>,
     mut query: Query<
@@ -102,3 +127,45 @@ pub fn animate_enemy(
         }
     }
 }
+
+/// Alpha of an enemy hiding in a bush
+const IN_BUSH_ALPHA: f32 = 0.3;
+
+/// Tints enemies with the color of their strongest status effect, fading out the hidden ones.
+pub fn tint_enemies(mut query: Query<(&StatusEffects, Has<InBush>, &mut Sprite), With<Enemy>>) {
+    for (effects, hidden, mut sprite) in &mut query {
+        let mut tint = effects.tint();
+        if hidden {
+            tint.set_alpha(IN_BUSH_ALPHA);
+        }
+        if sprite.color != tint {
+            sprite.color = tint;
+        }
+    }
+}
+
+pub fn render_obstacles(
+    mut commands: Commands,
+    sprites: Res<ObstacleSprites>,
+    query: Query<(Entity, &Obstacle), Added<Obstacle>>,
+) {
+    for (entity, obstacle) in &query {
+        let prop = &PROPS[obstacle.prop];
+        let anchor = Anchor(Vec2::new(0.0, prop.anchor_y));
+        match &sprites.animations[obstacle.prop] {
+            Some(animation) => {
+                commands.entity(entity).insert((
+                    Sprite::default(),
+                    SpriteAnimator::new(animation.clone()),
+                    anchor,
+                ));
+            }
+            None => {
+                commands.entity(entity).insert((
+                    Sprite::from_image(sprites.images[obstacle.prop].clone()),
+                    anchor,
+                ));
+            }
+        }
+    }
+}
@@ -1,22 +1,33 @@
-use crate::systems::constants::{SPAWN_RATE, TILE_SIZE, WAVE_DURATION};
+use crate::systems::animations::animation::SpriteAnimation;
+use crate::systems::constants::{TILE_SIZE, WAVE_DURATION};
+use crate::systems::states::waves::enemy::kinds::EnemyKind;
+use crate::systems::states::waves::obstacles::PROPS;
 use bevy::asset::{AssetServer, Assets, Handle};
 use bevy::image::{Image, TextureAtlasLayout};
 use bevy::math::UVec2;
 use bevy::prelude::{FromWorld, Resource, Timer, TimerMode, World};
+use std::time::Duration;
 
 #[derive(Resource)]
 pub struct WaveManager {
     pub wave: u32,
     pub wave_timer: Timer,
-    pub enemy_spawn_timer: Timer,
+    /// Spawn points of the `WaveScript` not spent yet
+    pub spawn_budget: f32,
+    /// Enemy rolled but not affordable yet, with its cost
+    pub next_spawn: Option<(EnemyKind, f32)>,
+    /// Index of the next `WaveEvent` to fire
+    pub next_event: usize,
 }
 
 impl Default for WaveManager {
     fn default() -> Self {
         Self {
-            wave: 3,
+            wave: 1,
             wave_timer: Timer::from_seconds(WAVE_DURATION, TimerMode::Once),
-            enemy_spawn_timer: Timer::from_seconds(SPAWN_RATE, TimerMode::Repeating),
+            spawn_budget: 0.0,
+            next_spawn: None,
+            next_event: 0,
         }
     }
 }
@@ -27,6 +38,16 @@ pub struct TilesTextureAtlas {
     pub layout: Handle<TextureAtlasLayout>,
 }
 
+impl TilesTextureAtlas {
+    /// Asset-free stand-in for headless runs.
+    pub fn placeholder() -> Self {
+        TilesTextureAtlas {
+            texture: Handle::default(),
+            layout: Handle::default(),
+        }
+    }
+}
+
 impl FromWorld for TilesTextureAtlas {
     fn from_world(world: &mut World) -> Self {
         let texture = { world.resource::<AssetServer>() }.load("spritesheet/spritesheet_tiles.png");
@@ -41,3 +62,57 @@ impl FromWorld for TilesTextureAtlas {
         TilesTextureAtlas { texture, layout }
     }
 }
+
+/// Frame interval of the swaying trees and bushes
+const PROP_FRAME_MS: u64 = 150;
+
+/// Image of every obstacle prop, in `PROPS` order. Animated props also get a looping clip.
+#[derive(Resource)]
+pub struct ObstacleSprites {
+    pub images: Vec<Handle<Image>>,
+    pub animations: Vec<Option<Handle<SpriteAnimation>>>,
+}
+
+impl FromWorld for ObstacleSprites {
+    fn from_world(world: &mut World) -> Self {
+        let images: Vec<Handle<Image>> = {
+            let asset_server = world.resource::<AssetServer>();
+            PROPS
+                .iter()
+                .map(|prop| asset_server.load(prop.sprite))
+                .collect()
+        };
+        let layouts: Vec<Option<Handle<TextureAtlasLayout>>> = {
+            let mut layouts = world.resource_mut::<Assets<TextureAtlasLayout>>();
+            PROPS
+                .iter()
+                .map(|prop| {
+                    (prop.frames > 1).then(|| {
+                        layouts.add(TextureAtlasLayout::from_grid(
+                            prop.frame_size,
+                            prop.frames,
+                            1,
+                            None,
+                            None,
+                        ))
+                    })
+                })
+                .collect()
+        };
+        let mut animations = world.resource_mut::<Assets<SpriteAnimation>>();
+        let animations = PROPS
+            .iter()
+            .zip(images.iter().zip(layouts))
+            .map(|(prop, (image, layout))| {
+                layout.map(|layout| {
+                    animations.add(
+                        SpriteAnimation::from_row(image.clone(), layout, 0, prop.frames as usize)
+                            .with_duration(Duration::from_millis(PROP_FRAME_MS))
+                            .looping(true),
+                    )
+                })
+            })
+            .collect();
+        ObstacleSprites { images, animations }
+    }
+}
@@ -0,0 +1,294 @@
+use crate::systems::constants::{SPAWN_RATE, WAVE_DURATION};
+use crate::systems::game::GameRng;
+use crate::systems::states::waves::enemy::kinds::EnemyKind;
+use crate::systems::states::waves::enemy::resources::EnemyRegistry;
+use bevy::asset::Asset;
+use bevy::prelude::*;
+use bevy::reflect::TypePath;
+use rand::distr::weighted::WeightedIndex;
+use rand::distr::Distribution;
+use serde::Deserialize;
+
+/// Extra spawn budget for every time the scripted waves loop, +50% per lap
+const LOOP_BUDGET_GROWTH: f32 = 0.5;
+
+/// Content and pacing of one wave, loaded from `assets/waves/<name>.wave.ron`.
+#[derive(Asset, TypePath, Deserialize, Debug, Clone)]
+pub struct WaveScript {
+    /// First wave using this script, it stays in use until the next scripted wave
+    pub wave: u32,
+    /// Seconds
+    pub duration: f32,
+    /// Keyframes of (fraction 0..1 of the wave elapsed, spawn points earned per second),
+    /// linearly interpolated. Regular enemies are bought with these points.
+    pub spawn_budget: Vec<(f32, f32)>,
+    /// Regular spawn pool, empty falls back to the `SpawnRule` of every enemy definition.
+    /// Entries without a positive weight and cost are dropped on load.
+    #[serde(default)]
+    pub enemies: Vec<WaveEnemy>,
+    #[serde(default)]
+    pub events: Vec<WaveEvent>,
+    #[serde(default)]
+    pub rewards: WaveRewards,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct WaveEnemy {
+    /// `EnemyDefinition` id
+    pub id: String,
+    pub weight: f32,
+    /// Spawn points spent on one of them, must be positive
+    #[serde(default = "default_cost")]
+    pub cost: f32,
+}
+
+fn default_cost() -> f32 {
+    1.0
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct WaveEvent {
+    /// Seconds since the start of the wave
+    pub at: f32,
+    pub action: WaveAction,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub enum WaveAction {
+    /// A pack of `count` enemies arriving together from one spot, on top of the budget
+    Horde { enemy: String, count: u32 },
+    /// Tougher, more rewarding versions of a regular enemy
+    Elite { enemy: String, count: u32 },
+    /// Appears right away, without the spawn warning
+    Boss { enemy: String },
+}
+
+/// Paid to the player when the wave timer runs out.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct WaveRewards {
+    pub currency: u32,
+    pub experience: u32,
+}
+
+impl Default for WaveScript {
+    /// The wave used when no script is loaded at all: a steady trickle of the default pool
+    fn default() -> Self {
+        Self {
+            wave: 1,
+            duration: WAVE_DURATION,
+            spawn_budget: vec![(0.0, 1.0 / SPAWN_RATE)],
+            enemies: Vec::new(),
+            events: Vec::new(),
+            rewards: WaveRewards::default(),
+        }
+    }
+}
+
+impl WaveScript {
+    /// Spawn points earned per second once `progress` (0..1) of the wave has elapsed
+    pub fn budget_at(&self, progress: f32) -> f32 {
+        let Some(first) = self.spawn_budget.first() else {
+            return 0.0;
+        };
+        if progress <= first.0 {
+            return first.1;
+        }
+        for pair in self.spawn_budget.windows(2) {
+            let ((from, start), (to, end)) = (pair[0], pair[1]);
+            if progress <= to {
+                let t = if to > from {
+                    (progress - from) / (to - from)
+                } else {
+                    1.0
+                };
+                return start + (end - start) * t;
+            }
+        }
+        self.spawn_budget.last().map_or(0.0, |(_, rate)| *rate)
+    }
+
+    /// Rolls the next regular enemy and its cost
+    pub fn pick_enemy(
+        &self,
+        registry: &EnemyRegistry,
+        wave: u32,
+        rng: &mut GameRng,
+    ) -> Option<(EnemyKind, f32)> {
+        if self.enemies.is_empty() {
+            return registry
+                .random_for_wave(wave, rng)
+                .map(|kind| (kind, default_cost()));
+        }
+        let pool: Vec<(EnemyKind, &WaveEnemy)> = self
+            .enemies
+            .iter()
+            .filter_map(|entry| Some((registry.find(&entry.id)?, entry)))
+            .collect();
+        let weights = WeightedIndex::new(pool.iter().map(|(_, entry)| entry.weight)).ok()?;
+        let (kind, entry) = pool[weights.sample(&mut **rng)];
+        Some((kind, entry.cost))
+    }
+}
+
+/// Every loaded `WaveScript`, by wave.
+#[derive(Resource, Default)]
+pub struct WaveScripts {
+    scripts: Vec<WaveScript>,
+    fallback: WaveScript,
+}
+
+impl WaveScripts {
+    pub fn new(mut scripts: Vec<WaveScript>, registry: &EnemyRegistry) -> Self {
+        scripts.sort_by_key(|script| script.wave);
+        for script in &mut scripts {
+            script.events.sort_by(|a, b| a.at.total_cmp(&b.at));
+            // A free enemy would be bought forever with no budget at all
+            script.enemies.retain(|entry| {
+                let valid = entry.weight > 0.0 && entry.cost > 0.0;
+                if !valid {
+                    warn!(
+                        "Wave {} drops '{}': weight and cost must be positive",
+                        script.wave, entry.id
+                    );
+                }
+                valid
+            });
+            let pool = script.enemies.iter().map(|entry| &entry.id);
+            let events = script.events.iter().map(|event| match &event.action {
+                WaveAction::Horde { enemy, .. }
+                | WaveAction::Elite { enemy, .. }
+                | WaveAction::Boss { enemy } => enemy,
+            });
+            for id in pool.chain(events) {
+                if registry.find(id).is_none() {
+                    warn!("Wave {} uses unknown enemy '{}'", script.wave, id);
+                }
+            }
+        }
+        Self {
+            scripts,
+            fallback: WaveScript::default(),
+        }
+    }
+
+    /// Script of `wave` and the factor applied to its spawn budget.
+    /// Past the last scripted wave the scripts loop, a little more crowded on every lap.
+    pub fn for_wave(&self, wave: u32) -> (&WaveScript, f32) {
+        let Some(last) = self.scripts.last().map(|script| script.wave.max(1)) else {
+            return (&self.fallback, 1.0);
+        };
+        let wave = wave.max(1);
+        let laps = (wave - 1) / last;
+        let looped = (wave - 1) % last + 1;
+        let script = self
+            .scripts
+            .iter()
+            .rev()
+            .find(|script| script.wave <= looped)
+            .unwrap_or(&self.scripts[0]);
+        (script, 1.0 + laps as f32 * LOOP_BUDGET_GROWTH)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn script(wave: u32, spawn_budget: Vec<(f32, f32)>) -> WaveScript {
+        WaveScript {
+            wave,
+            spawn_budget,
+            ..default()
+        }
+    }
+
+    fn enemy(id: &str, weight: f32, cost: f32) -> WaveEnemy {
+        WaveEnemy {
+            id: id.to_string(),
+            weight,
+            cost,
+        }
+    }
+
+    #[test]
+    fn budget_interpolates_between_keyframes() {
+        let script = script(1, vec![(0.0, 1.0), (0.5, 3.0), (1.0, 2.0)]);
+        assert_eq!(script.budget_at(0.0), 1.0);
+        assert_eq!(script.budget_at(0.25), 2.0);
+        assert_eq!(script.budget_at(0.5), 3.0);
+        assert_eq!(script.budget_at(0.75), 2.5);
+        assert_eq!(script.budget_at(1.0), 2.0);
+    }
+
+    #[test]
+    fn budget_holds_the_first_and_last_keyframes() {
+        let script = script(1, vec![(0.2, 1.0), (0.8, 4.0)]);
+        assert_eq!(script.budget_at(0.0), 1.0);
+        assert_eq!(script.budget_at(0.9), 4.0);
+        assert_eq!(script.budget_at(2.0), 4.0);
+    }
+
+    #[test]
+    fn duplicate_keyframes_step_the_budget() {
+        let script = script(1, vec![(0.0, 1.0), (0.5, 1.0), (0.5, 5.0), (1.0, 5.0)]);
+        assert_eq!(script.budget_at(0.5), 1.0);
+        assert_eq!(script.budget_at(0.51), 5.0);
+    }
+
+    #[test]
+    fn no_keyframe_means_no_budget() {
+        assert_eq!(script(1, Vec::new()).budget_at(0.5), 0.0);
+    }
+
+    #[test]
+    fn scripts_loop_with_a_growing_budget() {
+        let registry = EnemyRegistry::new(Vec::new());
+        let scripts = WaveScripts::new(
+            vec![
+                script(5, Vec::new()),
+                script(1, Vec::new()),
+                script(3, Vec::new()),
+            ],
+            &registry,
+        );
+        let wave_of = |wave| {
+            let (script, scale) = scripts.for_wave(wave);
+            (script.wave, scale)
+        };
+        assert_eq!(wave_of(1), (1, 1.0));
+        assert_eq!(wave_of(2), (1, 1.0));
+        assert_eq!(wave_of(4), (3, 1.0));
+        assert_eq!(wave_of(5), (5, 1.0));
+        assert_eq!(wave_of(6), (1, 1.5));
+        assert_eq!(wave_of(13), (3, 2.0));
+    }
+
+    #[test]
+    fn no_script_falls_back_to_the_default_wave() {
+        let scripts = WaveScripts::new(Vec::new(), &EnemyRegistry::new(Vec::new()));
+        let (script, scale) = scripts.for_wave(7);
+        assert_eq!(script.duration, WAVE_DURATION);
+        assert_eq!(scale, 1.0);
+    }
+
+    #[test]
+    fn entries_without_positive_weight_and_cost_are_dropped() {
+        let mut wave = script(1, Vec::new());
+        wave.enemies = vec![
+            enemy("free", 1.0, 0.0),
+            enemy("refund", 1.0, -2.0),
+            enemy("never", 0.0, 1.0),
+            enemy("regular", 2.0, 1.5),
+        ];
+        let scripts = WaveScripts::new(vec![wave], &EnemyRegistry::new(Vec::new()));
+        let ids: Vec<&str> = scripts
+            .for_wave(1)
+            .0
+            .enemies
+            .iter()
+            .map(|e| e.id.as_str())
+            .collect();
+        assert_eq!(ids, ["regular"]);
+    }
+}
@@ -0,0 +1,259 @@
+use crate::systems::constants::{tiles_to_pixels, GAME_AREA};
+use crate::systems::states::waves::enemy::components::Enemy;
+use bevy::prelude::*;
+
+/// Side of a grid cell, roughly the diameter of the biggest regular enemy.
+pub const SPATIAL_CELL_SIZE: f32 = tiles_to_pixels(1.0);
+
+/// Uniform grid over `GAME_AREA` bucketing every enemy by position, rebuilt each frame.
+/// Positions outside the area are clamped into the border cells, so nothing is ever lost.
+#[derive(Resource)]
+pub struct SpatialGrid {
+    origin: Vec2,
+    cell_size: f32,
+    cols: i32,
+    rows: i32,
+    cells: Vec<Vec<(Entity, Vec2)>>,
+}
+
+impl Default for SpatialGrid {
+    fn default() -> Self {
+        Self::new(GAME_AREA, SPATIAL_CELL_SIZE)
+    }
+}
+
+impl SpatialGrid {
+    pub fn new(area: Rect, cell_size: f32) -> Self {
+        let cols = (area.width() / cell_size).ceil().max(1.0) as i32;
+        let rows = (area.height() / cell_size).ceil().max(1.0) as i32;
+        Self {
+            origin: area.min,
+            cell_size,
+            cols,
+            rows,
+            cells: vec![Vec::new(); (cols * rows) as usize],
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.iter_mut().for_each(Vec::clear);
+    }
+
+    pub fn insert(&mut self, entity: Entity, position: Vec2) {
+        let (x, y) = self.cell_coords(position);
+        let index = self.cell_index(x, y);
+        self.cells[index].push((entity, position));
+    }
+
+    /// Every entry within `radius` of `center`
+    pub fn query_radius(
+        &self,
+        center: Vec2,
+        radius: f32,
+    ) -> impl Iterator<Item = (Entity, Vec2)> + '_ {
+        let (min_x, min_y) = self.cell_coords(center - Vec2::splat(radius));
+        let (max_x, max_y) = self.cell_coords(center + Vec2::splat(radius));
+        let radius_sq = radius * radius;
+        (min_y..=max_y)
+            .flat_map(move |y| (min_x..=max_x).map(move |x| (x, y)))
+            .flat_map(move |(x, y)| self.cells[self.cell_index(x, y)].iter().copied())
+            .filter(move |(_, position)| position.distance_squared(center) <= radius_sq)
+    }
+
+    /// Closest entry to `center` no further than `max_range`
+    pub fn nearest(&self, center: Vec2, max_range: f32) -> Option<(Entity, Vec2)> {
+        self.k_nearest(center, 1, max_range).into_iter().next()
+    }
+
+    /// Up to `k` entries closest to `center` no further than `max_range`, nearest first.
+    /// Scans rings of cells outwards and stops as soon as farther rings can't do better.
+    pub fn k_nearest(&self, center: Vec2, k: usize, max_range: f32) -> Vec<(Entity, Vec2)> {
+        if k == 0 {
+            return Vec::new();
+        }
+        let mut found: Vec<(f32, Entity, Vec2)> = Vec::new();
+        let (cx, cy) = self.cell_coords(center);
+        let max_range_sq = max_range * max_range;
+        let max_ring =
+            ((max_range / self.cell_size).ceil() as i32 + 1).min(self.cols.max(self.rows));
+
+        for ring in 0..=max_ring {
+            for (x, y) in ring_cells(cx, cy, ring) {
+                if x < 0 || y < 0 || x >= self.cols || y >= self.rows {
+                    continue;
+                }
+                for &(entity, position) in &self.cells[self.cell_index(x, y)] {
+                    let distance_sq = position.distance_squared(center);
+                    if distance_sq <= max_range_sq {
+                        found.push((distance_sq, entity, position));
+                    }
+                }
+            }
+            // Anything in the next ring is at least `ring * cell_size` away
+            let reach = ring as f32 * self.cell_size;
+            let settled = found.iter().filter(|(d, _, _)| *d <= reach * reach).count();
+            if settled >= k {
+                break;
+            }
+        }
+
+        found.sort_by(|a, b| a.0.total_cmp(&b.0));
+        found
+            .into_iter()
+            .take(k)
+            .map(|(_, entity, position)| (entity, position))
+            .collect()
+    }
+
+    fn cell_coords(&self, position: Vec2) -> (i32, i32) {
+        let local = (position - self.origin) / self.cell_size;
+        (
+            (local.x.floor() as i32).clamp(0, self.cols - 1),
+            (local.y.floor() as i32).clamp(0, self.rows - 1),
+        )
+    }
+
+    fn cell_index(&self, x: i32, y: i32) -> usize {
+        (y * self.cols + x) as usize
+    }
+}
+
+/// Cells at Chebyshev distance `ring` from `(cx, cy)`
+fn ring_cells(cx: i32, cy: i32, ring: i32) -> impl Iterator<Item = (i32, i32)> {
+    (-ring..=ring).flat_map(move |dy| {
+        let step = if dy.abs() == ring {
+            1
+        } else {
+            (2 * ring).max(1)
+        };
+        (-ring..=ring)
+            .step_by(step as usize)
+            .map(move |dx| (cx + dx, cy + dy))
+    })
+}
+
+pub fn rebuild_spatial_grid(
+    mut grid: ResMut<SpatialGrid>,
+    enemy_query: Query<(Entity, &GlobalTransform), With<Enemy>>,
+) {
+    grid.clear();
+    for (entity, transform) in &enemy_query {
+        grid.insert(entity, transform.translation().truncate());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AREA: Rect = Rect {
+        min: Vec2::new(-100.0, -100.0),
+        max: Vec2::new(100.0, 100.0),
+    };
+    const CELL: f32 = 10.0;
+
+    fn entity(index: u32) -> Entity {
+        Entity::from_raw_u32(index + 1).unwrap()
+    }
+
+    /// A lattice of points straddling many cell borders, plus a few outside the area
+    fn populated_grid() -> (SpatialGrid, Vec<(Entity, Vec2)>) {
+        let mut points = Vec::new();
+        for y in -12..=12 {
+            for x in -12..=12 {
+                points.push(Vec2::new(x as f32 * 7.5, y as f32 * 7.5 + 0.25));
+            }
+        }
+        points.extend([Vec2::new(130.0, 0.0), Vec2::new(-100.0, -140.0)]);
+        let entries: Vec<(Entity, Vec2)> = points
+            .into_iter()
+            .enumerate()
+            .map(|(i, position)| (entity(i as u32), position))
+            .collect();
+        let mut grid = SpatialGrid::new(AREA, CELL);
+        for &(entity, position) in &entries {
+            grid.insert(entity, position);
+        }
+        (grid, entries)
+    }
+
+    fn sorted(mut entities: Vec<Entity>) -> Vec<Entity> {
+        entities.sort();
+        entities
+    }
+
+    #[test]
+    fn query_radius_matches_brute_force_across_cell_borders() {
+        let (grid, entries) = populated_grid();
+        let centers = [
+            Vec2::ZERO,
+            Vec2::new(10.0, 10.0),
+            Vec2::new(-0.01, 19.99),
+            Vec2::new(95.0, -95.0),
+            Vec2::new(120.0, 0.0),
+        ];
+        for center in centers {
+            for radius in [0.5, 4.0, 10.0, 23.0] {
+                let expected = entries
+                    .iter()
+                    .filter(|(_, position)| position.distance(center) <= radius)
+                    .map(|(entity, _)| *entity)
+                    .collect();
+                let found = grid
+                    .query_radius(center, radius)
+                    .map(|(entity, _)| entity)
+                    .collect();
+                assert_eq!(sorted(found), sorted(expected), "{center} r={radius}");
+            }
+        }
+    }
+
+    #[test]
+    fn k_nearest_returns_the_k_closest_in_order() {
+        let (grid, entries) = populated_grid();
+        for center in [
+            Vec2::new(3.0, -4.0),
+            Vec2::new(-99.0, 99.0),
+            Vec2::new(55.0, 9.99),
+        ] {
+            for k in [1, 2, 5, 12] {
+                let mut by_distance: Vec<(f32, Entity)> = entries
+                    .iter()
+                    .map(|(entity, position)| (position.distance(center), *entity))
+                    .collect();
+                by_distance.sort_by(|a, b| a.0.total_cmp(&b.0));
+                let found = grid.k_nearest(center, k, 1_000.0);
+
+                assert_eq!(found.len(), k);
+                let distances: Vec<f32> = found.iter().map(|(_, p)| p.distance(center)).collect();
+                assert!(distances.windows(2).all(|pair| pair[0] <= pair[1]));
+                // Ties may come in any order, the distances must match
+                let expected: Vec<f32> = by_distance.iter().take(k).map(|(d, _)| *d).collect();
+                assert_eq!(distances, expected, "{center} k={k}");
+            }
+        }
+    }
+
+    #[test]
+    fn k_nearest_respects_max_range() {
+        let (grid, entries) = populated_grid();
+        let center = Vec2::new(3.0, 3.0);
+        let within = entries
+            .iter()
+            .filter(|(_, position)| position.distance(center) <= 8.0)
+            .count();
+        let found = grid.k_nearest(center, 50, 8.0);
+        assert_eq!(found.len(), within);
+        assert!(found.iter().all(|(_, p)| p.distance(center) <= 8.0));
+        assert!(grid.k_nearest(center, 0, 8.0).is_empty());
+    }
+
+    #[test]
+    fn positions_outside_the_area_are_kept_in_border_cells() {
+        let (grid, _) = populated_grid();
+        let (_, position) = grid.nearest(Vec2::new(140.0, 0.0), 20.0).unwrap();
+        assert_eq!(position, Vec2::new(130.0, 0.0));
+        let (_, position) = grid.nearest(Vec2::new(-100.0, -150.0), 15.0).unwrap();
+        assert_eq!(position, Vec2::new(-100.0, -140.0));
+    }
+}
@@ -0,0 +1,329 @@
+use crate::systems::states::waves::components::Health;
+use crate::systems::states::waves::enemy::components::Enemy;
+use crate::systems::states::waves::enemy::elites::{damage_enemy, Shield};
+use crate::systems::states::waves::weapons::messages::DamageDealtMessage;
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::time::Duration;
+
+/// Seconds between two damage-over-time ticks
+const DOT_TICK: f32 = 0.5;
+/// Poison is the only effect stacking, every stack ticking on its own
+const MAX_POISON_STACKS: usize = 5;
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusEffectKind {
+    /// Damage over time, does not stack
+    Burn,
+    /// Damage over time, stacks up to `MAX_POISON_STACKS`
+    Poison,
+    /// Reduces the movement speed
+    Slow,
+    /// Stops movement and attacks entirely
+    Freeze,
+}
+
+impl StatusEffectKind {
+    /// Color multiplied with the sprite of an affected enemy
+    pub fn tint(&self) -> Color {
+        match self {
+            StatusEffectKind::Burn => Color::srgb(1.0, 0.55, 0.3),
+            StatusEffectKind::Poison => Color::srgb(0.55, 1.0, 0.4),
+            StatusEffectKind::Slow => Color::srgb(0.6, 0.75, 1.0),
+            StatusEffectKind::Freeze => Color::srgb(0.45, 0.9, 1.0),
+        }
+    }
+
+    /// When several effects are active, the tint of the highest priority one is shown
+    fn priority(&self) -> u8 {
+        match self {
+            StatusEffectKind::Freeze => 3,
+            StatusEffectKind::Burn => 2,
+            StatusEffectKind::Poison => 1,
+            StatusEffectKind::Slow => 0,
+        }
+    }
+}
+
+/// Effect applied by a weapon on hit, as written in its `WeaponDefinition`.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct StatusEffect {
+    pub kind: StatusEffectKind,
+    /// Seconds
+    pub duration: f32,
+    /// Damage per second for burn and poison, fraction (0..1) of the speed removed by slow,
+    /// unused by freeze
+    #[serde(default)]
+    pub potency: f32,
+    /// Odds (0..1) of each hit applying the effect
+    #[serde(default = "default_chance")]
+    pub chance: f32,
+}
+
+fn default_chance() -> f32 {
+    1.0
+}
+
+#[derive(Debug, Clone)]
+struct ActiveStatusEffect {
+    kind: StatusEffectKind,
+    potency: f32,
+    timer: Timer,
+}
+
+/// Effects currently affecting an enemy.
+#[derive(Component, Debug, Clone)]
+pub struct StatusEffects {
+    effects: Vec<ActiveStatusEffect>,
+    dot_timer: Timer,
+}
+
+impl Default for StatusEffects {
+    fn default() -> Self {
+        Self {
+            effects: Vec::new(),
+            dot_timer: Timer::from_seconds(DOT_TICK, TimerMode::Repeating),
+        }
+    }
+}
+
+impl StatusEffects {
+    /// Burn, slow and freeze keep a single instance: reapplying one refreshes its duration and
+    /// keeps the strongest potency. Poison adds a stack, replacing the one closest to running
+    /// out once `MAX_POISON_STACKS` is reached.
+    pub fn apply(&mut self, effect: &StatusEffect) {
+        if self.effects.is_empty() {
+            self.dot_timer.reset();
+        }
+        let fresh = ActiveStatusEffect {
+            kind: effect.kind,
+            potency: effect.potency,
+            timer: Timer::from_seconds(effect.duration, TimerMode::Once),
+        };
+
+        if effect.kind == StatusEffectKind::Poison {
+            let stacks = self.stacks(StatusEffectKind::Poison);
+            if stacks < MAX_POISON_STACKS {
+                self.effects.push(fresh);
+            } else if let Some(oldest) = self
+                .effects
+                .iter_mut()
+                .filter(|active| active.kind == StatusEffectKind::Poison)
+                .min_by(|a, b| a.timer.remaining().cmp(&b.timer.remaining()))
+            {
+                *oldest = fresh;
+            }
+            return;
+        }
+
+        match self
+            .effects
+            .iter_mut()
+            .find(|active| active.kind == effect.kind)
+        {
+            Some(active) => {
+                active.potency = active.potency.max(effect.potency);
+                if effect.duration > active.timer.remaining_secs() {
+                    active.timer = fresh.timer;
+                }
+            }
+            None => self.effects.push(fresh),
+        }
+    }
+
+    /// Advances every effect, returns the damage-over-time dealt during `delta`.
+    pub fn tick(&mut self, delta: Duration) -> f32 {
+        if self.effects.is_empty() {
+            return 0.0;
+        }
+        self.dot_timer.tick(delta);
+        let ticks = self.dot_timer.times_finished_this_tick() as f32;
+        let damage = ticks * DOT_TICK * self.damage_per_second();
+
+        for active in &mut self.effects {
+            active.timer.tick(delta);
+        }
+        self.effects.retain(|active| !active.timer.is_finished());
+        damage
+    }
+
+    pub fn damage_per_second(&self) -> f32 {
+        self.effects
+            .iter()
+            .filter(|active| {
+                matches!(
+                    active.kind,
+                    StatusEffectKind::Burn | StatusEffectKind::Poison
+                )
+            })
+            .map(|active| active.potency)
+            .sum()
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.stacks(StatusEffectKind::Freeze) > 0
+    }
+
+    /// Factor applied to the movement speed: 0 while frozen, otherwise the strongest slow
+    pub fn speed_multiplier(&self) -> f32 {
+        if self.is_frozen() {
+            return 0.0;
+        }
+        let slow = self
+            .effects
+            .iter()
+            .filter(|active| active.kind == StatusEffectKind::Slow)
+            .map(|active| active.potency)
+            .fold(0.0, f32::max);
+        (1.0 - slow).clamp(0.0, 1.0)
+    }
+
+    /// Tint of the highest priority active effect, white when there is none
+    pub fn tint(&self) -> Color {
+        self.effects
+            .iter()
+            .max_by_key(|active| active.kind.priority())
+            .map_or(Color::WHITE, |active| active.kind.tint())
+    }
+
+    /// Kind of the damage-over-time effect dealing the most damage
+    fn strongest_dot(&self) -> Option<StatusEffectKind> {
+        self.effects
+            .iter()
+            .filter(|active| {
+                matches!(
+                    active.kind,
+                    StatusEffectKind::Burn | StatusEffectKind::Poison
+                )
+            })
+            .max_by(|a, b| a.potency.total_cmp(&b.potency))
+            .map(|active| active.kind)
+    }
+
+    fn stacks(&self, kind: StatusEffectKind) -> usize {
+        self.effects
+            .iter()
+            .filter(|active| active.kind == kind)
+            .count()
+    }
+}
+
+/// Ticks the effects of every enemy and deals their damage over time.
+pub fn update_status_effects(
+    mut query: Query<
+        (
+            &GlobalTransform,
+            &mut StatusEffects,
+            &mut Health,
+            Option<&mut Shield>,
+        ),
+        With<Enemy>,
+    >,
+    mut damage_events: MessageWriter<DamageDealtMessage>,
+    time: Res<Time>,
+) {
+    for (transform, mut effects, mut health, mut shield) in &mut query {
+        let source = effects.strongest_dot();
+        let damage = effects.tick(time.delta());
+        if damage <= 0.0 || health.value <= 0.0 {
+            continue;
+        }
+        damage_enemy(&mut health, shield.as_deref_mut(), damage);
+        damage_events.write(DamageDealtMessage {
+            amount: damage,
+            position: transform.translation().truncate(),
+            critical: false,
+            effect: source,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn effect(kind: StatusEffectKind, duration: f32, potency: f32) -> StatusEffect {
+        StatusEffect {
+            kind,
+            duration,
+            potency,
+            chance: 1.0,
+        }
+    }
+
+    #[test]
+    fn poison_stacks_up_to_the_cap_replacing_the_closest_to_expiry() {
+        let mut effects = StatusEffects::default();
+        for (duration, potency) in [(3.0, 1.0), (1.0, 2.0), (4.0, 4.0), (5.0, 8.0), (2.0, 16.0)] {
+            effects.apply(&effect(StatusEffectKind::Poison, duration, potency));
+        }
+        assert_eq!(effects.stacks(StatusEffectKind::Poison), MAX_POISON_STACKS);
+        assert_eq!(effects.damage_per_second(), 31.0);
+
+        // The 1s stack is the one replaced
+        effects.apply(&effect(StatusEffectKind::Poison, 6.0, 32.0));
+        assert_eq!(effects.stacks(StatusEffectKind::Poison), MAX_POISON_STACKS);
+        assert_eq!(effects.damage_per_second(), 61.0);
+    }
+
+    #[test]
+    fn reapplying_refreshes_and_keeps_the_strongest() {
+        let mut effects = StatusEffects::default();
+        effects.apply(&effect(StatusEffectKind::Burn, 2.0, 4.0));
+        effects.tick(Duration::from_millis(1500));
+        effects.apply(&effect(StatusEffectKind::Burn, 1.0, 2.0));
+        assert_eq!(effects.stacks(StatusEffectKind::Burn), 1);
+        assert_eq!(effects.damage_per_second(), 4.0);
+
+        // Refreshed to 1s from the second application
+        effects.tick(Duration::from_millis(900));
+        assert_eq!(effects.stacks(StatusEffectKind::Burn), 1);
+        effects.tick(Duration::from_millis(100));
+        assert_eq!(effects.stacks(StatusEffectKind::Burn), 0);
+
+        // A shorter application doesn't cut a longer one short
+        effects.apply(&effect(StatusEffectKind::Slow, 5.0, 0.3));
+        effects.apply(&effect(StatusEffectKind::Slow, 1.0, 0.6));
+        effects.tick(Duration::from_millis(2000));
+        assert_eq!(effects.stacks(StatusEffectKind::Slow), 1);
+        assert!((effects.speed_multiplier() - 0.4).abs() < 1e-6);
+    }
+
+    #[test]
+    fn damage_over_time_is_dealt_per_tick() {
+        let mut effects = StatusEffects::default();
+        effects.apply(&effect(StatusEffectKind::Burn, 3.0, 4.0));
+        effects.apply(&effect(StatusEffectKind::Poison, 3.0, 2.0));
+
+        assert_eq!(effects.tick(Duration::from_millis(250)), 0.0);
+        assert_eq!(effects.tick(Duration::from_millis(250)), DOT_TICK * 6.0);
+        // Two ticks in one long frame
+        assert_eq!(
+            effects.tick(Duration::from_millis(1000)),
+            2.0 * DOT_TICK * 6.0
+        );
+        // The last tick lands as the effects run out
+        assert_eq!(
+            effects.tick(Duration::from_millis(1500)),
+            3.0 * DOT_TICK * 6.0
+        );
+        assert_eq!(effects.tick(Duration::from_millis(1000)), 0.0);
+    }
+
+    #[test]
+    fn freeze_stops_movement_whatever_the_slow() {
+        let mut effects = StatusEffects::default();
+        effects.apply(&effect(StatusEffectKind::Slow, 2.0, 0.5));
+        assert!(!effects.is_frozen());
+        assert_eq!(effects.speed_multiplier(), 0.5);
+
+        effects.apply(&effect(StatusEffectKind::Freeze, 1.0, 0.0));
+        assert!(effects.is_frozen());
+        assert_eq!(effects.speed_multiplier(), 0.0);
+        assert_eq!(effects.tint(), StatusEffectKind::Freeze.tint());
+
+        effects.tick(Duration::from_millis(1000));
+        assert!(!effects.is_frozen());
+        assert_eq!(effects.speed_multiplier(), 0.5);
+    }
+}
@@ -1,29 +1,43 @@
 use crate::systems::animations::messages::AnimationEnded;
-use crate::systems::game::{GameOverStats, GameState, MarkedForDespawn};
+use crate::systems::game::{
+    GameOverStats, GameRng, GameState, MarkedForDespawn, MasterVolume, MusicVolume,
+};
 use crate::systems::states::waves::components::Action::DYING;
-use crate::systems::states::waves::components::{Action, BackgroundMusic, Health};
+use crate::systems::states::waves::components::{Action, BackgroundMusic, Dying, Health};
 use crate::systems::states::waves::enemy::components::Enemy;
-use crate::systems::states::waves::player::components::Player;
+use crate::systems::states::waves::player::components::{Player, PlayerStats};
+use crate::systems::states::waves::player::currency::Currency;
 use crate::systems::states::waves::player::experience::PlayerExperience;
 use crate::systems::states::waves::resources::WaveManager;
+use crate::systems::states::waves::script::WaveScripts;
 use bevy::audio::Volume;
 use bevy::prelude::*;
+use rand::RngExt;
+use std::time::Duration;
 
 const MUSIC_FADEOUT_START_SECS: f32 = 5.0;
 
-pub fn play_background_audio(asset_server: Res<AssetServer>, mut commands: Commands) {
-    let num: u8 = rand::random_range(1..3);
+pub fn play_background_audio(
+    asset_server: Res<AssetServer>,
+    master_volume: Res<MasterVolume>,
+    music_volume: Res<MusicVolume>,
+    mut rng: ResMut<GameRng>,
+    mut commands: Commands,
+) {
+    let num: u8 = rng.presentation().random_range(1..3);
     let audio1 = asset_server.load(format!("musics/music{num}.ogg"));
     commands.spawn((
         BackgroundMusic,
         DespawnOnExit(GameState::InWave),
         AudioPlayer::new(audio1),
-        PlaybackSettings::LOOP,
+        PlaybackSettings::LOOP.with_volume(master_volume.mix(music_volume.0)),
     ));
 }
 
 pub fn update_background_audio(
     wave_manager: Res<WaveManager>,
+    master_volume: Res<MasterVolume>,
+    music_volume: Res<MusicVolume>,
     mut audio_query: Query<&mut AudioSink, With<BackgroundMusic>>,
 ) {
     let remaining = wave_manager.wave_timer.remaining_secs();
@@ -37,12 +51,18 @@ pub fn update_background_audio(
 
     // t goes 1.0 → 0.0 as remaining goes from MUSIC_FADEOUT_START_SECS → 0
     let t = (remaining / MUSIC_FADEOUT_START_SECS).clamp(0.0, 1.0);
-    sink.set_volume(Volume::Linear(t));
+    sink.set_volume(master_volume.mix(music_volume.0) * Volume::Linear(t));
 }
 
-pub fn reset_wave_timers(mut wave_manager: ResMut<WaveManager>) {
+/// Starts the clock and the spawn budget of the wave from its `WaveScript`.
+pub fn reset_wave_timers(mut wave_manager: ResMut<WaveManager>, scripts: Res<WaveScripts>) {
+    let (script, _) = scripts.for_wave(wave_manager.wave);
+    let duration = Duration::from_secs_f32(script.duration.max(1.0));
+    wave_manager.wave_timer.set_duration(duration);
     wave_manager.wave_timer.reset();
-    wave_manager.enemy_spawn_timer.reset();
+    wave_manager.spawn_budget = 0.0;
+    wave_manager.next_spawn = None;
+    wave_manager.next_event = 0;
 }
 
 pub fn update_wave_timer(
@@ -55,18 +75,44 @@ pub fn update_wave_timer(
         next_state.set(GameState::UpgradeSelection);
     }
 }
+/// Pays the `WaveRewards` of the script when the wave timer runs out
+#[allow(clippy::type_complexity)]
+pub fn grant_wave_rewards(
+    wave_manager: Res<WaveManager>,
+    scripts: Res<WaveScripts>,
+    mut player_query: Query<
+        (&mut Currency, &mut PlayerExperience, &mut PlayerStats),
+        (With<Player>, Without<Dying>),
+    >,
+) {
+    if !wave_manager.wave_timer.just_finished() {
+        return;
+    }
+    let Ok((mut currency, mut experience, mut stats)) = player_query.single_mut() else {
+        return;
+    };
+    let (script, _) = scripts.for_wave(wave_manager.wave);
+    currency.0 += script.rewards.currency;
+    experience.gain(script.rewards.experience, &mut stats);
+}
+
 const Y_SORT_BASE: f32 = 25.0;
 const Y_SORT_SCALE: f32 = 0.01;
 
+/// Depth of a sprite standing at `y`, lower on screen is drawn in front
+pub fn y_sort_depth(y: f32) -> f32 {
+    Y_SORT_BASE - y * Y_SORT_SCALE
+}
+
 pub fn y_sort_enemies(mut query: Query<&mut Transform, With<Enemy>>) {
     for mut transform in &mut query {
-        transform.translation.z = Y_SORT_BASE - transform.translation.y * Y_SORT_SCALE;
+        transform.translation.z = y_sort_depth(transform.translation.y);
     }
 }
 
 pub fn y_sort_player(mut query: Query<&mut Transform, With<Player>>) {
     for mut transform in &mut query {
-        transform.translation.z = Y_SORT_BASE - transform.translation.y * Y_SORT_SCALE;
+        transform.translation.z = y_sort_depth(transform.translation.y);
     }
 }
 
@@ -99,9 +145,7 @@ pub fn check_game_is_over(
     // Once the non-repeating death clip finishes, AnimationEnded fires.
     for ev in anim_ended_reader.read() {
         if ev.entity == player_entity {
-            game_over_stats.wave_reached = wave_manager.wave;
-            game_over_stats.level_reached = xp.level;
-            game_over_stats.experience_total = xp.value;
+            game_over_stats.end_run(wave_manager.wave, xp);
             next_state.set(GameState::GameOver);
         }
     }
@@ -1,13 +1,14 @@
+use crate::systems::states::waves::player::components::PlayerStats;
+use crate::systems::states::waves::status::StatusEffect;
+use crate::systems::states::waves::weapons::kinds::WeaponKind;
 use bevy::math::Vec2;
-use bevy::prelude::{Bundle, Component, Name, Timer};
+use bevy::prelude::{Bundle, Component, Entity, Name, Timer};
 use bevy::time::TimerMode::Repeating;
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
-pub enum WeaponKind {
-    MachineGun,
-    Pistol,
-    Shotgun,
-}
+/// Spread (radians) added by each extra projectile of a weapon that fires straight.
+const EXTRA_PROJECTILE_SPREAD: f32 = 0.15;
+/// Projectiles fly a bit past the targeting range, so they still reach enemies at its edge.
+const PROJECTILE_RANGE_FACTOR: f32 = 1.2;
 
 #[derive(Component, Clone, Debug)]
 pub struct Weapon {
@@ -15,12 +16,75 @@ pub struct Weapon {
     pub base_cooldown: f32,
     pub base_range: f32,
     pub kind: WeaponKind,
+    pub projectile_count: u32,
+    /// Total spread of a shot in radians
+    pub spread_angle: f32,
+    pub projectile_speed: f32,
+    pub projectile_acceleration: f32,
+    pub projectile_lifetime: Option<f32>,
+    pub damage_falloff: f32,
+    pub pierce: u32,
+    pub bounce: u32,
+    pub chain: u32,
     pub bullet_size: Vec2,
     pub weapon_size: Vec2,
+    /// Effects each bullet can apply to the enemies it hits
+    pub on_hit: Vec<StatusEffect>,
     // Calculated from PlayerStats:
     pub damage_multiplier: f32,
     pub fire_rate_multiplier: f32,
     pub range_multiplier: f32,
+    pub extra_projectiles: u32,
+    pub crit_chance: f32,
+    pub crit_multiplier: f32,
+    pub extra_pierce: u32,
+    pub extra_bounces: u32,
+    pub extra_chains: u32,
+}
+
+impl Weapon {
+    /// Copies the player multipliers onto the weapon
+    pub fn apply_stats(&mut self, stats: &PlayerStats) {
+        self.damage_multiplier = stats.damage_multiplier;
+        self.fire_rate_multiplier = stats.fire_rate_multiplier;
+        self.range_multiplier = stats.range_multiplier;
+        self.extra_projectiles = stats.extra_projectiles;
+        self.crit_chance = stats.crit_chance;
+        self.crit_multiplier = stats.crit_multiplier;
+        self.extra_pierce = stats.extra_pierce;
+        self.extra_bounces = stats.extra_bounces;
+        self.extra_chains = stats.extra_chains;
+    }
+
+    /// Projectiles fired per shot, extra projectiles included
+    pub fn projectiles(&self) -> u32 {
+        self.projectile_count.max(1) + self.extra_projectiles
+    }
+
+    /// Total spread of a shot in radians. Weapons firing straight still fan their extra
+    /// projectiles out a little, so they don't all fly on top of each other.
+    pub fn spread(&self) -> f32 {
+        if self.spread_angle > 0.0 {
+            self.spread_angle
+        } else {
+            EXTRA_PROJECTILE_SPREAD * self.extra_projectiles as f32
+        }
+    }
+
+    /// Targeting range (px) once the range multiplier is applied
+    pub fn range(&self) -> f32 {
+        self.base_range * self.range_multiplier
+    }
+
+    /// Distance (px) a projectile travels before disappearing
+    pub fn projectile_range(&self) -> f32 {
+        self.range() * PROJECTILE_RANGE_FACTOR
+    }
+
+    /// Seconds between two shots once the fire rate is applied
+    pub fn cooldown(&self) -> f32 {
+        self.base_cooldown / self.fire_rate_multiplier
+    }
 }
 
 #[derive(Component, Clone)]
@@ -50,9 +114,88 @@ impl WeaponArea {
 pub struct Bullet {
     pub direction: Vec2,
     pub damage: f32,
-    pub kind: WeaponKind,
+    pub speed: f32,
+    /// Pixels per second added to `speed` every second
+    pub acceleration: f32,
+    /// Pixels covered so far, ricochets included
+    pub traveled: f32,
+    /// Pixels the bullet can cover before disappearing, `None` flies until it leaves the area
+    pub max_distance: Option<f32>,
+    pub lifetime: Option<Timer>,
+    /// Share (0..1) of `damage` lost by the time `traveled` reaches `max_distance`
+    pub falloff: f32,
+    /// Odds (0..1) of each hit dealing `crit_multiplier` times the damage
+    pub crit_chance: f32,
+    pub crit_multiplier: f32,
+    /// Rolled separately on every enemy hit
+    pub effects: Vec<StatusEffect>,
+    /// Remaining enemies this bullet can pass through
+    pub pierce: u32,
+    /// Remaining ricochets toward the next nearest enemy, used up once pierce runs out
+    pub bounces: u32,
+    /// Enemies a chain lightning jumps to from every hit
+    pub chains: u32,
+    /// Enemies already damaged, by the bullet or its chain lightning, so each one is hit only once
+    pub hits: Vec<Entity>,
 }
 
+impl Bullet {
+    pub fn new(direction: Vec2, damage: f32, speed: f32) -> Self {
+        Self {
+            direction,
+            damage,
+            speed,
+            acceleration: 0.0,
+            traveled: 0.0,
+            max_distance: None,
+            lifetime: None,
+            falloff: 0.0,
+            crit_chance: 0.0,
+            crit_multiplier: 1.0,
+            effects: Vec::new(),
+            pierce: 0,
+            bounces: 0,
+            chains: 0,
+            hits: Vec::new(),
+        }
+    }
+
+    /// Damage dealt by a hit right now, once the falloff over the distance traveled is applied
+    pub fn current_damage(&self) -> f32 {
+        match self.max_distance {
+            Some(max) if self.falloff > 0.0 && max > 0.0 => {
+                self.damage * (1.0 - self.falloff * (self.traveled / max).min(1.0))
+            }
+            _ => self.damage,
+        }
+    }
+
+    /// Out of range, out of time or stopped by a negative acceleration
+    pub fn is_spent(&self) -> bool {
+        self.max_distance.is_some_and(|max| self.traveled >= max)
+            || self.lifetime.as_ref().is_some_and(Timer::is_finished)
+            || self.speed <= 0.0
+    }
+}
+
+/// Floating number shown where a hit landed, fades out once `lifetime` ends
+#[derive(Component)]
+pub struct DamageNumber {
+    pub lifetime: Timer,
+    /// Pixels per second
+    pub velocity: Vec2,
+}
+
+/// Bolt drawn between two enemies struck by a chain lightning, fades out with `lifetime`
+#[derive(Component)]
+pub struct ChainArc {
+    pub lifetime: Timer,
+}
+
+/// Inactive bullet parked in the `BulletPool`, hidden until it is fired again
+#[derive(Component)]
+pub struct Pooled;
+
 #[derive(Bundle, Clone)]
 pub struct WeaponBundle {
     pub name: Name,
@@ -0,0 +1,105 @@
+use crate::systems::constants::tiles_to_pixels;
+use crate::systems::states::waves::status::StatusEffect;
+use crate::systems::states::waves::weapons::components::Weapon;
+use bevy::asset::Asset;
+use bevy::color::Color;
+use bevy::math::Vec2;
+use bevy::reflect::TypePath;
+use serde::Deserialize;
+
+/// Index of a `WeaponDefinition` inside the `WeaponsLibrary`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WeaponKind(pub usize);
+
+/// One weapon type, loaded from `assets/weapons/<id>.weapon.ron`.
+#[derive(Asset, TypePath, Deserialize, Debug, Clone)]
+pub struct WeaponDefinition {
+    pub id: String,
+    /// Damage of each projectile
+    pub damage: f32,
+    /// Seconds between two shots
+    pub cooldown: f32,
+    /// Targeting range in tiles
+    pub range: f32,
+    /// Projectiles fired per shot, fanned out over `spread_angle`
+    #[serde(default = "default_projectile_count")]
+    pub projectile_count: u32,
+    /// Total spread of a shot in degrees
+    #[serde(default)]
+    pub spread_angle: f32,
+    /// Pixels per second
+    pub projectile_speed: f32,
+    /// Pixels per second added to the speed every second, negative slows projectiles down
+    #[serde(default)]
+    pub projectile_acceleration: f32,
+    /// Seconds before a projectile disappears, `None` only limits it by range
+    #[serde(default)]
+    pub projectile_lifetime: Option<f32>,
+    /// Share (0..1) of the damage lost by the time a projectile reaches its max range
+    #[serde(default)]
+    pub damage_falloff: f32,
+    /// Extra enemies a projectile passes through before being destroyed
+    #[serde(default)]
+    pub pierce: u32,
+    /// Times a projectile ricochets toward the next nearest enemy once it can't pierce anymore
+    #[serde(default)]
+    pub bounce: u32,
+    /// Enemies a chain lightning jumps to from every enemy hit
+    #[serde(default)]
+    pub chain: u32,
+    pub bullet_size: f32,
+    pub weapon_size: (f32, f32),
+    /// sRGB components in 0..1, used for the bullets and for weapons without a sprite
+    pub color: (f32, f32, f32),
+    /// Image drawn for the weapon, `None` draws a `weapon_size` rectangle of `color`
+    #[serde(default)]
+    pub sprite: Option<String>,
+    pub fire_sound: String,
+    /// Base cost in the shop, before the per-wave markup
+    pub price: u32,
+    /// Status effects the projectiles can apply, e.g. `[(kind: Burn, duration: 2.0, potency: 4.0)]`
+    #[serde(default)]
+    pub on_hit: Vec<StatusEffect>,
+}
+
+fn default_projectile_count() -> u32 {
+    1
+}
+
+impl WeaponDefinition {
+    pub fn color(&self) -> Color {
+        let (r, g, b) = self.color;
+        Color::srgb(r, g, b)
+    }
+
+    /// A fresh weapon component with neutral stat multipliers
+    pub fn weapon(&self, kind: WeaponKind) -> Weapon {
+        Weapon {
+            kind,
+            base_damage: self.damage,
+            base_cooldown: self.cooldown,
+            base_range: tiles_to_pixels(self.range),
+            projectile_count: self.projectile_count.max(1),
+            spread_angle: self.spread_angle.to_radians(),
+            projectile_speed: self.projectile_speed,
+            projectile_acceleration: self.projectile_acceleration,
+            projectile_lifetime: self.projectile_lifetime,
+            damage_falloff: self.damage_falloff.clamp(0.0, 1.0),
+            pierce: self.pierce,
+            bounce: self.bounce,
+            chain: self.chain,
+            bullet_size: Vec2::splat(self.bullet_size),
+            weapon_size: Vec2::new(self.weapon_size.0, self.weapon_size.1),
+            on_hit: self.on_hit.clone(),
+            damage_multiplier: 1.0,
+            fire_rate_multiplier: 1.0,
+            range_multiplier: 1.0,
+            extra_projectiles: 0,
+            crit_chance: 0.0,
+            crit_multiplier: 1.0,
+            extra_pierce: 0,
+            extra_bounces: 0,
+            extra_chains: 0,
+        }
+    }
+}
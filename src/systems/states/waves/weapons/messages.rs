@@ -1,5 +1,7 @@
-use crate::systems::states::waves::weapons::components::{Bullet, Weapon};
-use bevy::prelude::{Entity, Message, Name, Transform};
+use crate::systems::states::waves::status::StatusEffectKind;
+use crate::systems::states::waves::weapons::components::Weapon;
+use crate::systems::states::waves::weapons::kinds::WeaponKind;
+use bevy::prelude::{Entity, Message, Name, Transform, Vec2};
 
 #[derive(Message, Debug)]
 pub struct WeaponSpawnedMessage {
@@ -12,6 +14,26 @@ pub struct WeaponSpawnedMessage {
 #[derive(Message, Debug)]
 pub struct BulletSpawnedMessage {
     pub entity: Entity,
-    pub bullet: Bullet,
     pub transform: Transform,
+    pub weapon: WeaponKind,
+    /// Only the first bullet of a multi-projectile shot plays the fire sound
+    pub play_sound: bool,
+}
+
+/// Written every time an enemy takes damage from the player, bullet hit or damage over time
+#[derive(Message, Debug)]
+pub struct DamageDealtMessage {
+    pub amount: f32,
+    /// World position of the hit
+    pub position: Vec2,
+    pub critical: bool,
+    /// Damage-over-time effect the damage comes from, `None` for a hit
+    pub effect: Option<StatusEffectKind>,
+}
+
+/// Written for every jump of a chain lightning, from the enemy it leaves to the one it strikes
+#[derive(Message, Debug)]
+pub struct ChainLightningMessage {
+    pub from: Vec2,
+    pub to: Vec2,
 }
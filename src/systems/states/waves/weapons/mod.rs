@@ -1,4 +1,5 @@
 pub mod components;
+pub mod kinds;
 pub mod messages;
 pub mod renderer;
 pub mod resources;
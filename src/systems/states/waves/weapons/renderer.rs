@@ -1,53 +1,153 @@
-use crate::systems::states::waves::weapons::components::WeaponKind::{MachineGun, Pistol, Shotgun};
+use crate::systems::game::{GameRng, GameState, MasterVolume, SoundEffectVolume};
+use crate::systems::states::waves::weapons::components::{ChainArc, DamageNumber};
 use crate::systems::states::waves::weapons::messages::{
-    BulletSpawnedMessage, WeaponSpawnedMessage,
+    BulletSpawnedMessage, ChainLightningMessage, DamageDealtMessage, WeaponSpawnedMessage,
 };
-use bevy::color::palettes::basic::{AQUA, RED};
-use bevy::color::palettes::css::BLUE;
+use crate::systems::states::waves::weapons::resources::WeaponsLibrary;
 use bevy::prelude::*;
+use rand::RngExt;
+
+const DAMAGE_NUMBER_LIFETIME: f32 = 0.6;
+const DAMAGE_NUMBER_RISE: f32 = 40.0;
+const DAMAGE_NUMBER_SIZE: f32 = 14.0;
+const CRIT_NUMBER_SIZE: f32 = 22.0;
+const DAMAGE_NUMBER_COLOR: Color = Color::srgb(1.0, 1.0, 1.0);
+const CRIT_NUMBER_COLOR: Color = Color::srgb(1.0, 0.75, 0.1);
+/// Above every y-sorted sprite
+const DAMAGE_NUMBER_Z: f32 = 100.0;
+const CHAIN_ARC_LIFETIME: f32 = 0.2;
+const CHAIN_ARC_WIDTH: f32 = 2.0;
+const CHAIN_ARC_COLOR: Color = Color::srgb(0.6, 0.8, 1.0);
+
 pub fn render_bullet(
     mut commands: Commands,
     mut events: MessageReader<BulletSpawnedMessage>,
     assets: Res<AssetServer>,
+    library: Res<WeaponsLibrary>,
+    master_volume: Res<MasterVolume>,
+    sound_effect_volume: Res<SoundEffectVolume>,
 ) {
     for event in events.read() {
-        let (sprite, sound) = {
-            match event.bullet.kind {
-                Shotgun => (
-                    Sprite::from_color(RED, event.transform.scale.truncate()),
-                    assets.load("effects/bullet1.ogg"),
-                ),
-                Pistol => (
-                    Sprite::from_color(BLUE, event.transform.scale.truncate()),
-                    assets.load("effects/bullet2.ogg"),
-                ),
-                MachineGun => (
-                    Sprite::from_color(AQUA, event.transform.scale.truncate()),
-                    assets.load("effects/bullet3.ogg"),
-                ),
-            }
-        };
-        commands.entity(event.entity).insert((
-            sprite,
-            AudioPlayer::new(sound),
-            PlaybackSettings::ONCE,
-        ));
+        let definition = library.get(event.weapon);
+        let sprite = Sprite::from_color(definition.color(), event.transform.scale.truncate());
+        let mut entity_cmd = commands.entity(event.entity);
+        entity_cmd.insert(sprite);
+        if event.play_sound {
+            entity_cmd.insert((
+                AudioPlayer::new(assets.load(definition.fire_sound.clone())),
+                PlaybackSettings::ONCE.with_volume(master_volume.mix(sound_effect_volume.0)),
+            ));
+        }
     }
 }
 
 pub fn render_weapon(
     mut commands: Commands,
     assets: ResMut<AssetServer>,
+    library: Res<WeaponsLibrary>,
     mut events: MessageReader<WeaponSpawnedMessage>,
 ) {
     for event in events.read() {
         debug!("Weapon {:?} spawned: {:?}", event.name, event);
+        let definition = library.get(event.weapon.kind);
         commands
             .entity(event.entity)
-            .insert((match event.weapon.kind {
-                Shotgun => Sprite::from_color(RED, event.weapon.weapon_size),
-                Pistol => Sprite::from_color(BLUE, event.weapon.weapon_size),
-                MachineGun => Sprite::from_image(assets.load("sprites/wand.png")),
+            .insert((match &definition.sprite {
+                Some(path) => Sprite::from_image(assets.load(path.clone())),
+                None => Sprite::from_color(definition.color(), event.weapon.weapon_size),
             },));
     }
 }
+
+pub fn spawn_damage_numbers(
+    mut commands: Commands,
+    mut events: MessageReader<DamageDealtMessage>,
+    mut rng: ResMut<GameRng>,
+) {
+    let rng = rng.presentation();
+    for event in events.read() {
+        let (text, size, color) = if let Some(effect) = event.effect {
+            (
+                format!("{:.0}", event.amount),
+                DAMAGE_NUMBER_SIZE,
+                effect.tint(),
+            )
+        } else if event.critical {
+            (
+                format!("{:.0}!", event.amount),
+                CRIT_NUMBER_SIZE,
+                CRIT_NUMBER_COLOR,
+            )
+        } else {
+            (
+                format!("{:.0}", event.amount),
+                DAMAGE_NUMBER_SIZE,
+                DAMAGE_NUMBER_COLOR,
+            )
+        };
+        // A little sideways drift keeps numbers of consecutive hits from stacking up
+        let drift = rng.random_range(-15.0..15.0);
+        commands.spawn((
+            DamageNumber {
+                lifetime: Timer::from_seconds(DAMAGE_NUMBER_LIFETIME, TimerMode::Once),
+                velocity: Vec2::new(drift, DAMAGE_NUMBER_RISE),
+            },
+            DespawnOnExit(GameState::InWave),
+            Text2d::new(text),
+            TextFont {
+                font_size: size,
+                ..default()
+            },
+            TextColor(color),
+            Transform::from_translation(event.position.extend(DAMAGE_NUMBER_Z)),
+        ));
+    }
+}
+
+/// Numbers float up and fade out, then despawn.
+pub fn animate_damage_numbers(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut DamageNumber, &mut Transform, &mut TextColor)>,
+    time: Res<Time>,
+) {
+    for (entity, mut number, mut transform, mut color) in &mut query {
+        number.lifetime.tick(time.delta());
+        if number.lifetime.is_finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        transform.translation += (number.velocity * time.delta_secs()).extend(0.0);
+        color.0.set_alpha(number.lifetime.fraction_remaining());
+    }
+}
+
+pub fn spawn_chain_arcs(mut commands: Commands, mut events: MessageReader<ChainLightningMessage>) {
+    for event in events.read() {
+        let delta = event.to - event.from;
+        let center = (event.from + event.to) / 2.0;
+        commands.spawn((
+            ChainArc {
+                lifetime: Timer::from_seconds(CHAIN_ARC_LIFETIME, TimerMode::Once),
+            },
+            DespawnOnExit(GameState::InWave),
+            Sprite::from_color(CHAIN_ARC_COLOR, Vec2::new(delta.length(), CHAIN_ARC_WIDTH)),
+            Transform::from_translation(center.extend(DAMAGE_NUMBER_Z))
+                .with_rotation(Quat::from_rotation_z(delta.to_angle())),
+        ));
+    }
+}
+
+pub fn animate_chain_arcs(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut ChainArc, &mut Sprite)>,
+    time: Res<Time>,
+) {
+    for (entity, mut arc, mut sprite) in &mut query {
+        arc.lifetime.tick(time.delta());
+        if arc.lifetime.is_finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        sprite.color.set_alpha(arc.lifetime.fraction_remaining());
+    }
+}
@@ -1,54 +1,165 @@
-use crate::systems::constants::tiles_to_pixels;
-use crate::systems::states::waves::weapons::components::Weapon;
-use crate::systems::states::waves::weapons::components::WeaponKind::{MachineGun, Pistol, Shotgun};
-use bevy::math::Vec2;
-use bevy::prelude::Resource;
+use crate::systems::game::GameState;
+use crate::systems::states::waves::weapons::components::{Pooled, Weapon};
+use crate::systems::states::waves::weapons::kinds::{WeaponDefinition, WeaponKind};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
+/// How weapons pick their direction, chosen in the settings menu.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AimMode {
+    /// Each weapon targets the nearest enemy in range
+    #[default]
+    Auto,
+    /// Weapons follow `ActionState::aim` (right stick or mouse cursor) and only fire while aiming
+    Manual,
+}
+
+impl AimMode {
+    pub fn next(self) -> Self {
+        match self {
+            AimMode::Auto => AimMode::Manual,
+            AimMode::Manual => AimMode::Auto,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AimMode::Auto => "Auto",
+            AimMode::Manual => "Manual",
+        }
+    }
+}
+
+/// `id` of the weapon every run starts with
+pub const STARTING_WEAPON: &str = "machine_gun";
+
+/// Every loaded `WeaponDefinition`, sorted by id so that `WeaponKind` indices (and therefore
+/// seeded weapon rolls) don't depend on the order files were read from disk.
 #[derive(Resource)]
 pub struct WeaponsLibrary {
-    pub weapons: Vec<Weapon>,
-}
-
-impl WeaponsLibrary {}
-
-impl Default for WeaponsLibrary {
-    fn default() -> Self {
-        WeaponsLibrary {
-            weapons: vec![
-                Weapon {
-                    kind: MachineGun,
-                    base_cooldown: 0.30,
-                    base_damage: 5.0,
-                    base_range: tiles_to_pixels(10.0),
-                    damage_multiplier: 1.0,
-                    fire_rate_multiplier: 1.0,
-                    range_multiplier: 1.0,
-                    bullet_size: Vec2::splat(2.),
-                    weapon_size: Vec2::new(1., 1.),
-                },
-                Weapon {
-                    base_cooldown: 0.75,
-                    base_damage: 7.0,
-                    base_range: tiles_to_pixels(12.0),
-                    kind: Pistol,
-                    damage_multiplier: 1.0,
-                    fire_rate_multiplier: 1.0,
-                    range_multiplier: 1.0,
-                    bullet_size: Vec2::splat(3.),
-                    weapon_size: Vec2::new(2., 5.),
-                },
-                Weapon {
-                    base_cooldown: 1.20,
-                    base_damage: 18.0,
-                    base_range: tiles_to_pixels(8.0),
-                    kind: Shotgun,
-                    damage_multiplier: 1.0,
-                    fire_rate_multiplier: 1.0,
-                    range_multiplier: 1.0,
-                    bullet_size: Vec2::splat(4.),
-                    weapon_size: Vec2::new(2., 5.),
-                },
-            ],
+    definitions: Vec<WeaponDefinition>,
+}
+
+impl WeaponsLibrary {
+    pub fn new(mut definitions: Vec<WeaponDefinition>) -> Self {
+        definitions.sort_by(|a, b| a.id.cmp(&b.id));
+        Self { definitions }
+    }
+
+    /// Library of the bundled `assets/weapons/<id>.weapon.ron` files, for tests.
+    #[cfg(test)]
+    pub fn from_assets(ids: &[&str]) -> Self {
+        let definitions = ids
+            .iter()
+            .map(|id| {
+                let path = format!(
+                    "{}/assets/weapons/{}.weapon.ron",
+                    env!("CARGO_MANIFEST_DIR"),
+                    id
+                );
+                ron::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+            })
+            .collect();
+        Self::new(definitions)
+    }
+
+    pub fn get(&self, kind: WeaponKind) -> &WeaponDefinition {
+        &self.definitions[kind.0]
+    }
+
+    pub fn find(&self, id: &str) -> Option<WeaponKind> {
+        self.definitions
+            .iter()
+            .position(|definition| definition.id == id)
+            .map(WeaponKind)
+    }
+
+    pub fn len(&self) -> usize {
+        self.definitions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.definitions.is_empty()
+    }
+
+    pub fn weapon(&self, kind: WeaponKind) -> Weapon {
+        self.get(kind).weapon(kind)
+    }
+
+    pub fn starting_weapon(&self) -> Option<WeaponKind> {
+        self.find(STARTING_WEAPON)
+            .or_else(|| (!self.is_empty()).then_some(WeaponKind(0)))
+    }
+}
+
+type ProjectileStyle = (Handle<Mesh>, Handle<ColorMaterial>);
+
+/// Mesh and material shared by every projectile with the same radius and color,
+/// so firing never adds new assets.
+#[derive(Resource, Default)]
+pub struct ProjectileAssets {
+    styles: HashMap<(u32, [u8; 4]), ProjectileStyle>,
+}
+
+impl ProjectileAssets {
+    pub fn get(
+        &mut self,
+        radius: f32,
+        color: Color,
+        meshes: &mut Assets<Mesh>,
+        materials: &mut Assets<ColorMaterial>,
+    ) -> (Mesh2d, MeshMaterial2d<ColorMaterial>) {
+        let key = (radius.to_bits(), color.to_srgba().to_u8_array());
+        let (mesh, material) = self
+            .styles
+            .entry(key)
+            .or_insert_with(|| (meshes.add(Circle::new(radius)), materials.add(color)));
+        (Mesh2d(mesh.clone()), MeshMaterial2d(material.clone()))
+    }
+}
+
+/// Bullet entities parked by `recycle_bullets`, handed out again before spawning new ones.
+/// Pooled entities still carry `DespawnOnExit(GameState::InWave)`, so the pool is emptied
+/// whenever the wave ends.
+#[derive(Resource, Default)]
+pub struct BulletPool {
+    free: Vec<Entity>,
+}
+
+impl BulletPool {
+    pub fn spawn(
+        &mut self,
+        commands: &mut Commands,
+        transform: Transform,
+        bundle: impl Bundle,
+    ) -> Entity {
+        // Set the global transform right away so collisions don't see the previous position
+        let components = (
+            transform,
+            GlobalTransform::from(transform),
+            Visibility::Inherited,
+            bundle,
+        );
+        match self.free.pop() {
+            Some(entity) => {
+                commands
+                    .entity(entity)
+                    .remove::<Pooled>()
+                    .insert(components);
+                entity
+            }
+            None => commands
+                .spawn((components, DespawnOnExit(GameState::InWave)))
+                .id(),
         }
     }
+
+    pub fn release(&mut self, entity: Entity) {
+        self.free.push(entity);
+    }
+
+    pub fn clear(&mut self) {
+        self.free.clear();
+    }
 }
@@ -1,59 +1,88 @@
-use crate::systems::constants::BULLET_SPEED;
-use crate::systems::game::GameState;
+use crate::systems::game::{GameRng, GameState, MarkedForDespawn};
 use crate::systems::input::resources::ActionState;
 use crate::systems::states::waves::components::Dying;
-use crate::systems::states::waves::enemy::components::Enemy;
-use crate::systems::states::waves::player::components::Player;
+use crate::systems::states::waves::player::components::{Player, PlayerStats};
+use crate::systems::states::waves::spatial::SpatialGrid;
 use crate::systems::states::waves::weapons::components::{
-    Bullet, Weapon, WeaponArea, WeaponBundle, WeaponCooldown,
+    Bullet, Pooled, Weapon, WeaponArea, WeaponBundle, WeaponCooldown,
 };
+use crate::systems::states::waves::weapons::kinds::WeaponKind;
 use crate::systems::states::waves::weapons::messages::{
     BulletSpawnedMessage, WeaponSpawnedMessage,
 };
-use crate::systems::states::waves::weapons::resources::WeaponsLibrary;
+use crate::systems::states::waves::weapons::resources::{AimMode, BulletPool, WeaponsLibrary};
 use crate::systems::states::waves::weapons::utils;
 use bevy::math::{Quat, Vec3};
 use bevy::prelude::*;
+use rand::RngExt;
 use std::f32::consts;
 
 pub fn add_weapon(
     mut commands: Commands,
     mut actions: ResMut<ActionState>,
-    player: Single<(Entity, &Children), With<Player>>,
+    player: Single<(Entity, &Children, &PlayerStats), With<Player>>,
     weapons_query: Query<&Weapon>,
     weapons_resource: Res<WeaponsLibrary>,
     mut events: MessageWriter<WeaponSpawnedMessage>,
+    mut rng: ResMut<GameRng>,
 ) {
     if !actions.add_weapon {
         return;
     }
     actions.add_weapon = false;
 
-    let index = rand::random_range(0..weapons_resource.weapons.len());
-    let weapon = weapons_resource.weapons.get(index).unwrap();
-    let (entity, children) = player.into_inner();
+    if weapons_resource.is_empty() {
+        return;
+    }
+    let kind = WeaponKind(rng.random_range(0..weapons_resource.len()));
+    let (entity, children, stats) = player.into_inner();
     let weapons_count = weapons_query.iter_many(children).count();
+    let message = equip_weapon(
+        &mut commands,
+        &weapons_resource,
+        kind,
+        entity,
+        stats,
+        weapons_count,
+    );
+    debug!("adding new weapon {:?}", message.name);
+    events.write(message);
+}
+
+/// Spawns a `kind` weapon as a child of `player`, already scaled by the player stats.
+/// `index` is the number of weapons the player carries so far, it only makes the name unique.
+pub fn equip_weapon(
+    commands: &mut Commands,
+    library: &WeaponsLibrary,
+    kind: WeaponKind,
+    player: Entity,
+    stats: &PlayerStats,
+    index: usize,
+) -> WeaponSpawnedMessage {
+    let mut weapon = library.weapon(kind);
+    weapon.apply_stats(stats);
     let weapon_bundle = WeaponBundle::new(
-        format!("{:?}-{}", weapon.kind, weapons_count),
+        format!("{}-{}", library.get(kind).id, index),
         weapon.clone(),
-        weapon.base_cooldown,
+        weapon.cooldown(),
     );
-    println!("adding new weapon {:?}", weapon_bundle.name);
-
     let weapon_entity = commands.spawn(weapon_bundle.clone()).id();
-    commands.entity(entity).add_child(weapon_entity);
-    events.write(WeaponSpawnedMessage {
+    commands.entity(player).add_child(weapon_entity);
+    WeaponSpawnedMessage {
         name: weapon_bundle.name,
         weapon: weapon_bundle.weapon,
         entity: weapon_entity,
-        player: entity,
-    });
+        player,
+    }
 }
 
-/// Smoothly moves and rotates weapons within their designated sectors to aim at nearest enemy
+/// Smoothly moves and rotates weapons within their designated sectors to aim at nearest enemy,
+/// or along the manual aim direction
 pub fn update_weapon_positioning(
     mut weapon_query: Query<(&mut Transform, &Weapon, &WeaponArea)>,
-    enemy_query: Query<&GlobalTransform, With<Enemy>>,
+    grid: Res<SpatialGrid>,
+    aim_mode: Res<AimMode>,
+    actions: Res<ActionState>,
     player_query: Query<&GlobalTransform, (With<Player>, Without<Dying>)>,
     time: Res<Time>,
 ) {
@@ -64,19 +93,18 @@ pub fn update_weapon_positioning(
     let player_pos = player_transform.translation();
 
     for (mut weapon_transform, weapon, weapon_area) in &mut weapon_query {
-        // Find nearest enemy within weapon range
-        let nearest_enemy = utils::get_nearest_enemy(
-            player_transform,
-            enemy_query.iter().collect(),
-            weapon.base_range * weapon.range_multiplier,
-        );
-
-        let Some(enemy_pos) = nearest_enemy else {
-            continue;
+        let range = weapon.range();
+        // Calculate direction to the target relative to player
+        let player_to_enemy = match *aim_mode {
+            // Find nearest enemy within weapon range
+            AimMode::Auto => match grid.nearest(player_pos.truncate(), range) {
+                Some((_, enemy_pos)) => enemy_pos - player_pos.truncate(),
+                None => continue,
+            },
+            // Aim at a point at the edge of the range, so weapons end up nearly parallel
+            AimMode::Manual if actions.aim != Vec2::ZERO => actions.aim * range,
+            AimMode::Manual => continue,
         };
-
-        // Calculate direction to enemy relative to player
-        let player_to_enemy = (enemy_pos - player_pos).truncate();
         let enemy_angle = player_to_enemy.y.atan2(player_to_enemy.x);
 
         // Clamp angle to weapon's allowed sector
@@ -104,17 +132,35 @@ pub fn update_weapon_positioning(
     }
 }
 
-pub fn move_bullets(mut bullet_query: Query<(&mut Transform, &Bullet)>, time: Res<Time>) {
-    for (mut transform, bullet) in &mut bullet_query {
-        transform.translation += bullet.direction.extend(0.0) * BULLET_SPEED * time.delta_secs();
+/// Moves bullets along their direction and retires the ones out of range or out of time
+pub fn move_bullets(
+    mut commands: Commands,
+    mut bullet_query: Query<(Entity, &mut Transform, &mut Bullet), Without<MarkedForDespawn>>,
+    time: Res<Time>,
+) {
+    for (entity, mut transform, mut bullet) in &mut bullet_query {
+        bullet.speed = (bullet.speed + bullet.acceleration * time.delta_secs()).max(0.0);
+        let step = bullet.speed * time.delta_secs();
+        transform.translation += bullet.direction.extend(0.0) * step;
+        bullet.traveled += step;
+        if let Some(lifetime) = &mut bullet.lifetime {
+            lifetime.tick(time.delta());
+        }
+        if bullet.is_spent() {
+            commands.entity(entity).insert(MarkedForDespawn);
+        }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn auto_shoot(
     mut commands: Commands,
     player_query: Query<&GlobalTransform, (With<Player>, Without<Dying>)>,
     weapons_query: Query<(&GlobalTransform, &mut Weapon, &mut WeaponCooldown)>,
-    enemy_query: Query<&GlobalTransform, (With<Enemy>, Without<Player>)>,
+    grid: Res<SpatialGrid>,
+    aim_mode: Res<AimMode>,
+    actions: Res<ActionState>,
+    mut pool: ResMut<BulletPool>,
     mut events: MessageWriter<BulletSpawnedMessage>,
     time: Res<Time>,
 ) {
@@ -129,40 +175,66 @@ pub fn auto_shoot(
         }
         let weapon_pos = weapon_transform.translation().truncate();
 
-        let Some(nearest_enemy) = utils::get_nearest_enemy(
-            weapon_transform,
-            enemy_query.iter().collect(),
-            weapon.base_range * weapon.range_multiplier,
-        ) else {
-            continue;
+        let aim = match *aim_mode {
+            AimMode::Auto => {
+                let Some((_, nearest_enemy)) = grid.nearest(weapon_pos, weapon.range()) else {
+                    continue;
+                };
+                // Compute direction to enemy
+                (nearest_enemy - weapon_pos).normalize()
+            }
+            // Manual aiming only fires while the player aims, whether an enemy is in range or not
+            AimMode::Manual if actions.aim != Vec2::ZERO => actions.aim,
+            AimMode::Manual => continue,
         };
 
-        // Compute direction to enemy
-        let direction = (nearest_enemy.truncate() - weapon_pos).normalize();
-        let spawn_offset = direction * 10.0; // push bullet forward by 20px
-
-        // Spawn a new bullet toward that direction
-        let transform = Transform::from_translation(weapon_pos.extend(1.0))
-            .with_translation(Vec3::new(
-                weapon_pos.x + spawn_offset.x,
-                weapon_pos.y + spawn_offset.y,
-                1.0,
-            ))
-            .with_scale(weapon.bullet_size.extend(1.0));
-        let bullet = Bullet {
-            direction,
-            damage: weapon.base_damage * weapon.damage_multiplier,
-            kind: weapon.kind,
-        };
-        let entity = commands
-            .spawn((transform, bullet.clone(), DespawnOnExit(GameState::InWave)))
-            .id();
+        // Fan the projectiles evenly over the spread, centered on the aim direction
+        let count = weapon.projectiles();
+        for i in 0..count {
+            let offset = if count > 1 {
+                weapon.spread() * (i as f32 / (count - 1) as f32 - 0.5)
+            } else {
+                0.0
+            };
+            let direction = Vec2::from_angle(offset).rotate(aim);
+            let spawn_offset = direction * 10.0; // push bullet forward by 20px
+
+            // Spawn a new bullet toward that direction
+            let transform = Transform::from_translation(weapon_pos.extend(1.0))
+                .with_translation(Vec3::new(
+                    weapon_pos.x + spawn_offset.x,
+                    weapon_pos.y + spawn_offset.y,
+                    1.0,
+                ))
+                .with_scale(weapon.bullet_size.extend(1.0));
+            let bullet = Bullet {
+                pierce: weapon.pierce + weapon.extra_pierce,
+                bounces: weapon.bounce + weapon.extra_bounces,
+                chains: weapon.chain + weapon.extra_chains,
+                crit_chance: weapon.crit_chance,
+                crit_multiplier: weapon.crit_multiplier,
+                effects: weapon.on_hit.clone(),
+                acceleration: weapon.projectile_acceleration,
+                max_distance: Some(weapon.projectile_range()),
+                lifetime: weapon
+                    .projectile_lifetime
+                    .map(|seconds| Timer::from_seconds(seconds, TimerMode::Once)),
+                falloff: weapon.damage_falloff,
+                ..Bullet::new(
+                    direction,
+                    weapon.base_damage * weapon.damage_multiplier,
+                    weapon.projectile_speed,
+                )
+            };
+            let entity = pool.spawn(&mut commands, transform, bullet);
 
-        events.write(BulletSpawnedMessage {
-            entity,
-            bullet,
-            transform,
-        });
+            events.write(BulletSpawnedMessage {
+                entity,
+                transform,
+                weapon: weapon.kind,
+                play_sound: i == 0,
+            });
+        }
 
         // reset cooldown
         cooldown.timer.reset();
@@ -182,7 +254,7 @@ pub fn recalculate_weapon_area(
 
         // For each weapons of player
         for (index, (entity, name)) in weapon_query.iter_many(children).enumerate() {
-            println!("Recalculating weapon area of weapon {:?}", name);
+            debug!("Recalculating weapon area of weapon {:?}", name);
             let orbit_radius = (10.0 * total_weapons as f32).max(20.0); // Distance from player center
             let center_arc = consts::TAU * (index as f32) / (total_weapons as f32);
             let angle = consts::TAU * (index as f32) / (total_weapons as f32);
@@ -198,3 +270,25 @@ pub fn recalculate_weapon_area(
         }
     }
 }
+
+/// Parks bullets marked for despawn in the `BulletPool` instead of despawning them.
+/// Everything but the transform and the state cleanup marker is stripped, so a reused
+/// bullet starts from a clean entity whatever it was before (player or hostile).
+pub fn recycle_bullets(
+    mut commands: Commands,
+    mut pool: ResMut<BulletPool>,
+    bullet_query: Query<Entity, (With<Bullet>, With<MarkedForDespawn>)>,
+) {
+    for entity in &bullet_query {
+        commands
+            .entity(entity)
+            .retain::<(Transform, GlobalTransform, DespawnOnExit<GameState>)>()
+            .insert((Pooled, Visibility::Hidden));
+        pool.release(entity);
+    }
+}
+
+/// Pooled bullets are despawned with the rest of the wave, forget about them
+pub fn clear_bullet_pool(mut pool: ResMut<BulletPool>) {
+    pool.clear();
+}
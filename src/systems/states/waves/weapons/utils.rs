@@ -52,6 +52,6 @@ pub fn get_nearest_enemy(
                 None
             }
         })
-        .min_by(|(_, dist1), (_, dist2)| dist1.partial_cmp(&dist2).unwrap())
+        .min_by(|(_, dist1), (_, dist2)| dist1.partial_cmp(dist2).unwrap())
         .map(|(pos, _)| pos)
 }
@@ -0,0 +1,33 @@
+use bevy_autoshooter_clone::systems::game::GameState;
+use bevy_autoshooter_clone::systems::gameplay::headless::HeadlessSimulation;
+
+#[test]
+fn wave_runs_without_a_window() {
+    let mut simulation = HeadlessSimulation::new(Some(1));
+    assert_eq!(simulation.state(), GameState::InWave);
+
+    let report = simulation.run_wave_for(10.0).report();
+
+    assert_eq!(report.final_state, GameState::InWave);
+    assert!(report.simulated_secs >= 10.0);
+    assert!(
+        report.wave_elapsed > 9.0,
+        "wave timer stuck at {}",
+        report.wave_elapsed
+    );
+    assert!(
+        report.enemies_alive + report.enemies_killed > 0,
+        "no enemy spawned"
+    );
+}
+
+#[test]
+fn same_seed_replays_the_same_wave() {
+    let first = HeadlessSimulation::new(Some(42))
+        .run_wave_for(20.0)
+        .report();
+    let second = HeadlessSimulation::new(Some(42))
+        .run_wave_for(20.0)
+        .report();
+    assert_eq!(first, second);
+}